@@ -77,18 +77,18 @@ impl TextSource {
         let mut line = self.line_index;
         // Zero-indexed character offset on the line
         let mut character = self.column_index;
-        let mut start_position = lsp_types::Position::default();
-        let mut end_position = lsp_types::Position::default();
+        let mut start_position = None;
+        let mut end_position = None;
         let mut chars = self.text.chars().peekable();
 
         let mut bytes_seen = 0;
 
         while let Some(chr) = chars.next() {
             if bytes_seen == start {
-                start_position = lsp_types::Position::new(line as u32, character as u32);
+                start_position = Some(lsp_types::Position::new(line as u32, character as u32));
             }
             if bytes_seen == end {
-                end_position = lsp_types::Position::new(line as u32, character as u32);
+                end_position = Some(lsp_types::Position::new(line as u32, character as u32));
                 break;
             }
 
@@ -105,13 +105,15 @@ impl TextSource {
             bytes_seen += chr.len_utf8();
         }
 
-        if start_position != lsp_types::Position::default()
-            && end_position == lsp_types::Position::default()
-        {
-            end_position = lsp_types::Position::new(line as u32, character as u32);
+        // A span ending at the end of the text, or past it, ends with the text
+        if start_position.is_some() && end_position.is_none() {
+            end_position = Some(lsp_types::Position::new(line as u32, character as u32));
         }
 
-        lsp_types::Range::new(start_position, end_position)
+        lsp_types::Range::new(
+            start_position.unwrap_or_default(),
+            end_position.unwrap_or_default(),
+        )
     }
 
     /// Ascending byte offset at which each line begins (line 0 starts at offset
@@ -169,6 +171,15 @@ mod test {
         assert_eq!(range.end, lsp_types::Position::new(0, 5));
     }
 
+    #[test]
+    fn to_range_whole_text_test() {
+        let span = Span::new(0, 9);
+        let text_source = TextSource::new("a {\n  b\n}", 0, 0);
+        let range = text_source.to_span_range(span);
+        assert_eq!(range.start, lsp_types::Position::new(0, 0));
+        assert_eq!(range.end, lsp_types::Position::new(2, 1));
+    }
+
     #[test]
     fn to_range_unicode_test() {
         let span = Span::new(0, 5);
@@ -338,45 +338,53 @@ pub fn get_ir_definition_references(
                 ExecutableDefinition::Operation(_) => OperationDefinitionName(name).into(),
                 ExecutableDefinition::Fragment(_) => FragmentDefinitionName(name).into(),
             };
-            let mut selections: Vec<_> = match definition {
+            let selections = match definition {
                 ExecutableDefinition::Operation(definition) => &definition.selections,
                 ExecutableDefinition::Fragment(definition) => &definition.selections,
+            };
+            (name, get_ir_selection_references(schema, selections))
+        })
+        .collect()
+}
+
+/// Get the fragments referenced by a list of selections, either by explicit
+/// fragment spreads or implicitly through the fragments of Relay Resolvers.
+pub fn get_ir_selection_references(
+    schema: &SDLSchema,
+    selections: &[Selection],
+) -> ExecutableDefinitionNameSet {
+    let mut selections: Vec<_> = selections.iter().collect();
+    let mut references: ExecutableDefinitionNameSet = Default::default();
+    while let Some(selection) = selections.pop() {
+        match selection {
+            Selection::FragmentSpread(selection) => {
+                references.insert(selection.fragment.item.into());
             }
-            .iter()
-            .collect();
-            let mut references: ExecutableDefinitionNameSet = Default::default();
-            while let Some(selection) = selections.pop() {
-                match selection {
-                    Selection::FragmentSpread(selection) => {
-                        references.insert(selection.fragment.item.into());
-                    }
-                    Selection::LinkedField(selection) => {
-                        for fragment_name in get_all_resolver_fragment_dependency_names(
-                            schema.field(selection.definition.item),
-                            schema,
-                        ) {
-                            references.insert(fragment_name.into());
-                        }
+            Selection::LinkedField(selection) => {
+                for fragment_name in get_all_resolver_fragment_dependency_names(
+                    schema.field(selection.definition.item),
+                    schema,
+                ) {
+                    references.insert(fragment_name.into());
+                }
 
-                        selections.extend(&selection.selections);
-                    }
-                    Selection::InlineFragment(selection) => {
-                        selections.extend(&selection.selections);
-                    }
-                    Selection::Condition(selection) => {
-                        selections.extend(&selection.selections);
-                    }
-                    Selection::ScalarField(selection) => {
-                        for fragment_name in get_all_resolver_fragment_dependency_names(
-                            schema.field(selection.definition.item),
-                            schema,
-                        ) {
-                            references.insert(fragment_name.into());
-                        }
-                    }
+                selections.extend(&selection.selections);
+            }
+            Selection::InlineFragment(selection) => {
+                selections.extend(&selection.selections);
+            }
+            Selection::Condition(selection) => {
+                selections.extend(&selection.selections);
+            }
+            Selection::ScalarField(selection) => {
+                for fragment_name in get_all_resolver_fragment_dependency_names(
+                    schema.field(selection.definition.item),
+                    schema,
+                ) {
+                    references.insert(fragment_name.into());
                 }
             }
-            (name, references)
-        })
-        .collect()
+        }
+    }
+    references
 }
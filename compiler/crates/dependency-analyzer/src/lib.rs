@@ -23,6 +23,7 @@ pub use ir::ExecutableDefinitionNameMap;
 pub use ir::ExecutableDefinitionNameSet;
 pub use ir::ExecutableDefinitionNameVec;
 pub use ir::get_ir_definition_references;
+pub use ir::get_ir_selection_references;
 pub use ir::get_reachable_ir;
 pub use minimized_executable::MinProgram;
 pub use query_stats::QueryStatsReport;
@@ -0,0 +1,401 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::fmt::Write;
+
+use common::Diagnostic;
+use common::DiagnosticSeverity;
+use common::Location;
+use common::get_diagnostics_data;
+use serde_json::Value;
+use serde_json::json;
+
+use crate::Sources;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Formats for diagnostics consumed by tools rather than people.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticsFormat {
+    /// A JSON object with a `diagnostics` array.
+    Json,
+    /// A SARIF 2.1.0 log with a single run.
+    Sarif,
+    /// GitHub Actions workflow commands, one annotation per line.
+    Github,
+}
+
+/// Collects diagnostics, and errors that have no source location, to render
+/// them in one of the [`DiagnosticsFormat`]s.
+pub struct DiagnosticReport<T: Sources> {
+    sources: T,
+    entries: Vec<ReportEntry>,
+}
+
+struct ReportEntry {
+    project: Option<String>,
+    severity: DiagnosticSeverity,
+    message: String,
+    message_type: Option<String>,
+    location: Option<ReportLocation>,
+    related_information: Vec<(String, Option<ReportLocation>)>,
+    fix_suggestions: Vec<String>,
+}
+
+/// A resolved location. Lines and columns are one-based; the range is
+/// missing when the source text could not be read.
+struct ReportLocation {
+    path: String,
+    range: Option<((u32, u32), (u32, u32))>,
+}
+
+impl<TSources: Sources> DiagnosticReport<TSources> {
+    pub fn new(sources: TSources) -> Self {
+        Self {
+            sources,
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn add_diagnostic(&mut self, project: Option<&str>, diagnostic: &Diagnostic) {
+        let fix_suggestions = match get_diagnostics_data(diagnostic) {
+            Some(Value::Array(items)) => items
+                .into_iter()
+                .filter_map(|item| item.as_str().map(str::to_string))
+                .collect(),
+            _ => vec![],
+        };
+        self.entries.push(ReportEntry {
+            project: project.map(str::to_string),
+            severity: diagnostic.severity(),
+            message: diagnostic.message().to_string(),
+            message_type: Some(diagnostic.message_type_name().to_string()),
+            location: self.resolve_location(diagnostic.location()),
+            related_information: diagnostic
+                .related_information()
+                .iter()
+                .map(|related| {
+                    (
+                        related.message.to_string(),
+                        self.resolve_location(related.location),
+                    )
+                })
+                .collect(),
+            fix_suggestions,
+        });
+    }
+
+    /// Add an error that is not attached to a source location.
+    pub fn add_error(&mut self, project: Option<&str>, message: String) {
+        self.entries.push(ReportEntry {
+            project: project.map(str::to_string),
+            severity: DiagnosticSeverity::ERROR,
+            message,
+            message_type: None,
+            location: None,
+            related_information: vec![],
+            fix_suggestions: vec![],
+        });
+    }
+
+    /// Render the report. `tool_name` identifies the reporting tool in
+    /// formats that record it.
+    pub fn render(&self, format: DiagnosticsFormat, tool_name: &str) -> String {
+        match format {
+            DiagnosticsFormat::Json => self.to_json(),
+            DiagnosticsFormat::Sarif => self.to_sarif(tool_name),
+            DiagnosticsFormat::Github => self.to_github_annotations(tool_name),
+        }
+    }
+
+    fn resolve_location(&self, location: Location) -> Option<ReportLocation> {
+        let source_location = location.source_location();
+        if source_location.is_generated() {
+            return None;
+        }
+        let range = self.sources.get(source_location).map(|source| {
+            let range = source.to_span_range(location.span());
+            (
+                (range.start.line + 1, range.start.character + 1),
+                (range.end.line + 1, range.end.character + 1),
+            )
+        });
+        Some(ReportLocation {
+            path: normalize_path(source_location.path()),
+            range,
+        })
+    }
+
+    fn to_json(&self) -> String {
+        let location_to_json = |location: &Option<ReportLocation>| match location {
+            Some(ReportLocation { path, range }) => json!({
+                "path": path,
+                "range": range.map(|((start_line, start_column), (end_line, end_column))| json!({
+                    "start": { "line": start_line, "column": start_column },
+                    "end": { "line": end_line, "column": end_column },
+                })),
+            }),
+            None => Value::Null,
+        };
+        let diagnostics: Vec<Value> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                json!({
+                    "project": entry.project,
+                    "severity": severity_name(entry.severity),
+                    "message": entry.message,
+                    "message_type": entry.message_type,
+                    "location": location_to_json(&entry.location),
+                    "related_information": entry
+                        .related_information
+                        .iter()
+                        .map(|(message, location)| json!({
+                            "message": message,
+                            "location": location_to_json(location),
+                        }))
+                        .collect::<Vec<_>>(),
+                    "fix_suggestions": entry.fix_suggestions,
+                })
+            })
+            .collect();
+        serde_json::to_string_pretty(&json!({ "diagnostics": diagnostics })).unwrap()
+    }
+
+    fn to_sarif(&self, tool_name: &str) -> String {
+        let physical_location = |location: &ReportLocation| {
+            let mut physical_location = json!({
+                "artifactLocation": { "uri": location.path },
+            });
+            if let Some(((start_line, start_column), (end_line, end_column))) = location.range {
+                physical_location["region"] = json!({
+                    "startLine": start_line,
+                    "startColumn": start_column,
+                    "endLine": end_line,
+                    "endColumn": end_column,
+                });
+            }
+            physical_location
+        };
+        let results: Vec<Value> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let mut result = json!({
+                    "level": match entry.severity {
+                        DiagnosticSeverity::ERROR => "error",
+                        DiagnosticSeverity::WARNING => "warning",
+                        _ => "note",
+                    },
+                    "message": { "text": entry.message },
+                    "locations": entry
+                        .location
+                        .iter()
+                        .map(|location| json!({ "physicalLocation": physical_location(location) }))
+                        .collect::<Vec<_>>(),
+                    "relatedLocations": entry
+                        .related_information
+                        .iter()
+                        .enumerate()
+                        .map(|(index, (message, location))| {
+                            let mut related = json!({
+                                "id": index,
+                                "message": { "text": message },
+                            });
+                            if let Some(location) = location {
+                                related["physicalLocation"] = physical_location(location);
+                            }
+                            related
+                        })
+                        .collect::<Vec<_>>(),
+                    // SARIF fixes are file edits; suggestions are only text.
+                    "properties": {
+                        "project": entry.project,
+                        "fixSuggestions": entry.fix_suggestions,
+                    },
+                });
+                if let Some(message_type) = &entry.message_type {
+                    result["ruleId"] = json!(message_type);
+                }
+                result
+            })
+            .collect();
+        serde_json::to_string_pretty(&json!({
+            "$schema": SARIF_SCHEMA,
+            "version": "2.1.0",
+            "runs": [{
+                "tool": { "driver": { "name": tool_name } },
+                "results": results,
+            }],
+        }))
+        .unwrap()
+    }
+
+    fn to_github_annotations(&self, tool_name: &str) -> String {
+        let mut output = String::new();
+        for entry in &self.entries {
+            let command = match entry.severity {
+                DiagnosticSeverity::ERROR => "error",
+                DiagnosticSeverity::WARNING => "warning",
+                DiagnosticSeverity::INFORMATION => "notice",
+                // Like the console output, leave hints out.
+                _ => continue,
+            };
+            let mut properties = Vec::new();
+            if let Some(location) = &entry.location {
+                properties.push(format!("file={}", escape_property(&location.path)));
+                if let Some(((start_line, start_column), (end_line, end_column))) = location.range {
+                    properties.push(format!("line={start_line}"));
+                    properties.push(format!("col={start_column}"));
+                    properties.push(format!("endLine={end_line}"));
+                    properties.push(format!("endColumn={end_column}"));
+                }
+            }
+            let title = match &entry.project {
+                Some(project) => format!("{tool_name} ({project})"),
+                None => tool_name.to_string(),
+            };
+            properties.push(format!("title={}", escape_property(&title)));
+
+            let mut message = entry.message.clone();
+            for (related_message, location) in &entry.related_information {
+                match location {
+                    Some(ReportLocation {
+                        path,
+                        range: Some(((line, column), _)),
+                    }) => write!(message, "\n{related_message} ({path}:{line}:{column})"),
+                    Some(ReportLocation { path, range: None }) => {
+                        write!(message, "\n{related_message} ({path})")
+                    }
+                    None => write!(message, "\n{related_message}"),
+                }
+                .unwrap();
+            }
+            if !entry.fix_suggestions.is_empty() {
+                write!(
+                    message,
+                    "\nSuggestions: {}",
+                    entry.fix_suggestions.join(", ")
+                )
+                .unwrap();
+            }
+            writeln!(
+                output,
+                "::{command} {}::{}",
+                properties.join(","),
+                escape_data(&message)
+            )
+            .unwrap();
+        }
+        output
+    }
+}
+
+fn severity_name(severity: DiagnosticSeverity) -> &'static str {
+    match severity {
+        DiagnosticSeverity::ERROR => "error",
+        DiagnosticSeverity::WARNING => "warning",
+        DiagnosticSeverity::INFORMATION => "info",
+        DiagnosticSeverity::HINT => "hint",
+        _ => "unknown",
+    }
+}
+
+/// Escape the message of a workflow command.
+fn escape_data(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a property value of a workflow command.
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
+
+/// Normalize Windows paths to Unix style, as tools expect URIs.
+fn normalize_path(path: &str) -> String {
+    path.replace("\\", "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use common::SourceLocationKey;
+    use common::Span;
+    use common::TextSource;
+
+    use super::*;
+
+    const SOURCE: &str = "fragment Foo on User {\n  nmae\n}\n";
+
+    fn report() -> DiagnosticReport<impl Sources> {
+        let mut report = DiagnosticReport::new(|_| Some(TextSource::from_whole_document(SOURCE)));
+        let source_location = SourceLocationKey::standalone("src/Foo.graphql");
+        let diagnostic = Diagnostic::error(
+            "Unknown field 'nmae'",
+            Location::new(source_location, Span::new(25, 29)),
+        )
+        .annotate(
+            "Type defined here",
+            Location::new(source_location, Span::new(16, 20)),
+        );
+        report.add_diagnostic(Some("web"), &diagnostic);
+        report.add_error(None, "Persisting failed, with: reasons".to_string());
+        report
+    }
+
+    #[test]
+    fn test_json_report() {
+        let json: Value =
+            serde_json::from_str(&report().render(DiagnosticsFormat::Json, "relay")).unwrap();
+        let diagnostics = json["diagnostics"].as_array().unwrap();
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0]["project"], "web");
+        assert_eq!(diagnostics[0]["severity"], "error");
+        assert_eq!(diagnostics[0]["location"]["path"], "src/Foo.graphql");
+        assert_eq!(
+            diagnostics[0]["location"]["range"],
+            json!({
+                "start": { "line": 2, "column": 3 },
+                "end": { "line": 2, "column": 7 },
+            })
+        );
+        assert_eq!(
+            diagnostics[0]["related_information"][0]["message"],
+            "Type defined here"
+        );
+        assert_eq!(diagnostics[1]["location"], Value::Null);
+    }
+
+    #[test]
+    fn test_sarif_report() {
+        let sarif: Value =
+            serde_json::from_str(&report().render(DiagnosticsFormat::Sarif, "relay")).unwrap();
+        assert_eq!(sarif["version"], "2.1.0");
+        let results = sarif["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results[0]["level"], "error");
+        assert_eq!(
+            results[0]["locations"][0]["physicalLocation"]["region"]["startLine"],
+            2
+        );
+        assert_eq!(results[1]["locations"], json!([]));
+    }
+
+    #[test]
+    fn test_github_annotations_escape_messages() {
+        assert_eq!(
+            report().render(DiagnosticsFormat::Github, "relay"),
+            "::error file=src/Foo.graphql,line=2,col=3,endLine=2,endColumn=7,title=relay (web)\
+             ::Unknown field 'nmae'%0AType defined here (src/Foo.graphql:1:17)\n\
+             ::error title=relay::Persisting failed, with: reasons\n"
+        );
+    }
+}
@@ -6,11 +6,14 @@
  */
 
 mod diagnostic_printer;
+mod diagnostic_report;
 mod source_printer;
 mod text_style;
 
 pub use diagnostic_printer::DiagnosticPrinter;
 pub use diagnostic_printer::Sources;
+pub use diagnostic_report::DiagnosticReport;
+pub use diagnostic_report::DiagnosticsFormat;
 pub use source_printer::SourcePrinter;
 pub use text_style::Style;
 pub use text_style::Styles;
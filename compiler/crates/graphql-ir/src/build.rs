@@ -170,6 +170,25 @@ pub fn build_ir_with_extra_features(
     })
 }
 
+/// Like `build_ir_with_extra_features`, with the signatures of fragments that
+/// are defined outside of the corpus. Spreads of these fragments are built with
+/// their arguments, like those of fragments of the corpus.
+pub fn build_ir_with_external_signatures(
+    schema: &SDLSchema,
+    definitions: &[graphql_syntax::ExecutableDefinition],
+    external_signatures: &FragmentSignatures,
+    options: &BuilderOptions,
+) -> DiagnosticsResult<Vec<ExecutableDefinition>> {
+    let mut signatures = build_signatures(schema, definitions)?;
+    for (name, signature) in external_signatures {
+        signatures.entry(*name).or_insert_with(|| signature.clone());
+    }
+    par_try_map(definitions, |definition| {
+        let mut builder = Builder::new(schema, &signatures, definition.location(), options);
+        builder.build_definition(definition)
+    })
+}
+
 pub fn build_type_annotation(
     schema: &SDLSchema,
     annotation: &graphql_syntax::TypeAnnotation,
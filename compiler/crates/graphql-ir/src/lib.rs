@@ -31,6 +31,7 @@ pub use build::RelayMode;
 pub use build::build_directive;
 pub use build::build_ir as build;
 pub use build::build_ir_in_relay_mode;
+pub use build::build_ir_with_external_signatures;
 pub use build::build_ir_with_extra_features;
 pub use constants::ARGUMENT_DEFINITION;
 pub use ir::*;
@@ -21,10 +21,13 @@ use log::error;
 use log::info;
 use relay_codemod::AvailableCodemod;
 use relay_codemod::run_codemod;
+use relay_compiler::ArtifactChangeReport;
+use relay_compiler::ArtifactDryRunWriter;
 #[cfg(unix)]
 use relay_compiler::DeferredArtifactCache;
 #[cfg(unix)]
 use relay_compiler::DeferredArtifactWriter;
+use relay_compiler::DryRunPersister;
 use relay_compiler::FileSourceKind;
 use relay_compiler::LocalPersister;
 #[cfg(unix)]
@@ -47,17 +50,26 @@ use relay_compiler::server_daemon::protocol::DaemonRequest;
 #[cfg(unix)]
 use relay_compiler::server_daemon::protocol::DaemonResponse;
 #[cfg(unix)]
+use relay_compiler::server_daemon::socket::DaemonRoot;
+#[cfg(unix)]
 use relay_compiler::server_daemon::socket::ServerConfig as DaemonServerConfig;
 #[cfg(unix)]
-use relay_compiler::status_reporter::BuildStatus;
+use relay_compiler::server_daemon::transport::DaemonEndpoint;
+#[cfg(unix)]
+use relay_compiler::server_daemon::transport::TcpEndpoint;
 #[cfg(unix)]
+use relay_compiler::status_reporter::BuildStatus;
+use relay_compiler::status_reporter::DiagnosticsFormat;
+use relay_compiler::status_reporter::JSONStatusReporter;
 use relay_compiler::status_reporter::NoopStatusReporter;
 use relay_compiler::subschema_extraction::compile_and_extract_subschema;
+use relay_lsp::ConfigLoader;
 use relay_lsp::DummyExtraDataProvider;
 use relay_lsp::FieldDefinitionSourceInfo;
 use relay_lsp::FieldSchemaInfo;
 use relay_lsp::LSPExtraDataProvider;
-use relay_lsp::start_language_server;
+use relay_lsp::LanguageServerOptions;
+use relay_lsp::start_language_server_with_options;
 use schema::SDLSchema;
 use schema_documentation::SchemaDocumentationLoader;
 use simplelog::ColorChoice;
@@ -148,6 +160,25 @@ struct CompileCommand {
     #[clap(long)]
     validate: bool,
 
+    /// Compute which artifacts the build would add, modify or delete, and
+    /// print them with unified diffs instead of writing to disk. Operations
+    /// are not persisted: the persist file is left as is and no requests are
+    /// sent to a remote persister.
+    #[clap(long, alias = "dry-run", conflicts_with_all = &["watch", "validate"])]
+    dry_run: bool,
+
+    /// Format of the `--dryRun` report. `json` also silences progress output
+    /// so that stdout holds only the report.
+    #[clap(long, value_enum, default_value = "human", requires = "dry_run")]
+    dry_run_format: DryRunFormat,
+
+    /// Print diagnostics in a machine-readable format instead of logging
+    /// them: `json`, `sarif` or `github` (GitHub Actions annotations). `json`
+    /// and `sarif` also silence progress output so that stdout holds only
+    /// the report.
+    #[clap(long, value_enum, alias = "diagnostics-format")]
+    diagnostics_format: Option<DiagnosticsFormatArg>,
+
     /// Send this build through the compiler daemon instead of compiling
     /// in-process. The daemon is started in the background on first use and
     /// reused across invocations, eliminating per-build startup cost. Pass
@@ -164,6 +195,8 @@ struct CompileCommand {
         conflicts_with_all = &[
             "watch",
             "validate",
+            "dry_run",
+            "diagnostics_format",
             "repersist",
             "no_watchman",
             "src",
@@ -214,6 +247,12 @@ struct LspCommand {
     /// implementation-first GraphQL schemas.
     #[clap(long)]
     locate_command: Option<String>,
+
+    /// File in which the language server saves its state after successful
+    /// builds. When the file exists, a restarted server restores its state from
+    /// it and only processes the files that changed since, using watchman.
+    #[clap(long)]
+    cache_file: Option<PathBuf>,
 }
 
 #[derive(Parser)]
@@ -271,6 +310,16 @@ struct ServerOpt {
     #[clap(long)]
     config: Option<PathBuf>,
 
+    /// Talk to the daemon listening on this socket instead of the one derived
+    /// from the config and projects, e.g. a daemon serving several configs.
+    #[clap(long, conflicts_with("token_file"))]
+    socket: Option<PathBuf>,
+
+    /// Talk to a daemon over TCP, using the port and token it published to
+    /// this file (see `start --tcpPort`).
+    #[clap(long)]
+    token_file: Option<PathBuf>,
+
     #[clap(subcommand)]
     command: ServerCommand,
 }
@@ -298,6 +347,16 @@ enum ServerCommand {
         /// Watchman normally.
         #[clap(long, requires("initial_import_state"))]
         initial_changed_files_list: Option<PathBuf>,
+        /// Also listen on this loopback TCP port (0 picks a free one). The
+        /// port and an access token are written to a file next to the
+        /// socket, readable only by the current user.
+        #[clap(long)]
+        tcp_port: Option<u16>,
+        /// Also serve this config from the same daemon. Clients address it
+        /// with `--socket` or `--tokenFile` and its `--config`. You can pass
+        /// this argument multiple times.
+        #[clap(long)]
+        additional_config: Vec<PathBuf>,
     },
     /// Request the daemon to write cached artifacts to disk.
     Write,
@@ -305,6 +364,24 @@ enum ServerCommand {
     Version,
     /// Shut down the daemon.
     Shutdown,
+    /// Print whether the daemon is building and how its last build ended.
+    Status,
+    /// Print the diagnostics of the daemon's latest build without writing artifacts.
+    Diagnostics,
+    /// List the artifacts the next write would change.
+    DirtyArtifacts,
+    /// List the artifacts generated from a source file.
+    Artifacts {
+        /// Source file, relative to the config root.
+        path: PathBuf,
+    },
+    /// Regenerate every artifact of the given projects.
+    Rebuild {
+        /// Projects to rebuild. If excluded, all enabled projects are rebuilt.
+        projects: Vec<String>,
+    },
+    /// Stream the daemon's build events to stdout as newline-delimited JSON.
+    Subscribe,
     /// Print the path of the daemon log file.
     LogFilePath,
     /// List all known daemon instances and their status.
@@ -318,6 +395,29 @@ enum ServerCommand {
     },
 }
 
+#[derive(ValueEnum, Clone, Copy)]
+enum DryRunFormat {
+    Human,
+    Json,
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+enum DiagnosticsFormatArg {
+    Json,
+    Sarif,
+    Github,
+}
+
+impl From<DiagnosticsFormatArg> for DiagnosticsFormat {
+    fn from(format: DiagnosticsFormatArg) -> Self {
+        match format {
+            DiagnosticsFormatArg::Json => DiagnosticsFormat::Json,
+            DiagnosticsFormatArg::Sarif => DiagnosticsFormat::Sarif,
+            DiagnosticsFormatArg::Github => DiagnosticsFormat::Github,
+        }
+    }
+}
+
 #[derive(ValueEnum, Clone, Copy)]
 enum OutputKind {
     Debug,
@@ -527,7 +627,16 @@ fn handle_compare_document_ir_command(command: CompareDocumentIRCommand) -> Resu
 }
 
 async fn handle_compiler_command(command: CompileCommand) -> Result<(), Error> {
-    configure_logger(command.output, TerminalMode::Mixed);
+    let output = if (command.dry_run && matches!(command.dry_run_format, DryRunFormat::Json))
+        || matches!(
+            command.diagnostics_format,
+            Some(DiagnosticsFormatArg::Json | DiagnosticsFormatArg::Sarif)
+        ) {
+        OutputKind::QuietWithErrors
+    } else {
+        command.output
+    };
+    configure_logger(output, TerminalMode::Mixed);
 
     if command.cli_config.is_defined() {
         return Err(Error::ConfigError(CompilerError::ConfigError {
@@ -557,7 +666,30 @@ async fn handle_compiler_command(command: CompileCommand) -> Result<(), Error> {
         config.artifact_writer = Box::<ArtifactValidationWriter>::default();
     }
 
+    if let Some(format) = command.diagnostics_format {
+        config.status_reporter = Box::new(
+            JSONStatusReporter::new(None, Box::new(NoopStatusReporter))
+                .with_diagnostics_format(format.into(), config.root_dir.clone()),
+        );
+    }
+
+    let dry_run_report = command
+        .dry_run
+        .then(|| Arc::new(ArtifactChangeReport::new(config.root_dir.clone())));
+    if let Some(report) = &dry_run_report {
+        config.artifact_writer = Box::new(ArtifactDryRunWriter::new(Arc::clone(report)));
+    }
+
     apply_default_cli_extensions(&mut config);
+    if dry_run_report.is_some() {
+        config.create_operation_persister = Some(Box::new(|project_config| {
+            project_config.persist.as_ref().map(
+                |persist_config| -> Box<dyn OperationPersister + Send + Sync> {
+                    Box::new(DryRunPersister::new(persist_config))
+                },
+            )
+        }));
+    }
 
     config.file_source_config = if should_use_watchman(command.no_watchman) {
         FileSourceKind::Watchman
@@ -587,6 +719,18 @@ async fn handle_compiler_command(command: CompileCommand) -> Result<(), Error> {
             })?;
     }
 
+    if let Some(report) = dry_run_report {
+        match command.dry_run_format {
+            DryRunFormat::Human => print!("{}", report.to_human_readable()),
+            DryRunFormat::Json => {
+                let json = report.to_json().map_err(|err| Error::CompilerError {
+                    details: format!("Unable to serialize the dry run report: {}", err),
+                })?;
+                println!("{}", json);
+            }
+        }
+    }
+
     info!("Done.");
     Ok(())
 }
@@ -649,7 +793,21 @@ impl LSPExtraDataProvider for ExtraDataProvider {
 async fn handle_lsp_command(command: LspCommand) -> Result<(), Error> {
     configure_logger(command.output, TerminalMode::Stderr);
 
-    let config = get_config(command.config)?;
+    let mut config = get_config(command.config.clone())?;
+    config.lsp_saved_state_file = command.cache_file.clone();
+    // Load the config from the same path when it changes
+    let config_path = command.config;
+    let cache_file = command.cache_file;
+    let config_loader: ConfigLoader = Box::new(move || {
+        let mut config = match &config_path {
+            Some(config_path) => Config::load(config_path.clone()),
+            None => {
+                Config::search(&current_dir().expect("Unable to get current working directory."))
+            }
+        }?;
+        config.lsp_saved_state_file = cache_file.clone();
+        Ok(config)
+    });
 
     let extra_data_provider: Box<dyn LSPExtraDataProvider + Send + Sync> =
         match command.locate_command {
@@ -660,11 +818,14 @@ async fn handle_lsp_command(command: LspCommand) -> Result<(), Error> {
     let perf_logger = Arc::new(ConsoleLogger);
     let schema_documentation_loader: Option<Box<dyn SchemaDocumentationLoader<SDLSchema>>> = None;
 
-    start_language_server(
+    start_language_server_with_options(
         config,
         perf_logger,
         extra_data_provider,
         schema_documentation_loader,
+        LanguageServerOptions {
+            config_loader: Some(config_loader),
+        },
     )
     .await
     .map_err(|err| Error::LSPError {
@@ -723,6 +884,8 @@ async fn handle_server_command(opt: ServerOpt) -> Result<(), Error> {
             foreground,
             initial_import_state,
             initial_changed_files_list,
+            tcp_port,
+            additional_config,
         } => {
             if foreground {
                 start_server_foreground(
@@ -731,6 +894,8 @@ async fn handle_server_command(opt: ServerOpt) -> Result<(), Error> {
                     opt.config,
                     initial_import_state,
                     initial_changed_files_list,
+                    tcp_port,
+                    additional_config,
                 )
                 .await
             } else {
@@ -738,10 +903,18 @@ async fn handle_server_command(opt: ServerOpt) -> Result<(), Error> {
                     "--config".to_string(),
                     config_path.to_string_lossy().into_owned(),
                 ];
-                let start_extra_args = server_daemon::build_initial_external_state_args(
+                let mut start_extra_args = server_daemon::build_initial_external_state_args(
                     initial_import_state.as_deref(),
                     initial_changed_files_list.as_deref(),
                 );
+                if let Some(port) = tcp_port {
+                    start_extra_args.push("--tcpPort".to_string());
+                    start_extra_args.push(port.to_string());
+                }
+                for path in &additional_config {
+                    start_extra_args.push("--additionalConfig".to_string());
+                    start_extra_args.push(path.to_string_lossy().into_owned());
+                }
                 server_daemon::start_daemon_process(
                     &config_path,
                     &opt.project,
@@ -757,6 +930,25 @@ async fn handle_server_command(opt: ServerOpt) -> Result<(), Error> {
             println!("{}", log_path.display());
             Ok(())
         }
+        ServerCommand::Subscribe => {
+            let (endpoint, root) = client_endpoint(
+                opt.token_file.as_deref(),
+                opt.socket.as_deref(),
+                &opt.project,
+                &config_path,
+            )?;
+            let result = server_daemon::subscribe(
+                &endpoint,
+                root.as_deref(),
+                server_daemon::print_daemon_event,
+            )
+            .await;
+            if let Err(e) = result {
+                error!("Failed to subscribe to {}: {}", endpoint, e);
+                std::process::exit(1);
+            }
+            Ok(())
+        }
         ServerCommand::List { cleanup, shutdown } => {
             if let Err(e) = server_daemon::list_daemons(cleanup, shutdown).await {
                 error!("Failed to list daemons: {}", e);
@@ -764,7 +956,14 @@ async fn handle_server_command(opt: ServerOpt) -> Result<(), Error> {
             }
             Ok(())
         }
-        cmd @ (ServerCommand::Write | ServerCommand::Version | ServerCommand::Shutdown) => {
+        cmd @ (ServerCommand::Write
+        | ServerCommand::Version
+        | ServerCommand::Shutdown
+        | ServerCommand::Status
+        | ServerCommand::Diagnostics
+        | ServerCommand::DirtyArtifacts
+        | ServerCommand::Artifacts { .. }
+        | ServerCommand::Rebuild { .. }) => {
             let request = match cmd {
                 ServerCommand::Write => DaemonRequest::Write {
                     flush_manifest_path: None,
@@ -772,13 +971,26 @@ async fn handle_server_command(opt: ServerOpt) -> Result<(), Error> {
                 },
                 ServerCommand::Version => DaemonRequest::Version,
                 ServerCommand::Shutdown => DaemonRequest::Shutdown,
+                ServerCommand::Status => DaemonRequest::Status,
+                ServerCommand::Diagnostics => DaemonRequest::Diagnostics,
+                ServerCommand::DirtyArtifacts => DaemonRequest::DirtyArtifacts,
+                ServerCommand::Artifacts { path } => DaemonRequest::ArtifactsForSource { path },
+                ServerCommand::Rebuild { projects } => DaemonRequest::Rebuild { projects },
                 _ => unreachable!(),
             };
+            let (endpoint, root) = client_endpoint(
+                opt.token_file.as_deref(),
+                opt.socket.as_deref(),
+                &opt.project,
+                &config_path,
+            )?;
             let socket_path = server_daemon::get_socket_path(&config_path, &opt.project);
 
             // For Write, check the daemon's version first and restart it on
-            // mismatch so artifacts aren't written by a stale compiler.
+            // mismatch so artifacts aren't written by a stale compiler. Only
+            // the daemon derived from the config can be restarted from here.
             if matches!(&request, DaemonRequest::Write { .. })
+                && root.is_none()
                 && let Some(response) =
                     server_daemon::send_request(&socket_path, DaemonRequest::Version).await
                 && server_daemon::has_version_mismatch(&response, &compiler_version())
@@ -793,13 +1005,47 @@ async fn handle_server_command(opt: ServerOpt) -> Result<(), Error> {
                 .await;
             }
 
-            info!("Sending {:?} to {}", request, socket_path.display());
-            let response = server_daemon::send_request(&socket_path, request).await;
+            info!("Sending {:?} to {}", request, endpoint);
+            let response =
+                server_daemon::send_request_to(&endpoint, root.as_deref(), request).await;
             log_daemon_response(response)
         }
     }
 }
 
+/// The daemon endpoint `relay server` client commands talk to, and the
+/// config root to address requests to. Without `--socket` or `--tokenFile`
+/// this is the daemon derived from the config and projects, which serves the
+/// config as its primary root, so no root needs to be named.
+#[cfg(unix)]
+fn client_endpoint(
+    token_file: Option<&Path>,
+    socket: Option<&Path>,
+    project: &[String],
+    config_path: &Path,
+) -> Result<(DaemonEndpoint, Option<PathBuf>), Error> {
+    if let Some(token_file) = token_file {
+        let endpoint = TcpEndpoint::read(token_file).map_err(|e| Error::CompilerError {
+            details: format!(
+                "Unable to read daemon token file {}: {e}",
+                token_file.display()
+            ),
+        })?;
+        Ok((
+            DaemonEndpoint::Tcp(endpoint),
+            Some(config_path.to_path_buf()),
+        ))
+    } else if let Some(socket_path) = socket {
+        Ok((
+            DaemonEndpoint::Unix(socket_path.to_path_buf()),
+            Some(config_path.to_path_buf()),
+        ))
+    } else {
+        let socket_path = server_daemon::get_socket_path(config_path, project);
+        Ok((DaemonEndpoint::Unix(socket_path), None))
+    }
+}
+
 #[cfg(unix)]
 async fn start_server_foreground(
     config_path: &Path,
@@ -807,17 +1053,54 @@ async fn start_server_foreground(
     user_config_arg: Option<PathBuf>,
     initial_import_state: Option<PathBuf>,
     initial_changed_files_list: Option<PathBuf>,
+    tcp_port: Option<u16>,
+    additional_config_paths: Vec<PathBuf>,
 ) -> Result<(), Error> {
     info!("Starting Relay compiler in server mode...");
 
     let mut config = get_config(user_config_arg)?;
     set_project_flag(&mut config, &projects.to_vec())?;
-    apply_default_cli_extensions(&mut config);
     server_daemon::apply_initial_external_state_hints(
         &mut config,
         initial_import_state,
         initial_changed_files_list,
     );
+    let mut roots = vec![daemon_root(
+        config,
+        config_path.to_path_buf(),
+        projects.to_vec(),
+    )];
+    // Additional roots compile all of their projects; `--project` selects
+    // projects of the primary config only.
+    for additional_config_path in additional_config_paths {
+        let config = get_config(Some(additional_config_path.clone()))?;
+        roots.push(daemon_root(config, additional_config_path, vec![]));
+    }
+
+    let socket_path = server_daemon::get_socket_path(config_path, projects);
+    info!("Starting server on {}", socket_path.display());
+
+    let perf_logger = Arc::new(ConsoleLogger);
+    server_daemon::start_server(DaemonServerConfig {
+        socket_path,
+        tcp_port,
+        roots,
+        perf_logger,
+        compiler_version: compiler_version(),
+    })
+    .await
+    .map_err(|e| Error::CompilerError {
+        details: format!("Daemon server error: {e}"),
+    })
+}
+
+/// Prepare a loaded config to be served by the daemon: builds report to a
+/// [`BuildStatus`] and artifacts are cached until a client asks to write.
+/// Each root logs its builds to the file derived from its own config path and
+/// projects, like the socket path of a daemon serving only that root.
+#[cfg(unix)]
+fn daemon_root(mut config: Config, config_path: PathBuf, projects: Vec<String>) -> DaemonRoot {
+    apply_default_cli_extensions(&mut config);
 
     // Wrap the existing status reporter so daemon clients can observe build
     // lifecycle and so handle_write can wait_for_idle.
@@ -828,8 +1111,7 @@ async fn start_server_foreground(
         config.root_dir.clone(),
         config.is_multi_project,
     );
-    let log_path = server_daemon::get_log_file_path(config_path, projects);
-    build_status.set_log_path(log_path);
+    build_status.set_log_path(server_daemon::get_log_file_path(&config_path, &projects));
     let build_status = Arc::new(build_status);
     config.daemon_build_status = Some(Arc::clone(&build_status));
     config.status_reporter = Box::new(Arc::clone(&build_status));
@@ -841,25 +1123,14 @@ async fn start_server_foreground(
     let artifact_cache = Arc::new(DeferredArtifactCache::new(original_artifact_writer));
     config.artifact_writer = Box::new(DeferredArtifactWriter::new(Arc::clone(&artifact_cache)));
 
-    let socket_path = server_daemon::get_socket_path(config_path, projects);
-    info!("Starting server on {}", socket_path.display());
-
-    let perf_logger = Arc::new(ConsoleLogger);
-    server_daemon::start_server(DaemonServerConfig {
-        socket_path,
-        config_path: config_path.to_path_buf(),
-        projects: projects.to_vec(),
+    DaemonRoot {
+        config_path,
+        projects,
         compiler_config: config,
-        perf_logger,
         artifact_cache,
         build_status,
-        compiler_version: compiler_version(),
         flush_writer_factory: None,
-    })
-    .await
-    .map_err(|e| Error::CompilerError {
-        details: format!("Daemon server error: {e}"),
-    })
+    }
 }
 
 /// Compute the OSS-CLI extra args (passes `--config <path>` so the spawned
@@ -71,6 +71,7 @@ use rayon::slice::ParallelSlice;
 use relay_codegen::Printer;
 use relay_config::ProjectName;
 use relay_transforms::CustomTransformsConfig;
+use relay_transforms::FragmentSpreadLocations;
 use relay_transforms::Programs;
 use relay_transforms::apply_transforms;
 use relay_typegen::FragmentLocations;
@@ -140,9 +141,13 @@ pub fn build_raw_program(
     schema: Arc<SDLSchema>,
     log_event: &impl PerfLogEvent,
     build_mode: BuildMode,
-) -> Result<(Program, SourceHashes), BuildProjectError> {
+) -> Result<(Program, SourceHashes, FragmentSpreadLocations), BuildProjectError> {
     // Build a type aware IR.
-    let BuildIRResult { ir, source_hashes } = log_event.time("build_ir_time", || {
+    let BuildIRResult {
+        ir,
+        source_hashes,
+        fragment_spread_locations,
+    } = log_event.time("build_ir_time", || {
         build_ir::build_ir(project_config, project_asts, &schema, build_mode, log_event).map_err(
             |errors| BuildProjectError::ValidationErrors {
                 errors,
@@ -156,7 +161,7 @@ pub fn build_raw_program(
         Program::from_definitions(schema, ir)
     });
 
-    Ok((program, source_hashes))
+    Ok((program, source_hashes, fragment_spread_locations))
 }
 
 const MIN_CHUNK_SIZE: usize = 8192;
@@ -168,9 +173,13 @@ fn build_raw_program_chunks(
     schema: Arc<SDLSchema>,
     log_event: &impl PerfLogEvent,
     build_mode: BuildMode,
-) -> Result<(Vec<Program>, SourceHashes), BuildProjectError> {
+) -> Result<(Vec<Program>, SourceHashes, FragmentSpreadLocations), BuildProjectError> {
     // Build a type aware IR.
-    let BuildIRResult { ir, source_hashes } = log_event.time("build_ir_time", || {
+    let BuildIRResult {
+        ir,
+        source_hashes,
+        fragment_spread_locations,
+    } = log_event.time("build_ir_time", || {
         build_ir::build_ir(project_config, project_asts, &schema, build_mode, log_event).map_err(
             |errors| BuildProjectError::ValidationErrors {
                 errors,
@@ -241,7 +250,7 @@ fn build_raw_program_chunks(
             .map(|definitions| Program::from_definitions(Arc::clone(&schema), definitions))
             .collect()
     });
-    Ok((programs, source_hashes))
+    Ok((programs, source_hashes, fragment_spread_locations))
 }
 
 // OK(Vec<Diagnostic>) = Compilation can continue
@@ -250,12 +259,18 @@ pub fn validate_program(
     config: &Config,
     project_config: &ProjectConfig,
     program: &Program,
+    fragment_spread_locations: &FragmentSpreadLocations,
     log_event: &impl PerfLogEvent,
 ) -> Result<Vec<Diagnostic>, Vec<Diagnostic>> {
     let timer = log_event.start("validate_time");
     log_event.number("validate_documents_count", program.document_count());
-    let result = validate(program, project_config, &config.additional_validations)
-        .map(|result| result.diagnostics);
+    let result = validate(
+        program,
+        project_config,
+        &config.additional_validations,
+        fragment_spread_locations,
+    )
+    .map(|result| result.diagnostics);
 
     log_event.stop(timer);
 
@@ -398,7 +413,7 @@ pub fn build_programs(
             }
         },
     );
-    let (programs, source_hashes) =
+    let (programs, source_hashes, fragment_spread_locations) =
         build_raw_program_chunks(project_config, project_asts, schema, log_event, build_mode)?;
 
     if compiler_state.should_cancel_current_build() {
@@ -418,7 +433,15 @@ pub fn build_programs(
                 // one runs on the current thread, the other is available for
                 // work-stealing by idle rayon threads.
                 let (validate_result, transform_result) = rayon::join(
-                    || validate_program(config, project_config, &arc_program, log_event),
+                    || {
+                        validate_program(
+                            config,
+                            project_config,
+                            &arc_program,
+                            &fragment_spread_locations,
+                            log_event,
+                        )
+                    },
                     || {
                         transform_program(
                             project_config,
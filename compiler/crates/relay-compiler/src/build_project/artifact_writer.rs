@@ -5,7 +5,6 @@
  * LICENSE file in the root directory of this source tree.
  */
 
-#[cfg(unix)]
 use std::collections::HashMap;
 use std::fmt::Write as _;
 use std::fs::File;
@@ -14,13 +13,13 @@ use std::io;
 use std::io::prelude::*;
 use std::path::Path;
 use std::path::PathBuf;
-#[cfg(unix)]
 use std::sync::Arc;
 use std::sync::Mutex;
 
 use dashmap::DashSet;
 use log::debug;
 use log::info;
+use serde::Serialize;
 use sha1::Digest;
 use sha1::Sha1;
 
@@ -255,8 +254,263 @@ fn write_outdated_artifacts(output: &mut String, title: &str, artifacts: &DashSe
     }
 }
 
+/// How a build would change an artifact on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArtifactChangeKind {
+    Added,
+    Modified,
+    Deleted,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ArtifactChange {
+    /// Path of the artifact, relative to the config root when it is inside it.
+    pub path: PathBuf,
+    pub kind: ArtifactChangeKind,
+    /// Unified diff from the artifact on disk to the generated one.
+    pub diff: String,
+}
+
+/// Collects the artifact changes recorded by an [`ArtifactDryRunWriter`], to
+/// be reported once the build is done.
+pub struct ArtifactChangeReport {
+    root_dir: PathBuf,
+    changes: Mutex<HashMap<PathBuf, ArtifactChange>>,
+}
+
+impl ArtifactChangeReport {
+    pub fn new(root_dir: PathBuf) -> Self {
+        Self {
+            root_dir,
+            changes: Default::default(),
+        }
+    }
+
+    fn record(&self, path: PathBuf, kind: ArtifactChangeKind, old: &[u8], new: &[u8]) {
+        let relative_path = path
+            .strip_prefix(&self.root_dir)
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|_| path.clone());
+        let display_path = relative_path.to_string_lossy();
+        let old_label = match kind {
+            ArtifactChangeKind::Added => "/dev/null".to_string(),
+            _ => format!("a/{display_path}"),
+        };
+        let new_label = match kind {
+            ArtifactChangeKind::Deleted => "/dev/null".to_string(),
+            _ => format!("b/{display_path}"),
+        };
+        let diff = unified_diff(
+            &old_label,
+            &new_label,
+            &String::from_utf8_lossy(old),
+            &String::from_utf8_lossy(new),
+        );
+        self.changes.lock().unwrap().insert(
+            path,
+            ArtifactChange {
+                path: relative_path,
+                kind,
+                diff,
+            },
+        );
+    }
+
+    /// The recorded changes, sorted by path.
+    pub fn changes(&self) -> Vec<ArtifactChange> {
+        let mut changes: Vec<_> = self.changes.lock().unwrap().values().cloned().collect();
+        changes.sort_by(|a, b| a.path.cmp(&b.path));
+        changes
+    }
+
+    /// The report as a JSON object with a `changes` array.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        #[derive(Serialize)]
+        struct Report {
+            changes: Vec<ArtifactChange>,
+        }
+        serde_json::to_string_pretty(&Report {
+            changes: self.changes(),
+        })
+    }
+
+    /// A summary line per artifact, followed by the diffs and a total.
+    pub fn to_human_readable(&self) -> String {
+        let changes = self.changes();
+        let mut output = String::new();
+        if changes.is_empty() {
+            writeln!(output, "No artifacts would change.").unwrap();
+            return output;
+        }
+        let count = |kind| changes.iter().filter(|change| change.kind == kind).count();
+        for change in &changes {
+            let marker = match change.kind {
+                ArtifactChangeKind::Added => 'A',
+                ArtifactChangeKind::Modified => 'M',
+                ArtifactChangeKind::Deleted => 'D',
+            };
+            writeln!(output, "{marker} {}", change.path.display()).unwrap();
+        }
+        for change in &changes {
+            writeln!(output).unwrap();
+            output.push_str(&change.diff);
+        }
+        writeln!(
+            output,
+            "\n{} added, {} modified, {} deleted",
+            count(ArtifactChangeKind::Added),
+            count(ArtifactChangeKind::Modified),
+            count(ArtifactChangeKind::Deleted),
+        )
+        .unwrap();
+        output
+    }
+}
+
+/// Artifact writer that records what a build would change in a shared
+/// [`ArtifactChangeReport`] instead of touching the filesystem.
+pub struct ArtifactDryRunWriter {
+    report: Arc<ArtifactChangeReport>,
+}
+
+impl ArtifactDryRunWriter {
+    pub fn new(report: Arc<ArtifactChangeReport>) -> Self {
+        Self { report }
+    }
+}
+
+impl ArtifactWriter for ArtifactDryRunWriter {
+    fn should_write(
+        &self,
+        path: &Path,
+        content: &[u8],
+        hash: Option<String>,
+    ) -> Result<bool, BuildProjectError> {
+        let op = |error| BuildProjectError::WriteFileError {
+            file: path.to_owned(),
+            source: error,
+        };
+        if let Some(file_hash) = hash {
+            hash_is_different(file_hash, content).map_err(op)
+        } else {
+            content_is_different(path, content).map_err(op)
+        }
+    }
+
+    fn write(&self, path: PathBuf, content: Vec<u8>) -> BuildProjectResult {
+        if path.exists() {
+            let existing_content =
+                std::fs::read(&path).map_err(|error| BuildProjectError::WriteFileError {
+                    file: path.clone(),
+                    source: error,
+                })?;
+            self.report.record(
+                path,
+                ArtifactChangeKind::Modified,
+                &existing_content,
+                &content,
+            );
+        } else {
+            self.report
+                .record(path, ArtifactChangeKind::Added, &[], &content);
+        }
+        Ok(())
+    }
+
+    fn remove(&self, path: PathBuf) -> BuildProjectResult {
+        // Like `ArtifactFileWriter`, removing a missing artifact is a no-op.
+        if let Ok(existing_content) = std::fs::read(&path) {
+            self.report
+                .record(path, ArtifactChangeKind::Deleted, &existing_content, &[]);
+        }
+        Ok(())
+    }
+
+    fn finalize(&self) -> crate::errors::Result<()> {
+        Ok(())
+    }
+}
+
+const DIFF_CONTEXT_LINES: usize = 3;
+
+/// Render a unified diff between `old` and `new`, with hunks of
+/// `DIFF_CONTEXT_LINES` lines of context. Empty if the texts are equal.
+fn unified_diff(old_label: &str, new_label: &str, old: &str, new: &str) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let lines = diff::slice(&old_lines, &new_lines);
+    let changed: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, diff::Result::Both(..)))
+        .map(|(index, _)| index)
+        .collect();
+    let mut output = String::new();
+    if changed.is_empty() {
+        return output;
+    }
+
+    // Line numbers of the old and new text preceding each entry.
+    let mut old_before = Vec::with_capacity(lines.len());
+    let mut new_before = Vec::with_capacity(lines.len());
+    let (mut old_line, mut new_line) = (0, 0);
+    for line in &lines {
+        old_before.push(old_line);
+        new_before.push(new_line);
+        match line {
+            diff::Result::Left(_) => old_line += 1,
+            diff::Result::Right(_) => new_line += 1,
+            diff::Result::Both(..) => {
+                old_line += 1;
+                new_line += 1;
+            }
+        }
+    }
+
+    // Expand each change by its context, merging hunks that overlap or touch.
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for index in changed {
+        let start = index.saturating_sub(DIFF_CONTEXT_LINES);
+        let end = (index + 1 + DIFF_CONTEXT_LINES).min(lines.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    writeln!(output, "--- {old_label}").unwrap();
+    writeln!(output, "+++ {new_label}").unwrap();
+    for (start, end) in hunks {
+        let hunk = &lines[start..end];
+        let old_count = hunk
+            .iter()
+            .filter(|line| !matches!(line, diff::Result::Right(_)))
+            .count();
+        let new_count = hunk
+            .iter()
+            .filter(|line| !matches!(line, diff::Result::Left(_)))
+            .count();
+        // By convention an empty range starts at the line before it.
+        let old_start = old_before[start] + usize::from(old_count > 0);
+        let new_start = new_before[start] + usize::from(new_count > 0);
+        writeln!(
+            output,
+            "@@ -{old_start},{old_count} +{new_start},{new_count} @@"
+        )
+        .unwrap();
+        for line in hunk {
+            match line {
+                diff::Result::Left(text) => writeln!(output, "-{text}").unwrap(),
+                diff::Result::Right(text) => writeln!(output, "+{text}").unwrap(),
+                diff::Result::Both(text, _) => writeln!(output, " {text}").unwrap(),
+            }
+        }
+    }
+    output
+}
+
 /// An operation to be performed on the filesystem during deferred write.
-#[cfg(unix)]
 #[derive(Clone)]
 pub enum ArtifactOperation {
     Write { content: Vec<u8> },
@@ -269,7 +523,6 @@ pub enum ArtifactOperation {
 /// deferring actual filesystem operations until `flush_to_disk` is called.
 /// Used by the server daemon to hold compilation output until a client
 /// requests a flush.
-#[cfg(unix)]
 pub struct DeferredArtifactCache {
     operations: Mutex<HashMap<PathBuf, ArtifactOperation>>,
     /// The inner writer used to perform actual write/remove operations during flush
@@ -280,7 +533,6 @@ pub struct DeferredArtifactCache {
     written_content_hashes: Mutex<HashMap<PathBuf, [u8; 20]>>,
 }
 
-#[cfg(unix)]
 impl DeferredArtifactCache {
     pub fn new(inner_writer: Box<dyn ArtifactWriter + Send + Sync>) -> Self {
         Self {
@@ -318,6 +570,22 @@ impl DeferredArtifactCache {
         }
     }
 
+    /// Paths of the cached operations that have not been flushed yet, as
+    /// `(written, removed)`, each sorted.
+    pub fn pending_artifacts(&self) -> (Vec<PathBuf>, Vec<PathBuf>) {
+        let mut written = Vec::new();
+        let mut removed = Vec::new();
+        for (path, op) in self.operations.lock().unwrap().iter() {
+            match op {
+                ArtifactOperation::Write { .. } => written.push(path.clone()),
+                ArtifactOperation::Remove => removed.push(path.clone()),
+            }
+        }
+        written.sort();
+        removed.sort();
+        (written, removed)
+    }
+
     /// Flush all cached operations to disk.
     ///
     /// Writes all cached content to disk and performs any pending removals,
@@ -387,19 +655,16 @@ impl DeferredArtifactCache {
 /// shared [`DeferredArtifactCache`] instead of performing them immediately.
 /// The actual filesystem operations are deferred until
 /// [`DeferredArtifactCache::flush_to_disk`] is called.
-#[cfg(unix)]
 pub struct DeferredArtifactWriter {
     cache: Arc<DeferredArtifactCache>,
 }
 
-#[cfg(unix)]
 impl DeferredArtifactWriter {
     pub fn new(cache: Arc<DeferredArtifactCache>) -> Self {
         Self { cache }
     }
 }
 
-#[cfg(unix)]
 impl ArtifactWriter for DeferredArtifactWriter {
     fn should_write(
         &self,
@@ -458,3 +723,69 @@ impl ArtifactWriter for DeferredArtifactWriter {
         self.cache.content_matches_last_write(path)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unified_diff_groups_changes_into_hunks() {
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n";
+        let new = "1\nTWO\n3\n4\n5\n6\n7\n8\n9\n10\n11\n";
+        assert_eq!(
+            unified_diff("a/x.js", "b/x.js", old, new),
+            "--- a/x.js\n+++ b/x.js\n\
+             @@ -1,5 +1,5 @@\n 1\n-2\n+TWO\n 3\n 4\n 5\n\
+             @@ -8,3 +8,4 @@\n 8\n 9\n 10\n+11\n"
+        );
+        assert_eq!(unified_diff("a/x.js", "b/x.js", old, old), "");
+    }
+
+    #[test]
+    fn test_dry_run_writer_records_changes_without_writing() {
+        let dir = tempfile::tempdir().unwrap();
+        let modified = dir.path().join("Modified.graphql.js");
+        let deleted = dir.path().join("Deleted.graphql.js");
+        let added = dir.path().join("__generated__/Added.graphql.js");
+        std::fs::write(&modified, "old\n").unwrap();
+        std::fs::write(&deleted, "gone\n").unwrap();
+
+        let report = Arc::new(ArtifactChangeReport::new(dir.path().to_path_buf()));
+        let writer = ArtifactDryRunWriter::new(Arc::clone(&report));
+        writer.write(modified.clone(), b"new\n".to_vec()).unwrap();
+        writer.write(added.clone(), b"added\n".to_vec()).unwrap();
+        writer.remove(deleted.clone()).unwrap();
+        writer
+            .remove(dir.path().join("NeverExisted.graphql.js"))
+            .unwrap();
+
+        assert_eq!(std::fs::read_to_string(&modified).unwrap(), "old\n");
+        assert!(deleted.exists());
+        assert!(!added.exists());
+        assert_eq!(
+            report.changes(),
+            vec![
+                ArtifactChange {
+                    path: PathBuf::from("Deleted.graphql.js"),
+                    kind: ArtifactChangeKind::Deleted,
+                    diff: "--- a/Deleted.graphql.js\n+++ /dev/null\n@@ -1,1 +0,0 @@\n-gone\n"
+                        .to_string(),
+                },
+                ArtifactChange {
+                    path: PathBuf::from("Modified.graphql.js"),
+                    kind: ArtifactChangeKind::Modified,
+                    diff: "--- a/Modified.graphql.js\n+++ b/Modified.graphql.js\n\
+                           @@ -1,1 +1,1 @@\n-old\n+new\n"
+                        .to_string(),
+                },
+                ArtifactChange {
+                    path: PathBuf::from("__generated__/Added.graphql.js"),
+                    kind: ArtifactChangeKind::Added,
+                    diff: "--- /dev/null\n+++ b/__generated__/Added.graphql.js\n\
+                           @@ -0,0 +1,1 @@\n+added\n"
+                        .to_string(),
+                },
+            ]
+        );
+    }
+}
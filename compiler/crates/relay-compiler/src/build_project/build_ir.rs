@@ -22,6 +22,7 @@ use intern::string_key::StringKey;
 use md5::Digest;
 use md5::Md5;
 use rayon::prelude::*;
+use relay_transforms::FragmentSpreadLocations;
 use relay_transforms::annotate_resolver_root_fragments;
 use schema::SDLSchema;
 
@@ -32,6 +33,9 @@ use crate::config::ProjectConfig;
 pub struct BuildIRResult {
     pub ir: Vec<graphql_ir::ExecutableDefinition>,
     pub source_hashes: SourceHashes,
+    /// The fragment spreads of all the definitions of the project, including
+    /// the ones an incremental build leaves out of `ir`.
+    pub fragment_spread_locations: FragmentSpreadLocations,
 }
 
 /// Map fragments and queries definition names to the md5 of they printed source
@@ -85,6 +89,11 @@ pub fn build_ir(
     if project_config.resolvers_schema_module.is_some() {
         ir = annotate_resolver_root_fragments(schema, ir);
     }
+    let fragment_spread_locations = if project_config.lint_rules.fragment_spread_count.is_some() {
+        FragmentSpreadLocations::from_definitions(&ir)
+    } else {
+        Default::default()
+    };
     let affected_ir: Vec<graphql_ir::ExecutableDefinition> = match build_mode {
         BuildMode::Incremental => get_reachable_ir(
             ir,
@@ -118,6 +127,7 @@ pub fn build_ir(
     Ok(BuildIRResult {
         ir: affected_ir,
         source_hashes,
+        fragment_spread_locations,
     })
 }
 
@@ -14,6 +14,7 @@ use errors::try_all;
 use graphql_ir::Program;
 use relay_config::JsModuleFormat;
 use relay_config::ProjectConfig;
+use relay_transforms::FragmentSpreadLocations;
 use relay_transforms::ValidateVariablesOptions;
 use relay_transforms::disallow_circular_no_inline_fragments;
 use relay_transforms::disallow_readtime_features_in_mutations;
@@ -25,6 +26,7 @@ use relay_transforms::validate_client_schema_extensions_use_catch;
 use relay_transforms::validate_connections;
 use relay_transforms::validate_fragment_alias_conflict;
 use relay_transforms::validate_global_variable_names;
+use relay_transforms::validate_lint_rules;
 use relay_transforms::validate_module_names;
 use relay_transforms::validate_no_double_underscore_alias;
 use relay_transforms::validate_no_inline_fragments_with_raw_response_type;
@@ -66,6 +68,7 @@ pub fn validate(
     program: &Program,
     project_config: &ProjectConfig,
     additional_validations: &Option<AdditionalValidations>,
+    fragment_spread_locations: &FragmentSpreadLocations,
 ) -> DiagnosticsResult<WithDiagnostics<()>> {
     let output = try_all(vec![
         validate_variables(project_config, program),
@@ -116,6 +119,11 @@ pub fn validate(
             project_config.feature_flags.enable_relay_resolver_mutations,
         ),
         validate_fragment_alias_conflict(program),
+        validate_lint_rules(
+            program,
+            &project_config.lint_rules,
+            fragment_spread_locations,
+        ),
     ]);
 
     transform_errors(output, project_config)
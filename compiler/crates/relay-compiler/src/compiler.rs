@@ -17,7 +17,6 @@ use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering::SeqCst;
 
-use common::Diagnostic;
 use common::PerfLogEvent;
 use common::PerfLogger;
 use common::WithDiagnostics;
@@ -26,6 +25,7 @@ use futures::future::join_all;
 use graphql_watchman::WatchmanFileSourceSubscriptionNextChange;
 use log::debug;
 use log::info;
+use log::warn;
 use rayon::prelude::*;
 use tokio::sync::Notify;
 use tokio::task;
@@ -49,6 +49,8 @@ use crate::file_source::FileSourceSubscriptionNextChange;
 use crate::file_source::LocatedDocblockSource;
 use crate::graphql_asts::GraphQLAsts;
 use crate::red_to_green::RedToGreen;
+use crate::status_reporter::BuildEvent;
+use crate::status_reporter::ProjectDiagnostics;
 
 /// Output of the per-iteration watch-mode setup block in [`Compiler::watch`]:
 /// the freshly built compiler state, the notify receiver the build loop awaits
@@ -75,7 +77,7 @@ impl<TPerfLogger: PerfLogger> Compiler<TPerfLogger> {
     pub async fn compile(&self) -> Result<CompilerState> {
         let setup_event = self.perf_logger.create_event("compiler_setup");
         self.config.status_reporter.build_starts();
-        let result: Result<(CompilerState, Vec<Diagnostic>)> = async {
+        let result: Result<(CompilerState, ProjectDiagnostics)> = async {
             if let Some(initialize_resources) = &self.config.initialize_resources {
                 let timer = setup_event.start("load_resources");
                 initialize_resources();
@@ -101,7 +103,7 @@ impl<TPerfLogger: PerfLogger> Compiler<TPerfLogger> {
             Ok((compiler_state, non_fatal_diagnostics)) => {
                 self.config
                     .status_reporter
-                    .build_completes(&non_fatal_diagnostics);
+                    .build_completes_for_projects(&non_fatal_diagnostics);
                 Ok(compiler_state)
             }
             Err(error) => {
@@ -122,7 +124,7 @@ impl<TPerfLogger: PerfLogger> Compiler<TPerfLogger> {
         let setup_event = self.perf_logger.create_event("incremental_build");
         self.config.status_reporter.build_starts();
 
-        let result: Result<Vec<Diagnostic>> = async {
+        let result: Result<ProjectDiagnostics> = async {
             let had_new_changes = compiler_state.merge_file_source_changes(
                 &self.config,
                 self.perf_logger.as_ref(),
@@ -133,7 +135,7 @@ impl<TPerfLogger: PerfLogger> Compiler<TPerfLogger> {
                 let diagnostics = self.build_projects(compiler_state, &setup_event).await?;
                 Ok(diagnostics)
             } else {
-                Ok(vec![])
+                Ok(ProjectDiagnostics::default())
             }
         }
         .await;
@@ -143,7 +145,7 @@ impl<TPerfLogger: PerfLogger> Compiler<TPerfLogger> {
             Ok(non_fatal_diagnostics) => {
                 self.config
                     .status_reporter
-                    .build_completes(&non_fatal_diagnostics);
+                    .build_completes_for_projects(&non_fatal_diagnostics);
                 Ok(())
             }
             Err(error) => {
@@ -337,7 +339,12 @@ impl<TPerfLogger: PerfLogger> Compiler<TPerfLogger> {
                     let mut red_to_green = RedToGreen::new();
                     match self.build_projects(&mut compiler_state, &setup_event).await {
                         Ok(diagnostics) => {
-                            self.config.status_reporter.build_completes(&diagnostics);
+                            if let Some(build_status) = &self.config.daemon_build_status {
+                                build_status.publish_artifact_maps(&compiler_state.artifacts);
+                            }
+                            self.config
+                                .status_reporter
+                                .build_completes_for_projects(&diagnostics);
                         }
                         Err(err) => {
                             red_to_green.log_error();
@@ -428,6 +435,7 @@ impl<TPerfLogger: PerfLogger> Compiler<TPerfLogger> {
                 // wait_for_idle() blocks through the entire reset cycle.
                 if build_status.take_reset_requested() {
                     info!("Reset requested: reinitializing compiler state from saved state...");
+                    build_status.publish_event(BuildEvent::WatchmanResync);
                     subscription_handle.abort();
                     return None;
                 }
@@ -540,7 +548,22 @@ impl<TPerfLogger: PerfLogger> Compiler<TPerfLogger> {
                 build_status.sync_file_changes(&self.config).await;
             }
 
-            if compiler_state.has_pending_file_source_changes() {
+            // In daemon mode, clients can ask for whole projects to be
+            // regenerated. Marking their artifacts dirty reuses the path
+            // that recompiles externally modified artifacts.
+            let mut rebuild_requested = false;
+            if let Some(build_status) = &self.config.daemon_build_status {
+                for project_name in build_status.take_rebuild_requests() {
+                    if compiler_state.mark_project_artifacts_dirty(project_name) {
+                        rebuild_requested = true;
+                    } else {
+                        warn!("Cannot rebuild project {project_name}: no artifact map available.");
+                        build_status.reject_rebuild(project_name);
+                    }
+                }
+            }
+
+            if rebuild_requested || compiler_state.has_pending_file_source_changes() {
                 let incremental_build_event =
                     self.perf_logger.create_event("incremental_build_event");
                 incremental_build_event
@@ -580,7 +603,12 @@ impl<TPerfLogger: PerfLogger> Compiler<TPerfLogger> {
                         .await
                     {
                         Ok(diagnostics) => {
-                            self.config.status_reporter.build_completes(&diagnostics);
+                            if let Some(build_status) = &self.config.daemon_build_status {
+                                build_status.publish_artifact_maps(&compiler_state.artifacts);
+                            }
+                            self.config
+                                .status_reporter
+                                .build_completes_for_projects(&diagnostics);
                             red_to_green.clear_error_and_log(self.perf_logger.as_ref());
                         }
                         Err(err) => {
@@ -616,7 +644,7 @@ impl<TPerfLogger: PerfLogger> Compiler<TPerfLogger> {
         &self,
         compiler_state: &mut CompilerState,
         setup_event: &impl PerfLogEvent,
-    ) -> Result<Vec<Diagnostic>> {
+    ) -> Result<ProjectDiagnostics> {
         let build_projects_time = setup_event.start("build_projects_time");
         let result = build_projects(
             Arc::clone(&self.config),
@@ -646,7 +674,7 @@ async fn build_projects<TPerfLogger: PerfLogger + 'static>(
     perf_logger: Arc<TPerfLogger>,
     setup_event: &impl PerfLogEvent,
     compiler_state: &mut CompilerState,
-) -> Result<Vec<Diagnostic>> {
+) -> Result<ProjectDiagnostics> {
     let dirty_artifact_sources = compiler_state.get_dirty_artifact_sources(&config);
     let mut graphql_asts = setup_event.time("parse_sources_time", || {
         GraphQLAsts::from_graphql_sources_map(
@@ -803,22 +831,27 @@ async fn build_projects<TPerfLogger: PerfLogger + 'static>(
 
     let commit_all_projects_timer = setup_event.start("commit_all_projects_time");
     let mut build_cancelled_during_commit = false;
-    let mut all_diagnostics: Vec<Diagnostic> = Vec::new();
+    let mut all_diagnostics = ProjectDiagnostics::default();
     for commit_result in join_all(handles).await {
         let commit_result: std::result::Result<std::result::Result<_, _>, _> = commit_result;
-        let mut inner_result = commit_result.map_err(|e| Error::JoinError {
+        let inner_result = commit_result.map_err(|e| Error::JoinError {
             error: e.to_string(),
         })?;
         match inner_result {
-            Ok(((project_name, next_artifact_map, schema), ref mut diagnostics)) => {
+            Ok(((project_name, next_artifact_map, schema), diagnostics)) => {
                 let next_artifact_map = Arc::new(ArtifactMapKind::Mapping(next_artifact_map));
                 compiler_state
                     .artifacts
                     .0
                     .insert(project_name, next_artifact_map);
                 compiler_state.schema_cache.insert(project_name, schema);
+                if let Some(build_status) = &config.daemon_build_status {
+                    build_status.publish_event(BuildEvent::ProjectCompiled {
+                        project: project_name,
+                    });
+                }
 
-                all_diagnostics.append(diagnostics);
+                all_diagnostics.push(project_name, diagnostics);
             }
             Err(BuildProjectFailure::Error(error)) => {
                 errors.push(error);
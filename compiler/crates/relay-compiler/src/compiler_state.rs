@@ -18,6 +18,7 @@ use std::fs::File as FsFile;
 use std::hash::Hash;
 use std::io::BufReader;
 use std::io::BufWriter;
+use std::io::Write;
 use std::path::MAIN_SEPARATOR;
 use std::path::Path;
 use std::path::PathBuf;
@@ -26,6 +27,7 @@ use std::sync::Arc;
 use std::sync::RwLock;
 use std::vec;
 
+use bincode::error::EncodeError;
 use common::PerfLogEvent;
 use common::PerfLogger;
 use common::SourceLocationKey;
@@ -45,6 +47,7 @@ use schema_diff::detect_changes_from_schemas;
 use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
+use zstd::stream::AutoFinishEncoder;
 use zstd::stream::read::Decoder as ZstdDecoder;
 use zstd::stream::write::Encoder as ZstdEncoder;
 
@@ -845,6 +848,25 @@ impl CompilerState {
         self.dirty_artifact_paths.clear();
     }
 
+    /// Mark every artifact of a project as dirty, so that the next build
+    /// regenerates all of them. Returns `false` when the project has no
+    /// mapping from sources to artifacts to regenerate from.
+    pub fn mark_project_artifacts_dirty(&mut self, project_name: ProjectName) -> bool {
+        let Some(artifacts) = self.artifacts.0.get(&project_name) else {
+            return false;
+        };
+        let ArtifactMapKind::Mapping(artifacts) = &**artifacts else {
+            return false;
+        };
+        let dirty_paths = self.dirty_artifact_paths.entry(project_name).or_default();
+        for entry in artifacts.0.iter() {
+            for artifact_record in entry.value() {
+                dirty_paths.insert(artifact_record.path.clone());
+            }
+        }
+        true
+    }
+
     /// Calculate dirty definitions/sources from dirty artifacts
     pub fn get_dirty_artifact_sources(
         &self,
@@ -889,35 +911,7 @@ impl CompilerState {
     }
 
     pub fn serialize_to_file(&self, path: &PathBuf) -> Result<()> {
-        let zstd_level: i32 = env::var("RELAY_SAVED_STATE_ZSTD_LEVEL").map_or_else(
-            |_| 12,
-            |level| {
-                level.parse::<i32>().expect(
-                    "Expected RELAY_SAVED_STATE_ZSTD_LEVEL environment variable to be a number.",
-                )
-            },
-        );
-
-        let writer = FsFile::create(path)
-            .and_then(|writer| {
-                let mut encoder = ZstdEncoder::new(writer, zstd_level)?;
-                match u32::try_from(std::thread::available_parallelism()?.get()) {
-                    Ok(threads) => {
-                        debug!("Using {threads} zstd threads");
-                        encoder.multithread(threads).ok();
-                    }
-                    Err(_) => {
-                        debug!("Using single-threaded zstd");
-                    }
-                }
-                Ok(encoder)
-            })
-            .map_err(|err| Error::WriteFileError {
-                file: path.clone(),
-                source: err,
-            })?
-            .auto_finish();
-
+        let writer = create_saved_state_writer(path)?;
         let mut writer =
             BufWriter::with_capacity(ZstdEncoder::<FsFile>::recommended_input_size(), writer);
         bincode::serde::encode_into_std_write(self, &mut writer, bincode::config::legacy())
@@ -928,6 +922,23 @@ impl CompilerState {
             })
     }
 
+    /// Serializes the state without compressing it, for
+    /// `write_serialized_to_file` to write it on another thread.
+    pub fn serialize_to_bytes(&self) -> std::result::Result<Vec<u8>, EncodeError> {
+        bincode::serde::encode_to_vec(self, bincode::config::legacy())
+    }
+
+    /// Writes a state serialized with `serialize_to_bytes` to a file that
+    /// `deserialize_from_file` can read.
+    pub fn write_serialized_to_file(serialized: &[u8], path: &PathBuf) -> Result<()> {
+        create_saved_state_writer(path)?
+            .write_all(serialized)
+            .map_err(|err| Error::WriteFileError {
+                file: path.clone(),
+                source: err,
+            })
+    }
+
     pub fn deserialize_from_file(path: &PathBuf) -> Result<Self> {
         let is_already_decompressed = path
             .to_str()
@@ -998,6 +1009,39 @@ impl CompilerState {
     }
 }
 
+/// Creates the file of a saved state, compressed at the level of the
+/// `RELAY_SAVED_STATE_ZSTD_LEVEL` environment variable.
+fn create_saved_state_writer(path: &PathBuf) -> Result<AutoFinishEncoder<'static, FsFile>> {
+    let zstd_level: i32 = env::var("RELAY_SAVED_STATE_ZSTD_LEVEL").map_or_else(
+        |_| 12,
+        |level| {
+            level.parse::<i32>().expect(
+                "Expected RELAY_SAVED_STATE_ZSTD_LEVEL environment variable to be a number.",
+            )
+        },
+    );
+
+    FsFile::create(path)
+        .and_then(|writer| {
+            let mut encoder = ZstdEncoder::new(writer, zstd_level)?;
+            match u32::try_from(std::thread::available_parallelism()?.get()) {
+                Ok(threads) => {
+                    debug!("Using {threads} zstd threads");
+                    encoder.multithread(threads).ok();
+                }
+                Err(_) => {
+                    debug!("Using single-threaded zstd");
+                }
+            }
+            Ok(encoder)
+        })
+        .map_err(|err| Error::WriteFileError {
+            file: path.clone(),
+            source: err,
+        })
+        .map(ZstdEncoder::auto_finish)
+}
+
 fn process_intermediate_schema_change(
     file_source_changes: &FileSourceResult,
     files: Vec<File>,
@@ -41,6 +41,7 @@ use relay_config::CustomType;
 use relay_config::DiagnosticReportConfig;
 pub use relay_config::ExtraArtifactsConfig;
 use relay_config::JsModuleFormat;
+use relay_config::LintRulesConfig;
 pub use relay_config::LocalPersistConfig;
 use relay_config::ModuleImportConfig;
 pub use relay_config::PersistConfig;
@@ -310,6 +311,8 @@ pub struct Config {
     /// Optional name for this config. This might be used by compiler extension
     /// code like logging or extra artifact generation.
     pub name: Option<String>,
+    /// Path of the config file that the config was loaded from.
+    pub config_path: PathBuf,
     /// Root directory of all projects to compile. Any other paths in the
     /// compiler should be relative to this root unless otherwise noted.
     pub root_dir: PathBuf,
@@ -335,6 +338,11 @@ pub struct Config {
     /// state from `(saved_state + changed_files)`. Subsequent iterations fall
     /// back to the normal Watchman path. Consume-once via Mutex&lt;Option&gt;.
     pub initial_external_changed_files_list: Mutex<Option<PathBuf>>,
+    /// If set, the language server restores its compiler state from this file
+    /// on startup, and saves its compiler state to it after successful builds,
+    /// so that a restarted server only processes the files that changed since
+    /// the state was saved.
+    pub lsp_saved_state_file: Option<PathBuf>,
     /// Function to generate extra
     pub generate_extra_artifacts: Option<GenerateExtraArtifactsFn>,
     pub generate_virtual_id_file_name: Option<GenerateVirtualIdFieldName>,
@@ -770,6 +778,7 @@ impl Config {
                     relativize_js_module_paths: config_file_project.relativize_js_module_paths,
                     module_import_config: config_file_project.module_import_config,
                     diagnostic_report_config: config_file_project.diagnostic_report_config,
+                    lint_rules: config_file_project.lint_rules,
                     resolvers_schema_module: config_file_project.resolvers_schema_module,
                     codegen_command: config_file_project.codegen_command,
                     get_custom_path_for_artifact: None,
@@ -792,6 +801,7 @@ impl Config {
                 is_multi_project,
             )),
             daemon_build_status: None,
+            config_path: config_path.clone(),
             root_dir,
             sources: config_file.sources,
             excludes: config_file.excludes,
@@ -802,6 +812,7 @@ impl Config {
             codegen_command: config_file.codegen_command,
             load_saved_state_file: Mutex::new(None),
             initial_external_changed_files_list: Mutex::new(None),
+            lsp_saved_state_file: None,
             generate_extra_artifacts: None,
             generate_virtual_id_file_name: None,
             get_artifacts_file_hash_map: None,
@@ -1281,6 +1292,7 @@ impl fmt::Debug for Config {
         let Config {
             name,
             artifact_writer: _,
+            config_path,
             root_dir,
             sources,
             excludes,
@@ -1292,6 +1304,7 @@ impl fmt::Debug for Config {
             codegen_command,
             load_saved_state_file,
             initial_external_changed_files_list,
+            lsp_saved_state_file,
             generate_extra_artifacts,
             saved_state_config,
             saved_state_loader,
@@ -1307,6 +1320,7 @@ impl fmt::Debug for Config {
 
         f.debug_struct("Config")
             .field("name", name)
+            .field("config_path", config_path)
             .field("root_dir", root_dir)
             .field("sources", sources)
             .field("excludes", excludes)
@@ -1327,6 +1341,7 @@ impl fmt::Debug for Config {
                     .map(|guard| guard.clone())
                     .ok(),
             )
+            .field("lsp_saved_state_file", lsp_saved_state_file)
             .field("saved_state_config", saved_state_config)
             .field(
                 "create_operation_persister",
@@ -1779,6 +1794,11 @@ pub struct ConfigFileProject {
     #[serde(default)]
     pub diagnostic_report_config: DiagnosticReportConfig,
 
+    /// Optional lint rules, reported as diagnostics with the configured
+    /// severity.
+    #[serde(default)]
+    pub lint_rules: LintRulesConfig,
+
     #[serde(default)]
     pub resolvers_schema_module: Option<ResolversSchemaModuleConfig>,
 
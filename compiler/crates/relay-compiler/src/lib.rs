@@ -23,7 +23,6 @@ mod graphql_asts;
 mod operation_persister;
 mod path_validator;
 mod red_to_green;
-#[cfg(unix)]
 pub mod server_daemon;
 pub mod status_reporter;
 pub mod subschema_extraction;
@@ -36,12 +35,12 @@ pub use build_project::ArtifactContent;
 pub use build_project::ArtifactGeneratedTypes;
 pub use build_project::BuildProjectFailure;
 pub use build_project::SourceHashes;
+pub use build_project::artifact_writer::ArtifactChangeReport;
+pub use build_project::artifact_writer::ArtifactDryRunWriter;
 pub use build_project::artifact_writer::ArtifactFileWriter;
 pub use build_project::artifact_writer::ArtifactValidationWriter;
 pub use build_project::artifact_writer::ArtifactWriter;
-#[cfg(unix)]
 pub use build_project::artifact_writer::DeferredArtifactCache;
-#[cfg(unix)]
 pub use build_project::artifact_writer::DeferredArtifactWriter;
 pub use build_project::artifact_writer::NoopArtifactWriter;
 pub use build_project::build_programs;
@@ -84,6 +83,7 @@ pub use file_source::source_for_location;
 pub use get_programs::assert_programs;
 pub use get_programs::get_programs;
 pub use graphql_asts::GraphQLAsts;
+pub use operation_persister::DryRunPersister;
 pub use operation_persister::LocalPersister;
 pub use operation_persister::RemotePersister;
 pub use operation_persister::persist_params;
@@ -5,9 +5,11 @@
  * LICENSE file in the root directory of this source tree.
  */
 
+mod dry_run_persister;
 mod local_persister;
 mod remote_persister;
 
+pub use dry_run_persister::DryRunPersister;
 pub use local_persister::LocalPersister;
 pub use remote_persister::RemotePersister;
 pub use remote_persister::persist_params;
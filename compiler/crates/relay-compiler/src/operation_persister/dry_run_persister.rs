@@ -0,0 +1,96 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use async_trait::async_trait;
+use md5::Digest;
+use md5::Md5;
+use persist_query::PersistError;
+use relay_config::PersistConfig;
+
+use super::LocalPersister;
+use crate::OperationPersister;
+use crate::config::ArtifactForPersister;
+
+/// A persister for dry runs, that stands in for the configured persister of
+/// a project without side effects: it neither rewrites the local persist
+/// file nor sends operations to the remote server.
+///
+/// Local ids are computed as the `LocalPersister` would compute them. Remote
+/// ids are only known to the server, so operations that would be persisted
+/// remotely get a placeholder id derived from their text.
+pub struct DryRunPersister {
+    local_persister: Option<LocalPersister>,
+}
+
+impl DryRunPersister {
+    pub fn new(config: &PersistConfig) -> Self {
+        let local_persister = match config {
+            PersistConfig::Local(local_config) => Some(LocalPersister::new(local_config.clone())),
+            PersistConfig::Remote(_) => None,
+        };
+        Self { local_persister }
+    }
+}
+
+#[async_trait]
+impl OperationPersister for DryRunPersister {
+    async fn persist_artifact(
+        &self,
+        artifact: ArtifactForPersister,
+    ) -> Result<String, PersistError> {
+        match &self.local_persister {
+            // Only adds the operation to the in-memory map of the persister.
+            Some(local_persister) => local_persister.persist_artifact(artifact).await,
+            None => {
+                let mut md5 = Md5::new();
+                md5.update(&artifact.text);
+                Ok(format!("dry-run:{}", hex::encode(md5.finalize())))
+            }
+        }
+    }
+
+    fn finalize(&self) -> Result<(), PersistError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use relay_config::LocalPersistAlgorithm;
+    use relay_config::LocalPersistConfig;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_local_persist_file_is_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("persisted_queries.json");
+        let content = "{\n  \"existing\": \"query Existing { me { id } }\"\n}\n";
+        std::fs::write(&file, content).unwrap();
+
+        let persister = DryRunPersister::new(&PersistConfig::Local(LocalPersistConfig {
+            file: file.clone(),
+            algorithm: LocalPersistAlgorithm::MD5,
+            include_query_text: false,
+        }));
+        let id = persister
+            .persist_artifact(ArtifactForPersister {
+                text: "query Test { me { name } }".to_string(),
+                relative_path: PathBuf::from("__generated__/TestQuery.graphql.js"),
+                override_schema: None,
+                schema_text: None,
+            })
+            .await
+            .unwrap();
+        persister.finalize().unwrap();
+
+        assert_eq!(id.len(), 32);
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), content);
+    }
+}
@@ -7,13 +7,17 @@
 
 //! Server daemon module for the Relay compiler.
 //!
-//! Provides a Unix domain socket daemon that holds compilation state in
-//! memory and lets clients request a flush-to-disk on demand instead of
-//! writing artifacts on every build. Unix-only.
+//! Provides a daemon that holds compilation state in memory and lets clients
+//! request a flush-to-disk on demand instead of writing artifacts on every
+//! build. The daemon listens on a Unix domain socket and, optionally, on
+//! loopback TCP (see [`transport`]); a single daemon can serve several config
+//! roots. Where there are no Unix sockets, TCP is the only transport, and
+//! clients have to connect to it explicitly.
 
 mod handlers;
 pub mod protocol;
 pub mod socket;
+pub mod transport;
 pub mod vcs_state;
 
 use std::collections::hash_map::DefaultHasher;
@@ -22,6 +26,7 @@ use std::fs;
 use std::fs::File;
 use std::hash::Hash;
 use std::hash::Hasher;
+#[cfg(unix)]
 use std::os::unix::net::UnixStream;
 use std::path::Path;
 use std::path::PathBuf;
@@ -36,10 +41,15 @@ use log::warn;
 use serde::Deserialize;
 use serde::Serialize;
 pub use socket::send_request;
+pub use socket::send_request_to;
 pub use socket::start_server;
+pub use socket::subscribe;
 
 use crate::ArtifactWriter;
 use crate::config::Config;
+use crate::server_daemon::protocol::BuildMessage;
+use crate::server_daemon::protocol::BuildOutcome;
+use crate::server_daemon::protocol::DaemonEvent;
 use crate::server_daemon::protocol::DaemonRequest;
 use crate::server_daemon::protocol::DaemonResponse;
 use crate::server_daemon::protocol::MessageSeverity;
@@ -126,6 +136,14 @@ pub struct DaemonMetadata {
     /// checks or shutdown. Useful for debugging (e.g. `kill -0` or `strace`).
     #[serde(default)]
     pub pid: u32,
+    /// Configuration files of the other roots this daemon serves, besides
+    /// `config_path`.
+    #[serde(default)]
+    pub additional_config_paths: Vec<PathBuf>,
+    /// Loopback TCP port the daemon also listens on, if any. Clients find
+    /// the access token in the file at [`transport::token_path_for_socket`].
+    #[serde(default)]
+    pub tcp_port: Option<u16>,
 }
 
 impl DaemonMetadata {
@@ -152,6 +170,7 @@ impl DaemonMetadata {
     ///
     /// Attempts a connection to the socket path to determine if a process
     /// is actively listening. This avoids PID-recycling issues.
+    #[cfg(unix)]
     pub fn status(&self) -> DaemonStatus {
         if !self.socket_path.exists() {
             return DaemonStatus::Gone;
@@ -162,6 +181,19 @@ impl DaemonMetadata {
         }
     }
 
+    /// Check whether the daemon's TCP port is active, the only transport
+    /// where there are no Unix sockets.
+    #[cfg(not(unix))]
+    pub fn status(&self) -> DaemonStatus {
+        let Some(port) = self.tcp_port else {
+            return DaemonStatus::Gone;
+        };
+        match std::net::TcpStream::connect((std::net::Ipv4Addr::LOCALHOST, port)) {
+            Ok(_) => DaemonStatus::Active,
+            Err(_) => DaemonStatus::Stale,
+        }
+    }
+
     /// Return all directories where daemon files could have been written.
     ///
     /// Always includes `/tmp`. If `XDG_RUNTIME_DIR` is set and differs from
@@ -221,7 +253,8 @@ impl DaemonMetadata {
         Ok(())
     }
 
-    /// Clean up all files associated with this daemon: metadata, socket, and log.
+    /// Clean up all files associated with this daemon: metadata, socket,
+    /// token, and log.
     ///
     /// Errors are logged at debug level but do not prevent other files from
     /// being cleaned up.
@@ -234,6 +267,12 @@ impl DaemonMetadata {
         {
             log::debug!("Failed to remove socket file: {}", e);
         }
+        let token_path = transport::token_path_for_socket(&self.socket_path);
+        if token_path.exists()
+            && let Err(e) = fs::remove_file(&token_path)
+        {
+            log::debug!("Failed to remove token file: {}", e);
+        }
         let log_path = log_path_for_socket(&self.socket_path);
         if log_path.exists()
             && let Err(e) = fs::remove_file(&log_path)
@@ -283,7 +322,7 @@ pub async fn list_daemons(cleanup: bool, shutdown: bool) -> std::io::Result<()>
             DaemonStatus::Stale => "stale",
             DaemonStatus::Gone => "gone",
         };
-        println!(
+        let mut line = format!(
             "[{}] pid={} socket={} config={} projects=[{}] version={}",
             status_str,
             metadata.pid,
@@ -292,6 +331,18 @@ pub async fn list_daemons(cleanup: bool, shutdown: bool) -> std::io::Result<()>
             metadata.projects.join(", "),
             metadata.compiler_version,
         );
+        if let Some(port) = metadata.tcp_port {
+            line.push_str(&format!(" tcp=127.0.0.1:{port}"));
+        }
+        if !metadata.additional_config_paths.is_empty() {
+            let additional: Vec<_> = metadata
+                .additional_config_paths
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect();
+            line.push_str(&format!(" additional_configs=[{}]", additional.join(", ")));
+        }
+        println!("{line}");
         if shutdown && matches!(status, DaemonStatus::Active) {
             info!(
                 "Shutting down active daemon: {}",
@@ -546,20 +597,9 @@ pub async fn ensure_daemon_running(
 pub fn log_daemon_response(response: Option<DaemonResponse>) -> bool {
     match response {
         Some(DaemonResponse::Success { result }) => match result {
-            ResponseResult::WriteAck { messages } => {
-                let mut has_error = false;
-                for msg in &messages {
-                    match msg.severity {
-                        MessageSeverity::Error => {
-                            error!("{}", msg.text);
-                            has_error = true;
-                        }
-                        MessageSeverity::Warning => warn!("{}", msg.text),
-                        MessageSeverity::Info => info!("{}", msg.text),
-                    }
-                }
-                !has_error
-            }
+            ResponseResult::WriteAck { messages }
+            | ResponseResult::Diagnostics { messages }
+            | ResponseResult::RebuildAck { messages } => log_build_messages(&messages),
             ResponseResult::Version { compiler_version } => {
                 println!("{}", compiler_version);
                 true
@@ -568,6 +608,38 @@ pub fn log_daemon_response(response: Option<DaemonResponse>) -> bool {
                 info!("Daemon shut down successfully.");
                 true
             }
+            ResponseResult::Subscribed => true,
+            ResponseResult::Status {
+                is_building,
+                last_build,
+                dirty_artifact_count,
+            } => {
+                let last_build = match last_build {
+                    Some(BuildOutcome::Success) => "success",
+                    Some(BuildOutcome::Errors) => "errors",
+                    Some(BuildOutcome::Restarting) => "restarting",
+                    None => "none",
+                };
+                println!(
+                    "building: {is_building}, last build: {last_build}, dirty artifacts: {dirty_artifact_count}"
+                );
+                true
+            }
+            ResponseResult::DirtyArtifacts { written, removed } => {
+                for path in &written {
+                    println!("M {}", path.display());
+                }
+                for path in &removed {
+                    println!("D {}", path.display());
+                }
+                true
+            }
+            ResponseResult::ArtifactsForSource { artifacts } => {
+                for artifact in &artifacts {
+                    println!("{} {}", artifact.project, artifact.path.display());
+                }
+                true
+            }
         },
         Some(DaemonResponse::Error { code, message }) => {
             error!("Error ({code:?}): {message}");
@@ -580,8 +652,34 @@ pub fn log_daemon_response(response: Option<DaemonResponse>) -> bool {
     }
 }
 
+/// Print a streamed daemon event to stdout as a single line of JSON.
+pub fn print_daemon_event(event: DaemonEvent) {
+    match serde_json::to_string(&event) {
+        Ok(json) => println!("{json}"),
+        Err(e) => error!("Failed to serialize daemon event {event:?}: {e}"),
+    }
+}
+
+/// Log build messages at their severity. Returns `false` if any of them
+/// is an error.
+fn log_build_messages(messages: &[BuildMessage]) -> bool {
+    let mut has_error = false;
+    for msg in messages {
+        match msg.severity {
+            MessageSeverity::Error => {
+                error!("{}", msg.text);
+                has_error = true;
+            }
+            MessageSeverity::Warning => warn!("{}", msg.text),
+            MessageSeverity::Info => info!("{}", msg.text),
+        }
+    }
+    !has_error
+}
+
 #[cfg(test)]
 mod tests {
+    #[cfg(unix)]
     use std::os::unix::net::UnixListener;
 
     use super::*;
@@ -632,6 +730,8 @@ mod tests {
             projects,
             compiler_version: "abc123".to_string(),
             pid: std::process::id(),
+            additional_config_paths: vec![],
+            tcp_port: None,
         }
     }
 
@@ -726,6 +826,7 @@ mod tests {
         assert_eq!(metadata.status(), DaemonStatus::Gone);
     }
 
+    #[cfg(unix)]
     #[test]
     fn test_status_stale_when_socket_file_exists_but_no_listener() {
         let dir = tempfile::tempdir().unwrap();
@@ -739,6 +840,7 @@ mod tests {
         assert_eq!(metadata.status(), DaemonStatus::Stale);
     }
 
+    #[cfg(unix)]
     #[test]
     fn test_status_active_when_listener_bound() {
         let dir = tempfile::tempdir().unwrap();
@@ -795,6 +897,7 @@ mod tests {
         metadata.cleanup();
     }
 
+    #[cfg(unix)]
     #[test]
     fn test_cleanup_stale_and_gone_via_list() {
         let dir = tempfile::tempdir().unwrap();
@@ -9,21 +9,37 @@
 //!
 //! Each handler processes a specific request type and returns an appropriate response.
 
+use std::collections::BTreeSet;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use common::SourceLocationKey;
+use docblock_shared::ResolverSourceHash;
+use extract_graphql::JavaScriptSourceFeature;
+use graphql_ir::ExecutableDefinitionName;
+use graphql_ir::FragmentDefinitionName;
+use graphql_ir::OperationDefinitionName;
+use graphql_syntax::ExecutableDefinition;
+use intern::string_key::Intern;
 use log::error;
 use log::info;
 use log::warn;
+use relay_config::ProjectName;
 use tokio::sync::Notify;
 
+use crate::artifact_map::ArtifactSourceKey;
 use crate::build_project::artifact_writer::DeferredArtifactCache;
+use crate::compiler_state::ArtifactMapKind;
 use crate::config::Config;
 use crate::server_daemon::FlushWriterFactory;
 use crate::server_daemon::protocol::BuildMessage;
+use crate::server_daemon::protocol::BuildOutcome;
 use crate::server_daemon::protocol::DaemonResponse;
 use crate::server_daemon::protocol::ErrorCode;
 use crate::server_daemon::protocol::ResponseResult;
+use crate::server_daemon::protocol::SourceArtifact;
+use crate::status_reporter::BuildEvent;
 use crate::status_reporter::BuildResult;
 use crate::status_reporter::BuildStatus;
 use crate::status_reporter::WatchmanSyncOutcome;
@@ -129,6 +145,9 @@ pub async fn handle_write(
     match flush_result {
         Ok(count) => {
             info!("Successfully flushed {} artifacts", count);
+            if count > 0 {
+                build_status.publish_event(BuildEvent::ArtifactsWritten { count });
+            }
 
             let extra_info = if build_result.is_ok() && count == 0 {
                 " (no changes since last write)"
@@ -164,3 +183,217 @@ pub fn handle_shutdown(shutdown_signal: &Arc<Notify>) -> DaemonResponse {
     shutdown_signal.notify_waiters();
     DaemonResponse::success(ResponseResult::ShutdownAck)
 }
+
+/// Handle Subscribe request. The acknowledgement is all there is to send
+/// here; the connection handler streams the events that follow.
+pub fn handle_subscribe() -> DaemonResponse {
+    DaemonResponse::success(ResponseResult::Subscribed)
+}
+
+/// Handle Status request. Answers from the current state without waiting
+/// for an ongoing build, and without consuming the stored build result.
+pub fn handle_status(
+    artifact_cache: &Arc<DeferredArtifactCache>,
+    build_status: &Arc<BuildStatus>,
+) -> DaemonResponse {
+    let last_build = build_status
+        .peek_build_result()
+        .map(|build_result| match build_result {
+            BuildResult::Success(_) => BuildOutcome::Success,
+            BuildResult::Errors(_) => BuildOutcome::Errors,
+            BuildResult::Restarting(_) => BuildOutcome::Restarting,
+        });
+    let (written, removed) = artifact_cache.pending_artifacts();
+    DaemonResponse::success(ResponseResult::Status {
+        is_building: build_status.is_building(),
+        last_build,
+        dirty_artifact_count: written.len() + removed.len(),
+    })
+}
+
+/// Handle Diagnostics request. Waits for any ongoing build to finish, then
+/// reports its diagnostics. Cached artifacts are not flushed and the build
+/// result is left for the next Write.
+pub async fn handle_diagnostics(build_status: &Arc<BuildStatus>) -> DaemonResponse {
+    build_status.wait_for_idle().await;
+    DaemonResponse::success(ResponseResult::Diagnostics {
+        messages: build_messages(build_status.peek_build_result()),
+    })
+}
+
+/// Handle DirtyArtifacts request, listing cached operations not yet flushed.
+pub fn handle_dirty_artifacts(artifact_cache: &Arc<DeferredArtifactCache>) -> DaemonResponse {
+    let (written, removed) = artifact_cache.pending_artifacts();
+    DaemonResponse::success(ResponseResult::DirtyArtifacts { written, removed })
+}
+
+/// Handle ArtifactsForSource request.
+///
+/// Reads the source file from disk and looks up the definitions and
+/// resolver docblocks it contains in the artifact maps of the last
+/// committed build, so the answer reflects what is on disk now and what
+/// the daemon last compiled.
+pub fn handle_artifacts_for_source(
+    build_status: &Arc<BuildStatus>,
+    config: &Arc<Config>,
+    path: PathBuf,
+) -> DaemonResponse {
+    let absolute_path = config.root_dir.join(&path);
+    let contents = match std::fs::read_to_string(&absolute_path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            return DaemonResponse::error(
+                ErrorCode::SourceNotReadable,
+                format!("Unable to read {}: {}", absolute_path.display(), e),
+            );
+        }
+    };
+    let source_keys = artifact_source_keys_for_source(&path, &contents);
+
+    let mut artifacts = BTreeSet::new();
+    for (project_name, artifact_map) in build_status.artifact_maps() {
+        if let ArtifactMapKind::Mapping(artifact_map) = &*artifact_map {
+            for source_key in &source_keys {
+                if let Some(records) = artifact_map.0.get(source_key) {
+                    for record in records.iter() {
+                        artifacts.insert((project_name.to_string(), record.path.clone()));
+                    }
+                }
+            }
+        }
+    }
+
+    DaemonResponse::success(ResponseResult::ArtifactsForSource {
+        artifacts: artifacts
+            .into_iter()
+            .map(|(project, path)| SourceArtifact { project, path })
+            .collect(),
+    })
+}
+
+/// Handle Rebuild request. Asks the build loop to regenerate every artifact
+/// of the given projects and waits for that build to finish.
+pub async fn handle_rebuild(
+    build_status: &Arc<BuildStatus>,
+    config: &Arc<Config>,
+    projects: Vec<String>,
+) -> DaemonResponse {
+    let mut project_names = Vec::with_capacity(projects.len());
+    for project in projects {
+        let project_name = ProjectName::from(project.as_str().intern());
+        if !config.projects.contains_key(&project_name) {
+            return DaemonResponse::error(
+                ErrorCode::UnknownProject,
+                format!("Unknown project: {project}"),
+            );
+        }
+        project_names.push(project_name);
+    }
+    if project_names.is_empty() {
+        project_names.extend(
+            config
+                .enabled_projects()
+                .map(|project_config| project_config.name),
+        );
+    }
+
+    // Rejections of an earlier request must not fail this one.
+    build_status.take_rejected_rebuilds(&project_names);
+    build_status.request_rebuild(project_names.iter().copied());
+    // The build loop may be busy with an unrelated build when the request
+    // arrives, in which case that build's completion unblocks us first.
+    // Keep waking the loop until it has picked up the request.
+    let mut picked_up = false;
+    for _attempt in 0..=MAX_FRESHNESS_RETRIES {
+        build_status.changes_pending();
+        build_status.notify_build_loop().await;
+        build_status.wait_for_idle().await;
+        if !build_status.has_pending_rebuild_requests() {
+            picked_up = true;
+            break;
+        }
+    }
+    if !picked_up {
+        return DaemonResponse::error(
+            ErrorCode::RebuildFailed,
+            "The build loop did not pick up the rebuild request. Retry this command.",
+        );
+    }
+
+    let rejected_rebuilds = build_status.take_rejected_rebuilds(&project_names);
+    if !rejected_rebuilds.is_empty() {
+        return DaemonResponse::error(
+            ErrorCode::RebuildFailed,
+            format!(
+                "Cannot rebuild {}: no artifact map available. Wait for the initial build to complete and retry this command.",
+                rejected_rebuilds
+                    .iter()
+                    .map(|project_name| format!("project {project_name}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        );
+    }
+
+    DaemonResponse::success(ResponseResult::RebuildAck {
+        messages: build_messages(build_status.peek_build_result()),
+    })
+}
+
+fn build_messages(build_result: Option<BuildResult>) -> Vec<BuildMessage> {
+    match build_result {
+        Some(BuildResult::Success(diagnostics) | BuildResult::Errors(diagnostics)) => diagnostics
+            .into_iter()
+            .map(|(severity, msg)| BuildMessage::from_diagnostic(severity, msg))
+            .collect(),
+        Some(BuildResult::Restarting(reason)) => vec![BuildMessage::info(format!(
+            "Daemon restarting to pick up new binary/config: {reason}. Retry this command."
+        ))],
+        None => vec![],
+    }
+}
+
+/// The keys under which artifacts generated from a source file are recorded:
+/// its named GraphQL definitions, its resolver docblocks, and the file as a
+/// whole for resolvers extracted from full sources.
+fn artifact_source_keys_for_source(path: &Path, contents: &str) -> Vec<ArtifactSourceKey> {
+    let mut source_keys = vec![ArtifactSourceKey::ResolverHash(ResolverSourceHash::new(
+        contents,
+    ))];
+    for (index, feature) in extract_graphql::extract(contents).into_iter().enumerate() {
+        match feature {
+            JavaScriptSourceFeature::GraphQL(graphql_source) => {
+                let source_location = SourceLocationKey::embedded(&path.to_string_lossy(), index);
+                let Ok(document) = graphql_syntax::parse_executable(
+                    &graphql_source.text_source().text,
+                    source_location,
+                ) else {
+                    continue;
+                };
+                for definition in document.definitions {
+                    let name = match definition {
+                        ExecutableDefinition::Operation(operation) => operation.name.map(|name| {
+                            ExecutableDefinitionName::OperationDefinitionName(
+                                OperationDefinitionName(name.value),
+                            )
+                        }),
+                        ExecutableDefinition::Fragment(fragment) => {
+                            Some(ExecutableDefinitionName::FragmentDefinitionName(
+                                FragmentDefinitionName(fragment.name.value),
+                            ))
+                        }
+                    };
+                    if let Some(name) = name {
+                        source_keys.push(ArtifactSourceKey::ExecutableDefinition(name));
+                    }
+                }
+            }
+            JavaScriptSourceFeature::Docblock(docblock_source) => {
+                source_keys.push(ArtifactSourceKey::ResolverHash(ResolverSourceHash::new(
+                    &docblock_source.text_source().text,
+                )));
+            }
+        }
+    }
+    source_keys
+}
@@ -8,7 +8,7 @@
 //! Protocol types for the server daemon.
 //!
 //! Defines the request and response message types used for communication
-//! between clients and the server daemon over Unix domain sockets.
+//! between clients and the server daemon over any of its transports.
 
 use std::path::PathBuf;
 
@@ -16,6 +16,8 @@ use common::DiagnosticSeverity;
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::status_reporter::BuildEvent;
+
 /// Request messages from client to daemon.
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(tag = "method", content = "params", rename_all = "snake_case")]
@@ -35,6 +37,61 @@ pub enum DaemonRequest {
     Version,
     /// Request graceful shutdown.
     Shutdown,
+    /// Report whether a build is in progress and how the last one ended.
+    /// Answers immediately, without waiting for an ongoing build.
+    Status,
+    /// Wait for any ongoing build to finish and return its diagnostics.
+    /// Unlike `Write`, cached artifacts are left in place.
+    Diagnostics,
+    /// List the artifact writes and removals cached since the last flush.
+    DirtyArtifacts,
+    /// List the artifacts generated from a source file. A relative `path`
+    /// is resolved against the config root.
+    ArtifactsForSource { path: PathBuf },
+    /// Regenerate every artifact of the named projects, even if none of
+    /// their sources changed, and wait for the build to finish. Results are
+    /// cached like any other build until the next `Write`.
+    Rebuild { projects: Vec<String> },
+    /// Keep the connection open and stream a [`DaemonEvent`] per line after
+    /// the `Subscribed` acknowledgement, until either side disconnects.
+    Subscribe,
+}
+
+/// A request together with the fields needed to authenticate and route it.
+///
+/// Clients talking to a single-root daemon over its Unix socket may send a
+/// bare [`DaemonRequest`] instead.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RequestEnvelope {
+    /// Token from the daemon's token file. Required over TCP.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// Config file of the root the request is for. Defaults to the root
+    /// the daemon was started with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_path: Option<PathBuf>,
+    pub request: DaemonRequest,
+}
+
+/// Any request line a daemon accepts.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum IncomingRequest {
+    Envelope(RequestEnvelope),
+    Bare(DaemonRequest),
+}
+
+impl IncomingRequest {
+    pub fn into_envelope(self) -> RequestEnvelope {
+        match self {
+            IncomingRequest::Envelope(envelope) => envelope,
+            IncomingRequest::Bare(request) => RequestEnvelope {
+                token: None,
+                config_path: None,
+                request,
+            },
+        }
+    }
 }
 
 /// Error codes for daemon error responses.
@@ -42,6 +99,11 @@ pub enum DaemonRequest {
 #[serde(rename_all = "snake_case")]
 pub enum ErrorCode {
     FlushFailed,
+    UnknownProject,
+    SourceNotReadable,
+    Unauthorized,
+    UnknownRoot,
+    RebuildFailed,
 }
 
 /// Response messages from daemon to client
@@ -70,9 +132,94 @@ impl DaemonResponse {
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ResponseResult {
-    WriteAck { messages: Vec<BuildMessage> },
-    Version { compiler_version: String },
+    WriteAck {
+        messages: Vec<BuildMessage>,
+    },
+    Version {
+        compiler_version: String,
+    },
     ShutdownAck,
+    Status {
+        is_building: bool,
+        /// `None` until the first build finishes, or after a successful
+        /// build has been consumed by a `Write`.
+        last_build: Option<BuildOutcome>,
+        dirty_artifact_count: usize,
+    },
+    Diagnostics {
+        messages: Vec<BuildMessage>,
+    },
+    DirtyArtifacts {
+        written: Vec<PathBuf>,
+        removed: Vec<PathBuf>,
+    },
+    ArtifactsForSource {
+        artifacts: Vec<SourceArtifact>,
+    },
+    RebuildAck {
+        messages: Vec<BuildMessage>,
+    },
+    Subscribed,
+}
+
+/// Events streamed to clients of a `Subscribe` request.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum DaemonEvent {
+    BuildStarted,
+    ProjectCompiled {
+        project: String,
+    },
+    DiagnosticsChanged {
+        messages: Vec<BuildMessage>,
+    },
+    ArtifactsWritten {
+        count: usize,
+    },
+    WatchmanResync,
+    /// The daemon is shutting down; the connection closes after this event.
+    DaemonStopping,
+    /// The client read too slowly and `skipped` events were dropped.
+    Lagged {
+        skipped: u64,
+    },
+}
+
+impl From<BuildEvent> for DaemonEvent {
+    fn from(event: BuildEvent) -> Self {
+        match event {
+            BuildEvent::BuildStarted => DaemonEvent::BuildStarted,
+            BuildEvent::ProjectCompiled { project } => DaemonEvent::ProjectCompiled {
+                project: project.to_string(),
+            },
+            BuildEvent::DiagnosticsChanged(diagnostics) => DaemonEvent::DiagnosticsChanged {
+                messages: diagnostics
+                    .into_iter()
+                    .map(|(severity, text)| BuildMessage::from_diagnostic(severity, text))
+                    .collect(),
+            },
+            BuildEvent::ArtifactsWritten { count } => DaemonEvent::ArtifactsWritten { count },
+            BuildEvent::WatchmanResync => DaemonEvent::WatchmanResync,
+            BuildEvent::DaemonStopping => DaemonEvent::DaemonStopping,
+        }
+    }
+}
+
+/// How the most recent build ended, as reported by a `Status` request.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BuildOutcome {
+    Success,
+    Errors,
+    Restarting,
+}
+
+/// An artifact generated from the source file of an `ArtifactsForSource`
+/// request. `path` is relative to the config root.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SourceArtifact {
+    pub project: String,
+    pub path: PathBuf,
 }
 
 /// A single build output message with its severity level.
@@ -5,26 +5,33 @@
  * LICENSE file in the root directory of this source tree.
  */
 
-//! Unix domain socket server implementation.
+//! Server daemon implementation.
 //!
-//! Provides the main server loop that accepts client connections and
-//! dispatches requests to handlers.
+//! Provides the main server loop that accepts client connections on the
+//! daemon's transports and dispatches requests to handlers for the config
+//! root they target.
 
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 
 use common::PerfLogger;
+use futures::future::select_all;
 use log::debug;
 use log::error;
 use log::info;
 use tokio::io::AsyncBufReadExt;
 use tokio::io::AsyncWriteExt;
 use tokio::io::BufReader;
+use tokio::io::ReadHalf;
+use tokio::io::WriteHalf;
+#[cfg(unix)]
 use tokio::net::UnixListener;
-use tokio::net::UnixStream;
+#[cfg(unix)]
 use tokio::signal::unix::SignalKind;
 use tokio::sync::Notify;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
 use tokio::task::JoinSet;
 
 use crate::build_project::artifact_writer::DeferredArtifactCache;
@@ -33,69 +40,188 @@ use crate::config::Config;
 use crate::server_daemon::DaemonMetadata;
 use crate::server_daemon::FlushWriterFactory;
 use crate::server_daemon::handlers;
+use crate::server_daemon::protocol::DaemonEvent;
 use crate::server_daemon::protocol::DaemonRequest;
 use crate::server_daemon::protocol::DaemonResponse;
+use crate::server_daemon::protocol::ErrorCode;
+use crate::server_daemon::protocol::IncomingRequest;
+use crate::server_daemon::protocol::RequestEnvelope;
+use crate::server_daemon::protocol::ResponseResult;
+use crate::server_daemon::transport::DaemonEndpoint;
+use crate::server_daemon::transport::DaemonStream;
+use crate::server_daemon::transport::Listener;
+use crate::server_daemon::transport::is_authorized;
+use crate::server_daemon::transport::token_path_for_socket;
+use crate::status_reporter::BuildEvent;
 use crate::status_reporter::BuildStatus;
 
+type ClientReader = BufReader<ReadHalf<Box<dyn DaemonStream>>>;
+type ClientWriter = WriteHalf<Box<dyn DaemonStream>>;
+
 /// Probe whether a process is actively listening on the given socket path.
 ///
 /// Returns `true` if a `connect` succeeds (something is listening), `false`
 /// otherwise. The connection is closed immediately.
+#[cfg(unix)]
 fn socket_has_listener(socket_path: &Path) -> bool {
     std::os::unix::net::UnixStream::connect(socket_path).is_ok()
 }
 
-pub struct ServerConfig<TPerfLogger: PerfLogger + 'static> {
-    pub socket_path: PathBuf,
+/// Bind the daemon's Unix socket, replacing a stale socket file nothing
+/// listens on anymore.
+#[cfg(unix)]
+fn bind_socket(socket_path: &Path) -> std::io::Result<Listener> {
+    if socket_path.exists() {
+        if socket_has_listener(socket_path) {
+            error!("Another daemon is already running on {:?}", socket_path);
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AddrInUse,
+                "Another daemon is already running",
+            ));
+        } else {
+            info!(
+                "Stale socket found with no connected process. Removing {:?}",
+                socket_path
+            );
+            std::fs::remove_file(socket_path)?;
+        }
+    }
+    Ok(Listener::Unix(UnixListener::bind(socket_path)?))
+}
+
+/// The signals that stop the daemon gracefully: SIGINT and SIGTERM on Unix,
+/// Ctrl-C elsewhere.
+struct StopSignals {
+    #[cfg(unix)]
+    sigint: tokio::signal::unix::Signal,
+    #[cfg(unix)]
+    sigterm: tokio::signal::unix::Signal,
+}
+
+impl StopSignals {
+    fn new() -> std::io::Result<Self> {
+        Ok(Self {
+            #[cfg(unix)]
+            sigint: tokio::signal::unix::signal(SignalKind::interrupt())?,
+            #[cfg(unix)]
+            sigterm: tokio::signal::unix::signal(SignalKind::terminate())?,
+        })
+    }
+
+    /// Wait for the next stop signal and return its name.
+    #[cfg(unix)]
+    async fn recv(&mut self) -> &'static str {
+        tokio::select! {
+            _ = self.sigint.recv() => "SIGINT",
+            _ = self.sigterm.recv() => "SIGTERM",
+        }
+    }
+
+    /// Wait for the next stop signal and return its name.
+    #[cfg(not(unix))]
+    async fn recv(&mut self) -> &'static str {
+        let _ = tokio::signal::ctrl_c().await;
+        "Ctrl-C"
+    }
+}
+
+/// A Relay config served by the daemon, with the state its requests are
+/// answered from. Each root runs its own compiler.
+pub struct DaemonRoot {
     pub config_path: PathBuf,
     pub projects: Vec<String>,
     pub compiler_config: Config,
-    pub perf_logger: Arc<TPerfLogger>,
     pub artifact_cache: Arc<DeferredArtifactCache>,
     pub build_status: Arc<BuildStatus>,
-    pub compiler_version: String,
     /// Optional writer factory invoked by `handle_write` when a `Write`
     /// request supplies both `flush_manifest_path` and `flush_shard_dir`.
     /// When `None`, every `Write` flushes straight to disk.
     pub flush_writer_factory: Option<FlushWriterFactory>,
 }
 
-/// Start the Unix domain socket server.
+pub struct ServerConfig<TPerfLogger: PerfLogger + 'static> {
+    /// The Unix socket to listen on. Also names the daemon's metadata, token
+    /// and log files, which is all it is used for on other platforms.
+    pub socket_path: PathBuf,
+    /// When set, also listen on `127.0.0.1` at this port (`0` picks a free
+    /// one), publishing the port and access token next to the socket.
+    /// Required where there are no Unix sockets.
+    pub tcp_port: Option<u16>,
+    /// The roots to serve. Requests that do not name a config go to the
+    /// first one, which also identifies the daemon in its metadata.
+    pub roots: Vec<DaemonRoot>,
+    pub perf_logger: Arc<TPerfLogger>,
+    pub compiler_version: String,
+}
+
+/// The parts of a [`DaemonRoot`] client handlers need.
+struct RootState {
+    /// Canonicalized, so requests can name the config by any path.
+    config_path: PathBuf,
+    config: Arc<Config>,
+    artifact_cache: Arc<DeferredArtifactCache>,
+    build_status: Arc<BuildStatus>,
+    flush_writer_factory: Option<FlushWriterFactory>,
+}
+
+/// Start the server daemon.
 ///
-/// This function binds to the specified socket path and accepts client connections
-/// in a loop. Each client connection is handled in a separate task.
+/// This function binds to the specified socket path on Unix (and TCP port,
+/// if any) and accepts client connections in a loop. Each client connection is
+/// handled in a separate task.
 ///
 /// The server will gracefully shut down when the shutdown signal is received.
 pub async fn start_server<TPerfLogger: PerfLogger + 'static>(
     config: ServerConfig<TPerfLogger>,
 ) -> Result<(), std::io::Error> {
-    let socket_path = config.socket_path;
-    if socket_path.exists() {
-        if socket_has_listener(&socket_path) {
-            error!("Another daemon is already running on {:?}", socket_path);
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::AddrInUse,
-                "Another daemon is already running",
-            ));
-        } else {
-            info!(
-                "Stale socket found with no connected process. Removing {:?}",
-                socket_path
-            );
-            std::fs::remove_file(&socket_path)?;
-        }
+    if config.roots.is_empty() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "The daemon needs at least one config root",
+        ));
     }
 
-    let listener = UnixListener::bind(&socket_path)?;
-    info!("Server daemon listening on {:?}", socket_path);
+    let socket_path = config.socket_path;
+    let mut listeners = Vec::new();
+    #[cfg(unix)]
+    {
+        listeners.push(bind_socket(&socket_path)?);
+        info!("Server daemon listening on {:?}", socket_path);
+    }
+    let mut tcp_port = None;
+    if let Some(port) = config.tcp_port {
+        let token_path = token_path_for_socket(&socket_path);
+        let listener = Listener::bind_tcp(port, &token_path).await?;
+        tcp_port = listener.local_port();
+        info!(
+            "Server daemon listening on 127.0.0.1:{}, token in {:?}",
+            tcp_port.unwrap_or(port),
+            token_path
+        );
+        listeners.push(listener);
+    }
+    if listeners.is_empty() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "The daemon needs a TCP port on platforms without Unix sockets",
+        ));
+    }
 
     // Write metadata so clients can discover this daemon
+    let mut roots = config.roots.into_iter();
+    let primary_root = roots.next().unwrap();
+    let additional_roots: Vec<DaemonRoot> = roots.collect();
     let metadata = DaemonMetadata {
         socket_path: socket_path.clone(),
-        config_path: config.config_path,
-        projects: config.projects,
+        config_path: primary_root.config_path.clone(),
+        projects: primary_root.projects.clone(),
         compiler_version: config.compiler_version.clone(),
         pid: std::process::id(),
+        additional_config_paths: additional_roots
+            .iter()
+            .map(|root| root.config_path.clone())
+            .collect(),
+        tcp_port,
     };
     if let Err(e) = metadata.write() {
         debug!("Failed to write daemon metadata: {}", e);
@@ -104,53 +230,60 @@ pub async fn start_server<TPerfLogger: PerfLogger + 'static>(
     let shutdown_signal = Arc::new(Notify::new());
 
     // Set up signal handlers for graceful shutdown
-    let mut sigint = tokio::signal::unix::signal(SignalKind::interrupt())?;
-    let mut sigterm = tokio::signal::unix::signal(SignalKind::terminate())?;
+    let mut stop_signals = StopSignals::new()?;
 
-    let artifact_cache = Arc::clone(&config.artifact_cache);
-    let build_status = Arc::clone(&config.build_status);
     let compiler_version: Arc<str> = config.compiler_version.into();
-    let flush_writer_factory = config.flush_writer_factory;
-
-    let compiler_config = Arc::new(config.compiler_config);
-    let compiler = Compiler::new(
-        Arc::clone(&compiler_config),
-        Arc::clone(&config.perf_logger),
-    );
-    let mut compiler_handle = tokio::spawn(async move {
-        if let Err(e) = compiler.watch().await {
-            error!("Compiler watch error: {}", e);
-        }
-    });
+
+    let mut root_states = Vec::new();
+    let mut compiler_handles: Vec<JoinHandle<()>> = Vec::new();
+    for root in std::iter::once(primary_root).chain(additional_roots) {
+        let compiler_config = Arc::new(root.compiler_config);
+        let compiler = Compiler::new(
+            Arc::clone(&compiler_config),
+            Arc::clone(&config.perf_logger),
+        );
+        compiler_handles.push(tokio::spawn(async move {
+            if let Err(e) = compiler.watch().await {
+                error!("Compiler watch error: {}", e);
+            }
+        }));
+        root_states.push(RootState {
+            config_path: root.config_path.canonicalize().unwrap_or(root.config_path),
+            config: compiler_config,
+            artifact_cache: root.artifact_cache,
+            build_status: root.build_status,
+            flush_writer_factory: root.flush_writer_factory,
+        });
+    }
+    let root_states = Arc::new(root_states);
 
     let mut client_tasks = JoinSet::new();
-    // Set when the `compiler_handle` select arm drives the JoinHandle to
+    // Set to the root whose compiler task the select arm below drove to
     // completion. Awaiting a JoinHandle a second time panics with
     // `JoinHandle polled after completion`, so the post-loop `abort + await`
-    // below must be skipped in that case. Without this guard, every
-    // intentional restart (and every compiler crash) panics on shutdown.
-    let mut compiler_completed = false;
+    // must skip that handle. Without this guard, every intentional restart
+    // (and every compiler crash) panics on shutdown.
+    let mut completed_compiler = None;
 
     loop {
         tokio::select! {
-            result = listener.accept() => {
+            (result, listener_index, _) = select_all(
+                listeners.iter().map(|listener| Box::pin(listener.accept()))
+            ) => {
                 match result {
-                    Ok((stream, _addr)) => {
+                    Ok(stream) => {
+                        let required_token: Option<Arc<str>> =
+                            listeners[listener_index].required_token().map(Into::into);
                         let shutdown = Arc::clone(&shutdown_signal);
-                        let task_artifact_cache = Arc::clone(&artifact_cache);
-                        let task_build_status = Arc::clone(&build_status);
+                        let task_roots = Arc::clone(&root_states);
                         let task_compiler_version = Arc::clone(&compiler_version);
-                        let task_config = Arc::clone(&compiler_config);
-                        let task_flush_writer_factory = flush_writer_factory.clone();
                         client_tasks.spawn(async move {
                             if let Err(e) = handle_client(
                                 stream,
+                                required_token,
                                 shutdown,
-                                task_artifact_cache,
-                                task_build_status,
+                                task_roots,
                                 task_compiler_version,
-                                task_config,
-                                task_flush_writer_factory,
                             ).await {
                                 debug!("Client handler error: {}", e);
                             }
@@ -161,8 +294,9 @@ pub async fn start_server<TPerfLogger: PerfLogger + 'static>(
                     }
                 }
             }
-            result = &mut compiler_handle => {
-                compiler_completed = true;
+            (result, root_index, _) = select_all(compiler_handles.iter_mut()) => {
+                completed_compiler = Some(root_index);
+                let build_status = &root_states[root_index].build_status;
                 // Was this an intentional restart from `Compiler::watch`
                 // detecting binary/config drift? `restart_initiated` is
                 // called there immediately before the loop exits cleanly
@@ -173,50 +307,64 @@ pub async fn start_server<TPerfLogger: PerfLogger + 'static>(
                 // handler to an `Info`-severity client message so the
                 // client retries against the freshly-spawned daemon
                 // instead of reporting a build failure).
-                if let Some(reason) = build_status.take_restart_reason() {
+                let stop_reason = if let Some(reason) = build_status.take_restart_reason() {
                     info!("Daemon restarting to pick up new binary/config: {reason}");
-                    build_status.daemon_restarting(reason);
-                    break;
-                }
-                let message = match result {
-                    Ok(()) => "Compiler exited unexpectedly".to_string(),
-                    Err(e) if e.is_panic() => {
-                        let panic_msg = e.into_panic();
-                        let detail = panic_msg
-                            .downcast_ref::<&str>()
-                            .map(|s| s.to_string())
-                            .or_else(|| panic_msg.downcast_ref::<String>().cloned())
-                            .unwrap_or_else(|| "unknown panic".to_string());
-                        format!("Compiler panicked: {}", detail)
-                    }
-                    Err(e) => format!("Compiler task failed: {}", e),
+                    build_status.daemon_restarting(reason.clone());
+                    reason
+                } else {
+                    let message = match result {
+                        Ok(()) => "Compiler exited unexpectedly".to_string(),
+                        Err(e) if e.is_panic() => {
+                            let panic_msg = e.into_panic();
+                            let detail = panic_msg
+                                .downcast_ref::<&str>()
+                                .map(|s| s.to_string())
+                                .or_else(|| panic_msg.downcast_ref::<String>().cloned())
+                                .unwrap_or_else(|| "unknown panic".to_string());
+                            format!("Compiler panicked: {}", detail)
+                        }
+                        Err(e) => format!("Compiler task failed: {}", e),
+                    };
+                    error!("{}", message);
+                    // Unblock any in-flight wait_for_idle() calls so connected
+                    // clients get the error. Then exit so the next client starts
+                    // a fresh daemon (which may succeed if the issue was transient).
+                    build_status.compiler_crashed(message.clone());
+                    message
                 };
-                error!("{}", message);
-                // Unblock any in-flight wait_for_idle() calls so connected
-                // clients get the error. Then exit so the next client starts
-                // a fresh daemon (which may succeed if the issue was transient).
-                build_status.compiler_crashed(message);
+                // The whole daemon exits with this root, so clients of the
+                // other roots should retry against the next daemon too.
+                for (index, root) in root_states.iter().enumerate() {
+                    if index != root_index {
+                        root.build_status.daemon_restarting(format!(
+                            "compiler for {} stopped ({stop_reason})",
+                            root_states[root_index].config_path.display()
+                        ));
+                    }
+                }
                 break;
             }
             _ = shutdown_signal.notified() => {
                 info!("Shutdown signal received, stopping daemon");
                 break;
             }
-            _ = sigint.recv() => {
-                info!("Received SIGINT, stopping daemon");
-                break;
-            }
-            _ = sigterm.recv() => {
-                info!("Received SIGTERM, stopping daemon");
+            signal = stop_signals.recv() => {
+                info!("Received {signal}, stopping daemon");
                 break;
             }
         }
     }
 
+    // Let subscribers know no further events are coming, so their handlers
+    // return instead of holding up the wait below.
+    for root in root_states.iter() {
+        root.build_status.publish_event(BuildEvent::DaemonStopping);
+    }
+
     // Wait for in-flight client handlers to finish sending their responses.
     // Without this, the process can exit before a handler that just woke up
     // from wait_for_idle() (due to compiler_crashed) has written its response
-    // back to the Unix socket — causing the client to see "No server response".
+    // back to the client — causing the client to see "No server response".
     if !client_tasks.is_empty() {
         info!(
             "Waiting up to 5 seconds for {} in-flight client handler(s)...",
@@ -228,12 +376,14 @@ pub async fn start_server<TPerfLogger: PerfLogger + 'static>(
         .await;
     }
 
-    // Abort the compiler task (no-op if it already exited) and wait for cleanup.
-    // Skip when the `compiler_handle` select arm above already drove the handle
-    // to completion — awaiting a JoinHandle twice panics.
-    if !compiler_completed {
-        compiler_handle.abort();
-        let _ = compiler_handle.await;
+    // Abort the compiler tasks (no-op if they already exited) and wait for
+    // cleanup, skipping the one the select arm above already drove to
+    // completion — awaiting a JoinHandle twice panics.
+    for (index, compiler_handle) in compiler_handles.into_iter().enumerate() {
+        if completed_compiler != Some(index) {
+            compiler_handle.abort();
+            let _ = compiler_handle.await;
+        }
     }
 
     metadata.cleanup();
@@ -243,15 +393,13 @@ pub async fn start_server<TPerfLogger: PerfLogger + 'static>(
 
 /// Handle a single client connection
 async fn handle_client(
-    stream: UnixStream,
+    stream: Box<dyn DaemonStream>,
+    required_token: Option<Arc<str>>,
     shutdown_signal: Arc<Notify>,
-    artifact_cache: Arc<DeferredArtifactCache>,
-    build_status: Arc<BuildStatus>,
+    roots: Arc<Vec<RootState>>,
     compiler_version: Arc<str>,
-    config: Arc<Config>,
-    flush_writer_factory: Option<FlushWriterFactory>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let (reader, mut writer) = stream.into_split();
+    let (reader, mut writer) = tokio::io::split(stream);
     let mut reader = BufReader::new(reader);
     let mut line = String::new();
 
@@ -262,37 +410,119 @@ async fn handle_client(
         return Ok(());
     }
 
-    let request: DaemonRequest = serde_json::from_str(&line)?;
+    let RequestEnvelope {
+        token,
+        config_path,
+        request,
+    } = serde_json::from_str::<IncomingRequest>(&line)?.into_envelope();
     debug!("Received request: {:?}", request);
 
-    let response = dispatch_request(
-        request,
-        &shutdown_signal,
-        &artifact_cache,
-        &build_status,
-        &compiler_version,
-        &config,
-        flush_writer_factory.as_ref(),
-    )
-    .await;
+    if !is_authorized(required_token.as_deref(), token.as_deref()) {
+        let response = DaemonResponse::error(ErrorCode::Unauthorized, "Missing or invalid token");
+        return write_line(&mut writer, &response).await;
+    }
+    let Some(root) = select_root(&roots, config_path.as_deref()) else {
+        let response = DaemonResponse::error(
+            ErrorCode::UnknownRoot,
+            format!(
+                "This daemon does not serve {}",
+                config_path.unwrap_or_default().display()
+            ),
+        );
+        return write_line(&mut writer, &response).await;
+    };
 
-    let mut response_json = serde_json::to_string(&response)?;
-    response_json.push('\n');
-    writer.write_all(response_json.as_bytes()).await?;
+    // Subscribe before acknowledging so that no event published in between
+    // is missed.
+    let events =
+        matches!(request, DaemonRequest::Subscribe).then(|| root.build_status.subscribe_events());
+
+    let response = dispatch_request(request, &shutdown_signal, root, &compiler_version).await;
+    write_line(&mut writer, &response).await?;
+
+    if let Some(events) = events {
+        stream_events(reader, writer, events).await?;
+    }
 
     Ok(())
 }
 
+/// The root a request is for: the one whose config is `config_path`, or the
+/// primary root when the request does not say.
+fn select_root<'a>(roots: &'a [RootState], config_path: Option<&Path>) -> Option<&'a RootState> {
+    match config_path {
+        None => roots.first(),
+        Some(config_path) => {
+            let config_path = config_path
+                .canonicalize()
+                .unwrap_or_else(|_| config_path.to_path_buf());
+            roots.iter().find(|root| root.config_path == config_path)
+        }
+    }
+}
+
+/// Forward build events to a subscribed client, one JSON object per line,
+/// until the client disconnects or the daemon stops.
+async fn stream_events(
+    mut reader: ClientReader,
+    mut writer: ClientWriter,
+    mut events: broadcast::Receiver<BuildEvent>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let mut client_input = String::new();
+    loop {
+        let event = tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    write_line(&mut writer, &DaemonEvent::Lagged { skipped }).await?;
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => return Ok(()),
+            },
+            // Subscribers have nothing more to send; reading only tells us
+            // when they hang up.
+            bytes_read = reader.read_line(&mut client_input) => {
+                if bytes_read? == 0 {
+                    debug!("Subscriber disconnected");
+                    return Ok(());
+                }
+                client_input.clear();
+                continue;
+            }
+        };
+        let is_last = event == BuildEvent::DaemonStopping;
+        write_line(&mut writer, &DaemonEvent::from(event)).await?;
+        if is_last {
+            return Ok(());
+        }
+    }
+}
+
+/// Write `message` to the client as a single line of JSON.
+async fn write_line(
+    writer: &mut ClientWriter,
+    message: &impl serde::Serialize,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let mut json = serde_json::to_string(message)?;
+    json.push('\n');
+    writer.write_all(json.as_bytes()).await?;
+    Ok(())
+}
+
 /// Dispatch a request to the appropriate handler
 async fn dispatch_request(
     request: DaemonRequest,
     shutdown_signal: &Arc<Notify>,
-    artifact_cache: &Arc<DeferredArtifactCache>,
-    build_status: &Arc<BuildStatus>,
+    root: &RootState,
     compiler_version: &str,
-    config: &Arc<Config>,
-    flush_writer_factory: Option<&FlushWriterFactory>,
 ) -> DaemonResponse {
+    let RootState {
+        artifact_cache,
+        build_status,
+        config,
+        flush_writer_factory,
+        ..
+    } = root;
     match request {
         DaemonRequest::Write {
             flush_manifest_path,
@@ -302,7 +532,7 @@ async fn dispatch_request(
                 artifact_cache,
                 build_status,
                 config,
-                flush_writer_factory,
+                flush_writer_factory.as_ref(),
                 flush_manifest_path,
                 flush_shard_dir,
             )
@@ -310,18 +540,67 @@ async fn dispatch_request(
         }
         DaemonRequest::Version => handlers::handle_version(compiler_version),
         DaemonRequest::Shutdown => handlers::handle_shutdown(shutdown_signal),
+        DaemonRequest::Status => handlers::handle_status(artifact_cache, build_status),
+        DaemonRequest::Diagnostics => handlers::handle_diagnostics(build_status).await,
+        DaemonRequest::DirtyArtifacts => handlers::handle_dirty_artifacts(artifact_cache),
+        DaemonRequest::ArtifactsForSource { path } => {
+            handlers::handle_artifacts_for_source(build_status, config, path)
+        }
+        DaemonRequest::Rebuild { projects } => {
+            handlers::handle_rebuild(build_status, config, projects).await
+        }
+        DaemonRequest::Subscribe => handlers::handle_subscribe(),
     }
 }
 
-/// Send a request to a running daemon.
+/// Serialize `request` as a request line for `endpoint`. Requests that need
+/// neither a token nor a root are sent bare, so that they are understood by
+/// daemons predating [`RequestEnvelope`] — in particular the `Version`
+/// request used to detect such daemons.
+fn request_line(
+    endpoint: &DaemonEndpoint,
+    config_path: Option<&Path>,
+    request: DaemonRequest,
+) -> serde_json::Result<String> {
+    let token = endpoint.token().map(str::to_string);
+    let mut line = if token.is_none() && config_path.is_none() {
+        serde_json::to_string(&request)?
+    } else {
+        serde_json::to_string(&RequestEnvelope {
+            token,
+            config_path: config_path.map(Path::to_path_buf),
+            request,
+        })?
+    };
+    line.push('\n');
+    Ok(line)
+}
+
+/// Send a request to a running daemon over its Unix socket.
 ///
 /// Returns None if the daemon is not running or the request fails.
 pub async fn send_request(socket_path: &Path, request: DaemonRequest) -> Option<DaemonResponse> {
-    let stream = UnixStream::connect(socket_path).await.ok()?;
-    let (reader, mut writer) = stream.into_split();
+    send_request_to(
+        &DaemonEndpoint::Unix(socket_path.to_path_buf()),
+        None,
+        request,
+    )
+    .await
+}
+
+/// Send a request to a running daemon at `endpoint`, for the root whose
+/// config is `config_path` (the daemon's primary root when `None`).
+///
+/// Returns None if the daemon is not running or the request fails.
+pub async fn send_request_to(
+    endpoint: &DaemonEndpoint,
+    config_path: Option<&Path>,
+    request: DaemonRequest,
+) -> Option<DaemonResponse> {
+    let stream = endpoint.connect().await.ok()?;
+    let (reader, mut writer) = tokio::io::split(stream);
 
-    let mut request_json = serde_json::to_string(&request).ok()?;
-    request_json.push('\n');
+    let request_json = request_line(endpoint, config_path, request).ok()?;
     writer.write_all(request_json.as_bytes()).await.ok()?;
     writer.shutdown().await.ok()?;
 
@@ -331,3 +610,41 @@ pub async fn send_request(socket_path: &Path, request: DaemonRequest) -> Option<
 
     serde_json::from_str(&response).ok()
 }
+
+/// Subscribe to the build events of a daemon root, calling `on_event` for
+/// each one until the daemon stops or the connection drops.
+///
+/// Returns an error if the daemon is not running or does not acknowledge
+/// the subscription.
+pub async fn subscribe(
+    endpoint: &DaemonEndpoint,
+    config_path: Option<&Path>,
+    mut on_event: impl FnMut(DaemonEvent),
+) -> std::io::Result<()> {
+    let stream = endpoint.connect().await?;
+    let (reader, mut writer) = tokio::io::split(stream);
+
+    let request_json = request_line(endpoint, config_path, DaemonRequest::Subscribe)?;
+    writer.write_all(request_json.as_bytes()).await?;
+
+    let mut lines = BufReader::new(reader).lines();
+    let acknowledged = match lines.next_line().await? {
+        Some(line) => matches!(
+            serde_json::from_str::<DaemonResponse>(&line)?,
+            DaemonResponse::Success {
+                result: ResponseResult::Subscribed
+            }
+        ),
+        None => false,
+    };
+    if !acknowledged {
+        return Err(std::io::Error::other(
+            "Daemon did not accept the subscription",
+        ));
+    }
+
+    while let Some(line) = lines.next_line().await? {
+        on_event(serde_json::from_str(&line)?);
+    }
+    Ok(())
+}
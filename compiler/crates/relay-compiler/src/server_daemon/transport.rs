@@ -0,0 +1,253 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Transports the server daemon can accept client connections on.
+//!
+//! On Unix, every daemon listens on its Unix domain socket. It can
+//! additionally listen on loopback TCP, so clients that do not share the
+//! socket file (e.g. in a dev container with a forwarded port) can reach it;
+//! on other platforms TCP is the only transport. A TCP daemon writes its
+//! port and a random token to a token file readable only by its owner; every
+//! request arriving over TCP must carry that token.
+
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::net::Ipv4Addr;
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncWrite;
+use tokio::net::TcpListener;
+use tokio::net::TcpStream;
+#[cfg(unix)]
+use tokio::net::UnixListener;
+#[cfg(unix)]
+use tokio::net::UnixStream;
+
+/// Contents of a TCP daemon's token file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TcpEndpoint {
+    pub port: u16,
+    pub token: String,
+}
+
+impl TcpEndpoint {
+    /// Read the endpoint a TCP daemon published to `token_path`.
+    pub fn read(token_path: &Path) -> std::io::Result<Self> {
+        let contents = fs::read_to_string(token_path)?;
+        serde_json::from_str(&contents)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    /// Write this endpoint to `token_path`, readable only by the current user.
+    fn write(&self, token_path: &Path) -> std::io::Result<()> {
+        let contents = serde_json::to_string(self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        // Remove first so a leftover file with looser permissions is not reused.
+        if token_path.exists() {
+            fs::remove_file(token_path)?;
+        }
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        // Elsewhere the file inherits the access rules of the per-user
+        // runtime or temp directory it is created in.
+        #[cfg(unix)]
+        options.mode(0o600);
+        let mut file = options.open(token_path)?;
+        file.write_all(contents.as_bytes())
+    }
+}
+
+/// Where a client finds a daemon. Unix sockets can only be connected to on
+/// Unix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DaemonEndpoint {
+    Unix(PathBuf),
+    Tcp(TcpEndpoint),
+}
+
+impl DaemonEndpoint {
+    /// The token to send with each request, if the endpoint requires one.
+    pub fn token(&self) -> Option<&str> {
+        match self {
+            DaemonEndpoint::Unix(_) => None,
+            DaemonEndpoint::Tcp(endpoint) => Some(&endpoint.token),
+        }
+    }
+
+    pub(crate) async fn connect(&self) -> std::io::Result<Box<dyn DaemonStream>> {
+        Ok(match self {
+            DaemonEndpoint::Unix(socket_path) => connect_unix(socket_path).await?,
+            DaemonEndpoint::Tcp(endpoint) => {
+                Box::new(TcpStream::connect((Ipv4Addr::LOCALHOST, endpoint.port)).await?)
+            }
+        })
+    }
+}
+
+impl std::fmt::Display for DaemonEndpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DaemonEndpoint::Unix(socket_path) => write!(f, "{}", socket_path.display()),
+            DaemonEndpoint::Tcp(endpoint) => write!(f, "127.0.0.1:{}", endpoint.port),
+        }
+    }
+}
+
+#[cfg(unix)]
+async fn connect_unix(socket_path: &Path) -> std::io::Result<Box<dyn DaemonStream>> {
+    Ok(Box::new(UnixStream::connect(socket_path).await?))
+}
+
+#[cfg(not(unix))]
+async fn connect_unix(_socket_path: &Path) -> std::io::Result<Box<dyn DaemonStream>> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "Unix domain sockets are not supported on this platform",
+    ))
+}
+
+/// Derive a TCP daemon's token file path from its socket path.
+pub fn token_path_for_socket(socket_path: &Path) -> PathBuf {
+    socket_path.with_extension("token")
+}
+
+/// A client connection, whichever transport it arrived on.
+pub(crate) trait DaemonStream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> DaemonStream for T {}
+
+pub(crate) enum Listener {
+    #[cfg(unix)]
+    Unix(UnixListener),
+    Tcp {
+        listener: TcpListener,
+        token: String,
+    },
+}
+
+impl Listener {
+    /// Bind a TCP listener on loopback and publish its endpoint.
+    pub(crate) async fn bind_tcp(port: u16, token_path: &Path) -> std::io::Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port)).await?;
+        let endpoint = TcpEndpoint {
+            port: listener.local_addr()?.port(),
+            token: generate_token(),
+        };
+        endpoint.write(token_path)?;
+        Ok(Listener::Tcp {
+            listener,
+            token: endpoint.token,
+        })
+    }
+
+    pub(crate) async fn accept(&self) -> std::io::Result<Box<dyn DaemonStream>> {
+        Ok(match self {
+            #[cfg(unix)]
+            Listener::Unix(listener) => Box::new(listener.accept().await?.0),
+            Listener::Tcp { listener, .. } => Box::new(listener.accept().await?.0),
+        })
+    }
+
+    /// The port this listener is bound to, if it is a TCP listener.
+    pub(crate) fn local_port(&self) -> Option<u16> {
+        match self {
+            #[cfg(unix)]
+            Listener::Unix(_) => None,
+            Listener::Tcp { listener, .. } => listener.local_addr().ok().map(|addr| addr.port()),
+        }
+    }
+
+    /// The token requests arriving on this listener must carry. Unix socket
+    /// access is governed by file permissions instead.
+    pub(crate) fn required_token(&self) -> Option<&str> {
+        match self {
+            #[cfg(unix)]
+            Listener::Unix(_) => None,
+            Listener::Tcp { token, .. } => Some(token),
+        }
+    }
+}
+
+/// Whether a request carrying `token` may be served on a listener requiring
+/// `required_token`.
+pub(crate) fn is_authorized(required_token: Option<&str>, token: Option<&str>) -> bool {
+    match required_token {
+        None => true,
+        Some(expected) => {
+            token.is_some_and(|token| constant_time_eq(token.as_bytes(), expected.as_bytes()))
+        }
+    }
+}
+
+/// A random token from the cryptographically secure thread-local generator.
+fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::fill(&mut bytes);
+    hex::encode(bytes)
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_file_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let token_path = dir.path().join("relay-server-test.token");
+        let endpoint = TcpEndpoint {
+            port: 4321,
+            token: generate_token(),
+        };
+        endpoint.write(&token_path).unwrap();
+
+        assert_eq!(TcpEndpoint::read(&token_path).unwrap(), endpoint);
+        assert_ne!(generate_token(), endpoint.token);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_token_file_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let token_path = dir.path().join("relay-server-test.token");
+        let endpoint = TcpEndpoint {
+            port: 4321,
+            token: generate_token(),
+        };
+        endpoint.write(&token_path).unwrap();
+
+        let mode = fs::metadata(&token_path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[tokio::test]
+    async fn test_tcp_listener_requires_published_token() {
+        let dir = tempfile::tempdir().unwrap();
+        let token_path = dir.path().join("relay-server-test.token");
+        let listener = Listener::bind_tcp(0, &token_path).await.unwrap();
+        let endpoint = TcpEndpoint::read(&token_path).unwrap();
+
+        assert_eq!(listener.local_port(), Some(endpoint.port));
+        assert_ne!(endpoint.port, 0);
+        let required_token = listener.required_token();
+        assert!(is_authorized(required_token, Some(&endpoint.token)));
+        assert!(!is_authorized(required_token, Some("not-the-token")));
+        assert!(!is_authorized(required_token, None));
+    }
+}
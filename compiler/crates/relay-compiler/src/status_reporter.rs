@@ -9,7 +9,9 @@
 //!
 //! This module contains the following implementations of the `StatusReporter` trait:
 //! * `ConsoleStatusReporter`: Reports the status to the console using the `log` crate.
-//! * `JSONStatusReporter`: Reports the status to a JSON file using the `serde_json` crate.
+//! * `JSONStatusReporter`: Reports the status to a JSON file using the `serde_json` crate, or
+//!   the diagnostics as JSON, SARIF or GitHub Actions annotations for tools such as code
+//!   review bots.
 //! * `BuildStatus`: Wraps a base reporter (decorator pattern), delegating reporting while
 //!   also tracking build state for daemon/client synchronization.
 use std::fs;
@@ -25,16 +27,25 @@ use std::sync::atomic::Ordering::SeqCst;
 
 use common::Diagnostic;
 use common::DiagnosticSeverity;
+use fnv::FnvHashMap;
+use fnv::FnvHashSet;
 use graphql_cli::DiagnosticPrinter;
+use graphql_cli::DiagnosticReport;
+pub use graphql_cli::DiagnosticsFormat;
+use graphql_cli::Sources;
 use log::error;
 use log::info;
 use log::warn;
+use relay_config::ProjectName;
 use tokio::sync::Notify;
+use tokio::sync::broadcast;
 use watchman_client::prelude::Clock;
 
 use crate::FileSourceResult;
 use crate::FsSourceReader;
 use crate::SourceReader;
+use crate::compiler_state::ArtifactMapKind;
+use crate::compiler_state::ProjectArtifactMap;
 use crate::config::Config;
 use crate::errors::BuildProjectError;
 use crate::errors::Error;
@@ -45,6 +56,42 @@ pub trait StatusReporter {
     fn build_starts(&self);
     fn build_completes(&self, diagnostics: &[Diagnostic]);
     fn build_errors(&self, error: &Error);
+
+    /// Reports a successful build with the diagnostics of each project. By
+    /// default, the diagnostics of all projects are reported together.
+    fn build_completes_for_projects(&self, project_diagnostics: &ProjectDiagnostics) {
+        self.build_completes(project_diagnostics.diagnostics());
+    }
+}
+
+/// The diagnostics of a successful build, grouped by the project that
+/// reported them.
+#[derive(Debug, Default)]
+pub struct ProjectDiagnostics {
+    diagnostics: Vec<Diagnostic>,
+    project_ends: Vec<(ProjectName, usize)>,
+}
+
+impl ProjectDiagnostics {
+    pub fn push(&mut self, project_name: ProjectName, mut diagnostics: Vec<Diagnostic>) {
+        self.diagnostics.append(&mut diagnostics);
+        self.project_ends
+            .push((project_name, self.diagnostics.len()));
+    }
+
+    /// The diagnostics of all projects.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (ProjectName, &[Diagnostic])> {
+        let mut start = 0;
+        self.project_ends.iter().map(move |(project_name, end)| {
+            let diagnostics = &self.diagnostics[start..*end];
+            start = *end;
+            (*project_name, diagnostics)
+        })
+    }
 }
 
 /// A no-op reporter used as a placeholder when the real reporter is being moved.
@@ -72,6 +119,28 @@ pub enum BuildResult {
     Restarting(String),
 }
 
+/// A build lifecycle event, published by [`BuildStatus`] to every daemon
+/// client subscribed with [`BuildStatus::subscribe_events`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum BuildEvent {
+    BuildStarted,
+    /// A project's artifacts were committed as part of the current build.
+    ProjectCompiled {
+        project: ProjectName,
+    },
+    /// The diagnostics of a finished build differ from the previous build's.
+    DiagnosticsChanged(Vec<(DiagnosticSeverity, String)>),
+    /// Cached artifacts were flushed.
+    ArtifactsWritten {
+        count: usize,
+    },
+    /// Watchman could not give a trustworthy incremental answer, so the
+    /// compiler state is being reinitialized.
+    WatchmanResync,
+    /// The daemon is shutting down or restarting; no further events follow.
+    DaemonStopping,
+}
+
 /// Outcome of a Watchman sync — what the caller should do next.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WatchmanSyncOutcome {
@@ -130,6 +199,22 @@ pub struct BuildStatus {
     /// from an unexpected crash. Stored as `String` to keep
     /// [`status_reporter`] free of a dependency on [`crate::config`].
     restart_reason: Mutex<Option<String>>,
+    /// Projects a client asked to rebuild from scratch. Drained by the
+    /// build loop via [`Self::take_rebuild_requests`].
+    rebuild_requests: Mutex<Vec<ProjectName>>,
+    /// Requested projects the build loop could not rebuild, for lack of an
+    /// artifact map. Drained by the requests via [`Self::take_rejected_rebuilds`].
+    rejected_rebuilds: Mutex<FnvHashSet<ProjectName>>,
+    /// Per-project artifact maps as of the last committed build, published
+    /// by the build loop so request handlers can map sources to artifacts
+    /// without touching the compiler state.
+    artifact_maps: Mutex<FnvHashMap<ProjectName, Arc<ArtifactMapKind>>>,
+    /// Fan-out of [`BuildEvent`]s to subscribed daemon clients. Sending
+    /// without subscribers is a no-op.
+    events: broadcast::Sender<BuildEvent>,
+    /// Diagnostics of the last finished build, used to publish
+    /// [`BuildEvent::DiagnosticsChanged`] only when they differ.
+    last_diagnostics: Mutex<Option<Vec<(DiagnosticSeverity, String)>>>,
 }
 
 /// State guarded by the `watchman_sync` mutex in [`BuildStatus`].
@@ -152,6 +237,10 @@ impl BuildStatus {
     /// Maximum log file size in bytes before truncation (~1MB).
     const MAX_LOG_BYTES: u64 = 1_000_000;
 
+    /// Number of events a slow subscriber may fall behind by before it
+    /// starts missing them.
+    const EVENT_CHANNEL_CAPACITY: usize = 256;
+
     /// Create a new `BuildStatus` that wraps the given base reporter.
     ///
     /// The `root_dir` and `is_multi_project` parameters are used to format
@@ -177,6 +266,11 @@ impl BuildStatus {
             }),
             needs_reset: AtomicBool::new(false),
             restart_reason: Mutex::new(None),
+            rebuild_requests: Mutex::new(Vec::new()),
+            rejected_rebuilds: Mutex::new(FnvHashSet::default()),
+            artifact_maps: Mutex::new(FnvHashMap::default()),
+            events: broadcast::channel(Self::EVENT_CHANNEL_CAPACITY).0,
+            last_diagnostics: Mutex::new(None),
         }
     }
 
@@ -297,6 +391,80 @@ impl BuildStatus {
         self.restart_reason.lock().unwrap().take()
     }
 
+    /// Queue projects whose artifacts should all be regenerated on the next
+    /// build loop iteration. Caller should also call
+    /// [`Self::notify_build_loop`] to wake the loop.
+    pub fn request_rebuild(&self, projects: impl IntoIterator<Item = ProjectName>) {
+        self.rebuild_requests.lock().unwrap().extend(projects);
+    }
+
+    /// Take the projects queued by [`Self::request_rebuild`].
+    pub fn take_rebuild_requests(&self) -> Vec<ProjectName> {
+        std::mem::take(&mut *self.rebuild_requests.lock().unwrap())
+    }
+
+    /// Whether any rebuild request is still waiting for the build loop.
+    pub fn has_pending_rebuild_requests(&self) -> bool {
+        !self.rebuild_requests.lock().unwrap().is_empty()
+    }
+
+    /// Record that the build loop could not rebuild a requested project.
+    pub fn reject_rebuild(&self, project_name: ProjectName) {
+        self.rejected_rebuilds.lock().unwrap().insert(project_name);
+    }
+
+    /// Take the projects among `projects` that the build loop could not
+    /// rebuild since they were requested.
+    pub fn take_rejected_rebuilds(&self, projects: &[ProjectName]) -> Vec<ProjectName> {
+        let mut rejected_rebuilds = self.rejected_rebuilds.lock().unwrap();
+        projects
+            .iter()
+            .filter(|project_name| rejected_rebuilds.remove(project_name))
+            .copied()
+            .collect()
+    }
+
+    /// Record the artifact maps of a committed build. The maps are shared,
+    /// not copied: each build replaces a project's `Arc` rather than
+    /// mutating it, so the snapshot stays consistent.
+    pub fn publish_artifact_maps(&self, artifacts: &ProjectArtifactMap) {
+        *self.artifact_maps.lock().unwrap() = artifacts
+            .0
+            .iter()
+            .map(|(project_name, artifact_map)| (*project_name, Arc::clone(artifact_map)))
+            .collect();
+    }
+
+    /// The artifact maps last recorded by [`Self::publish_artifact_maps`].
+    pub fn artifact_maps(&self) -> FnvHashMap<ProjectName, Arc<ArtifactMapKind>> {
+        self.artifact_maps.lock().unwrap().clone()
+    }
+
+    /// Receive every [`BuildEvent`] published from now on.
+    pub fn subscribe_events(&self) -> broadcast::Receiver<BuildEvent> {
+        self.events.subscribe()
+    }
+
+    /// Publish an event to all current subscribers.
+    pub fn publish_event(&self, event: BuildEvent) {
+        let _ = self.events.send(event);
+    }
+
+    /// Publish [`BuildEvent::DiagnosticsChanged`] if `diagnostics` differ
+    /// from those of the previous build.
+    fn publish_diagnostics(&self, diagnostics: &[(DiagnosticSeverity, String)]) {
+        let mut last_diagnostics = self.last_diagnostics.lock().unwrap();
+        if last_diagnostics.as_deref() != Some(diagnostics) {
+            *last_diagnostics = Some(diagnostics.to_vec());
+            self.publish_event(BuildEvent::DiagnosticsChanged(diagnostics.to_vec()));
+        }
+    }
+
+    /// Whether a build is currently in progress.
+    pub fn is_building(&self) -> bool {
+        self.is_building.load(SeqCst)
+    }
+
     /// Called when pending changes were determined to not require a build.
     pub fn no_pending_changes(&self) {
         self.is_building.store(false, SeqCst);
@@ -308,10 +476,9 @@ impl BuildStatus {
     /// Stores an error build result and unblocks any pending `wait_for_idle()`
     /// calls so clients receive the error instead of hanging forever.
     pub fn compiler_crashed(&self, message: String) {
-        self.set_build_result(BuildResult::Errors(vec![(
-            DiagnosticSeverity::ERROR,
-            message,
-        )]));
+        let messages = vec![(DiagnosticSeverity::ERROR, message)];
+        self.publish_diagnostics(&messages);
+        self.set_build_result(BuildResult::Errors(messages));
         self.build_completed();
     }
 
@@ -347,6 +514,12 @@ impl BuildStatus {
         }
     }
 
+    /// Get the build result without clearing it, so that a later
+    /// [`Self::take_build_result`] still observes it.
+    pub fn peek_build_result(&self) -> Option<BuildResult> {
+        self.build_result.lock().unwrap().clone()
+    }
+
     fn set_build_result(&self, result: BuildResult) {
         *self.build_result.lock().unwrap() = Some(result);
     }
@@ -398,23 +571,18 @@ impl BuildStatus {
 impl StatusReporter for BuildStatus {
     fn build_starts(&self) {
         self.base_reporter.build_starts();
+        self.publish_event(BuildEvent::BuildStarted);
     }
 
     fn build_completes(&self, diagnostics: &[Diagnostic]) {
         self.base_reporter.build_completes(diagnostics);
-        self.set_build_result(BuildResult::Success(
-            diagnostics
-                .iter()
-                .filter(|d| d.severity() != DiagnosticSeverity::HINT)
-                .map(|d| {
-                    (
-                        d.severity(),
-                        format_diagnostic(&self.root_dir, &FsSourceReader, d),
-                    )
-                })
-                .collect(),
-        ));
-        self.build_completed();
+        self.record_build_success(diagnostics);
+    }
+
+    fn build_completes_for_projects(&self, project_diagnostics: &ProjectDiagnostics) {
+        self.base_reporter
+            .build_completes_for_projects(project_diagnostics);
+        self.record_build_success(project_diagnostics.diagnostics());
     }
 
     fn build_errors(&self, error: &Error) {
@@ -425,11 +593,30 @@ impl StatusReporter for BuildStatus {
             self.is_multi_project,
             error,
         );
+        self.publish_diagnostics(&messages);
         self.set_build_result(BuildResult::Errors(messages));
         self.build_completed();
     }
 }
 
+impl BuildStatus {
+    fn record_build_success(&self, diagnostics: &[Diagnostic]) {
+        let messages: Vec<_> = diagnostics
+            .iter()
+            .filter(|d| d.severity() != DiagnosticSeverity::HINT)
+            .map(|d| {
+                (
+                    d.severity(),
+                    format_diagnostic(&self.root_dir, &FsSourceReader, d),
+                )
+            })
+            .collect();
+        self.publish_diagnostics(&messages);
+        self.set_build_result(BuildResult::Success(messages));
+        self.build_completed();
+    }
+}
+
 impl StatusReporter for Arc<BuildStatus> {
     fn build_starts(&self) {
         (**self).build_starts();
@@ -439,6 +626,10 @@ impl StatusReporter for Arc<BuildStatus> {
         (**self).build_completes(diagnostics);
     }
 
+    fn build_completes_for_projects(&self, project_diagnostics: &ProjectDiagnostics) {
+        (**self).build_completes_for_projects(project_diagnostics);
+    }
+
     fn build_errors(&self, error: &Error) {
         (**self).build_errors(error);
     }
@@ -595,6 +786,15 @@ impl StatusReporter for ConsoleStatusReporter {
 pub struct JSONStatusReporter {
     path: Option<PathBuf>,
     base_reporter: Box<dyn StatusReporter + Send + Sync>,
+    diagnostics_format: Option<DiagnosticsFormatOptions>,
+}
+
+/// Renders the diagnostics in a [`DiagnosticsFormat`] with their source,
+/// including errors without a source location (e.g. persisting failures).
+struct DiagnosticsFormatOptions {
+    format: DiagnosticsFormat,
+    source_reader: Box<dyn SourceReader + Send + Sync>,
+    root_dir: PathBuf,
 }
 
 impl JSONStatusReporter {
@@ -605,69 +805,166 @@ impl JSONStatusReporter {
         Self {
             path,
             base_reporter,
+            diagnostics_format: None,
         }
     }
-}
 
-impl StatusReporter for JSONStatusReporter {
-    fn build_starts(&self) {}
+    /// Reports only the diagnostics of each build, in the given format.
+    pub fn with_diagnostics_format(mut self, format: DiagnosticsFormat, root_dir: PathBuf) -> Self {
+        self.diagnostics_format = Some(DiagnosticsFormatOptions {
+            format,
+            source_reader: Box::new(FsSourceReader),
+            root_dir,
+        });
+        self
+    }
 
-    fn build_completes(&self, diagnostics: &[Diagnostic]) {
-        match &self.path {
-            Some(path) => {
-                self.base_reporter.build_completes(diagnostics);
-                let mut file = OpenOptions::new()
-                    .write(true)
-                    .create(true)
-                    .truncate(true)
-                    .open(path)
-                    .unwrap();
-                writeln!(
-                    file,
-                    "{{\"completed\":true,\"diagnostics\":{}}}",
-                    serde_json::to_string(diagnostics).unwrap()
-                )
-                .unwrap();
+    fn report_completion(&self, project_diagnostics: &[(Option<ProjectName>, &[Diagnostic])]) {
+        let output = match &self.diagnostics_format {
+            Some(options) => {
+                let mut report = options.new_report();
+                for (project_name, diagnostics) in project_diagnostics {
+                    let project = project_name.map(|project_name| project_name.to_string());
+                    for diagnostic in *diagnostics {
+                        report.add_diagnostic(project.as_deref(), diagnostic);
+                    }
+                }
+                options.render(report)
             }
             None => {
-                println!(
-                    "{{\"completed\":true,\"diagnostics\":{}}}",
-                    serde_json::to_string(diagnostics).unwrap()
-                );
+                let diagnostics = project_diagnostics
+                    .iter()
+                    .flat_map(|(_, diagnostics)| diagnostics.iter())
+                    .collect::<Vec<_>>();
+                format!(
+                    "{{\"completed\":true,\"diagnostics\":{}}}\n",
+                    serde_json::to_string(&diagnostics).unwrap()
+                )
             }
-        }
+        };
+        self.write(&output);
     }
 
-    fn build_errors(&self, error: &Error) {
+    fn write(&self, output: &str) {
         match &self.path {
             Some(path) => {
-                self.base_reporter.build_errors(error);
-
                 let mut file = OpenOptions::new()
                     .write(true)
                     .create(true)
                     .truncate(true)
                     .open(path)
                     .unwrap();
-                writeln!(
-                    file,
-                    "{{\"completed\":false,\"error\":{}}}",
-                    serde_json::to_string(error).unwrap()
-                )
-                .unwrap();
+                write!(file, "{output}").unwrap();
             }
-            None => {
-                println!(
-                    "{{\"completed\":false,\"error\":{}}}",
-                    serde_json::to_string(error).unwrap()
-                );
+            None => print!("{output}"),
+        }
+    }
+}
+
+impl DiagnosticsFormatOptions {
+    fn new_report(&self) -> DiagnosticReport<impl Sources + '_> {
+        DiagnosticReport::new(|source_location| {
+            source_for_location(&self.root_dir, source_location, self.source_reader.as_ref())
+                .map(|source| source.to_text_source())
+        })
+    }
+
+    fn render(&self, report: DiagnosticReport<impl Sources>) -> String {
+        let output = report.render(self.format, "relay-compiler");
+        match self.format {
+            // One annotation per line, already newline-terminated.
+            DiagnosticsFormat::Github => output,
+            DiagnosticsFormat::Json | DiagnosticsFormat::Sarif => format!("{output}\n"),
+        }
+    }
+
+    fn render_error(&self, error: &Error) -> Option<String> {
+        let mut report = self.new_report();
+        match error {
+            // The build that cancelled this one reports instead.
+            Error::Cancelled => return None,
+            Error::DiagnosticsError { errors } => {
+                for diagnostic in errors {
+                    report.add_diagnostic(None, diagnostic);
+                }
             }
+            Error::BuildProjectsErrors { errors } => {
+                for error in errors {
+                    match error {
+                        BuildProjectError::ValidationErrors {
+                            errors,
+                            project_name,
+                        } => {
+                            let project = project_name.to_string();
+                            for diagnostic in errors {
+                                report.add_diagnostic(Some(&project), diagnostic);
+                            }
+                        }
+                        BuildProjectError::PersistErrors {
+                            errors,
+                            project_name,
+                        } => {
+                            let project = project_name.to_string();
+                            for error in errors {
+                                report.add_error(Some(&project), error.to_string());
+                            }
+                        }
+                        error => report.add_error(None, error.to_string()),
+                    }
+                }
+            }
+            error => report.add_error(None, error.to_string()),
+        }
+        Some(self.render(report))
+    }
+}
+
+impl StatusReporter for JSONStatusReporter {
+    fn build_starts(&self) {}
+
+    fn build_completes(&self, diagnostics: &[Diagnostic]) {
+        if self.path.is_some() {
+            self.base_reporter.build_completes(diagnostics);
+        }
+        self.report_completion(&[(None, diagnostics)]);
+    }
+
+    fn build_completes_for_projects(&self, project_diagnostics: &ProjectDiagnostics) {
+        if self.path.is_some() {
+            self.base_reporter
+                .build_completes_for_projects(project_diagnostics);
+        }
+        self.report_completion(
+            &project_diagnostics
+                .iter()
+                .map(|(project_name, diagnostics)| (Some(project_name), diagnostics))
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    fn build_errors(&self, error: &Error) {
+        if self.path.is_some() {
+            self.base_reporter.build_errors(error);
         }
+        let output = match &self.diagnostics_format {
+            Some(options) => match options.render_error(error) {
+                Some(output) => output,
+                None => return,
+            },
+            None => format!(
+                "{{\"completed\":false,\"error\":{}}}\n",
+                serde_json::to_string(error).unwrap()
+            ),
+        };
+        self.write(&output);
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use common::Location;
+    use intern::string_key::Intern;
+
     use super::*;
 
     fn fresh_build_status() -> BuildStatus {
@@ -779,4 +1076,117 @@ mod tests {
             )
             .expect("waiter task panicked");
     }
+
+    /// Status and diagnostics requests read the build result with
+    /// `peek_build_result`; a successful result must survive them so the
+    /// next `Write` still reports the build's diagnostics.
+    #[test]
+    fn test_peek_build_result_does_not_consume_success() {
+        let bs = fresh_build_status();
+        bs.build_completes(&[]);
+        assert!(matches!(
+            bs.peek_build_result(),
+            Some(BuildResult::Success(_))
+        ));
+        assert!(matches!(
+            bs.take_build_result(),
+            Some(BuildResult::Success(_))
+        ));
+        assert!(bs.peek_build_result().is_none());
+    }
+
+    #[test]
+    fn test_rebuild_requests_are_drained() {
+        let bs = fresh_build_status();
+        assert!(!bs.has_pending_rebuild_requests());
+
+        bs.request_rebuild([ProjectName::default()]);
+        assert!(bs.has_pending_rebuild_requests());
+        assert_eq!(bs.take_rebuild_requests(), vec![ProjectName::default()]);
+        assert!(!bs.has_pending_rebuild_requests());
+    }
+
+    /// A request only takes the rejections of the projects it requested.
+    #[test]
+    fn test_rejected_rebuilds_are_taken_per_project() {
+        let bs = fresh_build_status();
+        let first = ProjectName::from("first".intern());
+        let second = ProjectName::from("second".intern());
+
+        bs.reject_rebuild(first);
+        bs.reject_rebuild(second);
+        assert_eq!(bs.take_rejected_rebuilds(&[first]), vec![first]);
+        assert_eq!(bs.take_rejected_rebuilds(&[first]), vec![]);
+        assert_eq!(bs.take_rejected_rebuilds(&[first, second]), vec![second]);
+    }
+
+    /// Subscribers see a diagnostics event only when a build's diagnostics
+    /// differ from the previous build's, so an unchanged rebuild does not
+    /// make every overlay re-render.
+    #[test]
+    fn test_diagnostics_changed_published_only_on_change() {
+        let bs = fresh_build_status();
+        let mut events = bs.subscribe_events();
+
+        bs.build_completes(&[]);
+        bs.build_completes(&[]);
+        assert_eq!(
+            events.try_recv(),
+            Ok(BuildEvent::DiagnosticsChanged(vec![]))
+        );
+        assert!(events.try_recv().is_err());
+
+        bs.compiler_crashed("boom".to_string());
+        assert_eq!(
+            events.try_recv(),
+            Ok(BuildEvent::DiagnosticsChanged(vec![(
+                DiagnosticSeverity::ERROR,
+                "boom".to_string()
+            )]))
+        );
+        bs.build_completes(&[]);
+        assert_eq!(
+            events.try_recv(),
+            Ok(BuildEvent::DiagnosticsChanged(vec![]))
+        );
+    }
+
+    #[test]
+    fn test_project_diagnostics_are_grouped_by_project() {
+        let mut project_diagnostics = ProjectDiagnostics::default();
+        project_diagnostics.push(
+            ProjectName::from("first".intern()),
+            vec![
+                Diagnostic::error("one", Location::generated()),
+                Diagnostic::error("two", Location::generated()),
+            ],
+        );
+        project_diagnostics.push(ProjectName::from("second".intern()), vec![]);
+        project_diagnostics.push(
+            ProjectName::from("third".intern()),
+            vec![Diagnostic::error("three", Location::generated())],
+        );
+
+        assert_eq!(project_diagnostics.diagnostics().len(), 3);
+        assert_eq!(
+            project_diagnostics
+                .iter()
+                .map(|(project_name, diagnostics)| (
+                    project_name.to_string(),
+                    diagnostics
+                        .iter()
+                        .map(|diagnostic| diagnostic.message().to_string())
+                        .collect::<Vec<_>>()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "first".to_string(),
+                    vec!["one".to_string(), "two".to_string()]
+                ),
+                ("second".to_string(), vec![]),
+                ("third".to_string(), vec!["three".to_string()]),
+            ]
+        );
+    }
 }
@@ -4,7 +4,7 @@
  * This source code is licensed under the MIT license found in the
  * LICENSE file in the root directory of this source tree.
  *
- * @generated SignedSource<<f79a30f1b5207f484f90191102ee4253>>
+ * @generated SignedSource<<b6b6812b5ac067ef90fdcd939f214cdb>>
  */
 
 mod relay_compiler_integration;
@@ -264,6 +264,13 @@ async fn incremental_file_deletion() {
     test_fixture(transform_fixture, file!(), "incremental_file_deletion.input", "relay_compiler_integration/fixtures/incremental_file_deletion.expected", input, expected).await;
 }
 
+#[tokio::test]
+async fn incremental_fragment_spread_count_lint_rule() {
+    let input = include_str!("relay_compiler_integration/fixtures/incremental_fragment_spread_count_lint_rule.input");
+    let expected = include_str!("relay_compiler_integration/fixtures/incremental_fragment_spread_count_lint_rule.expected");
+    test_fixture(transform_fixture, file!(), "incremental_fragment_spread_count_lint_rule.input", "relay_compiler_integration/fixtures/incremental_fragment_spread_count_lint_rule.expected", input, expected).await;
+}
+
 #[tokio::test]
 async fn incremental_fuzz_multiproject_cross_fragment_change() {
     let input = include_str!("relay_compiler_integration/fixtures/incremental_fuzz_multiproject_cross_fragment_change.input");
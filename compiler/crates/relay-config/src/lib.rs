@@ -13,6 +13,7 @@ mod connection_interface;
 mod defer_stream_interface;
 mod diagnostic_report_config;
 mod js_module_format;
+mod lint_rules_config;
 mod module_import_config;
 mod non_node_id_fields_config;
 mod project_config;
@@ -25,6 +26,10 @@ pub use defer_stream_interface::DeferStreamInterface;
 pub use diagnostic_report_config::DiagnosticLevel;
 pub use diagnostic_report_config::DiagnosticReportConfig;
 pub use js_module_format::JsModuleFormat;
+pub use lint_rules_config::FragmentSpreadCountRuleConfig;
+pub use lint_rules_config::LintRulesConfig;
+pub use lint_rules_config::PluralFragmentOnLargeListRuleConfig;
+pub use lint_rules_config::QueryWithoutPreloadableRuleConfig;
 pub use module_import_config::ModuleImportConfig;
 pub use module_import_config::ModuleProvider;
 pub use module_import_config::Surface;
@@ -0,0 +1,74 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::path::PathBuf;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::diagnostic_report_config::DiagnosticLevel;
+
+/// Configuration of the optional lint rules of a project. Rules are disabled
+/// unless they are configured, and report diagnostics with their configured
+/// severity. Whether these fail the build is decided by the `criticalLevel` of
+/// `diagnosticReportConfig`.
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct LintRulesConfig {
+    /// Reports spreads of `@relay(plural: true)` fragments on lists that are
+    /// fetched with a `first` or `last` argument above `maxListSize`.
+    #[serde(default)]
+    pub plural_fragment_on_large_list: Option<PluralFragmentOnLargeListRuleConfig>,
+
+    /// Reports queries without the `@preloadable` directive in the given paths.
+    #[serde(default)]
+    pub query_without_preloadable: Option<QueryWithoutPreloadableRuleConfig>,
+
+    /// Reports fragments that are spread more than `maxSpreads` times in the
+    /// project.
+    #[serde(default)]
+    pub fragment_spread_count: Option<FragmentSpreadCountRuleConfig>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct PluralFragmentOnLargeListRuleConfig {
+    /// Severity of the reported diagnostics.
+    #[serde(default = "default_lint_rule_severity")]
+    pub severity: DiagnosticLevel,
+
+    /// Largest number of items a plural fragment may be spread on.
+    pub max_list_size: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct QueryWithoutPreloadableRuleConfig {
+    /// Severity of the reported diagnostics.
+    #[serde(default = "default_lint_rule_severity")]
+    pub severity: DiagnosticLevel,
+
+    /// Directories, relative to the `root` of the config (the directory of the
+    /// config file by default), in which queries must be `@preloadable`.
+    pub paths: Vec<PathBuf>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct FragmentSpreadCountRuleConfig {
+    /// Severity of the reported diagnostics.
+    #[serde(default = "default_lint_rule_severity")]
+    pub severity: DiagnosticLevel,
+
+    /// Largest number of times a fragment may be spread.
+    pub max_spreads: usize,
+}
+
+fn default_lint_rule_severity() -> DiagnosticLevel {
+    DiagnosticLevel::Warning
+}
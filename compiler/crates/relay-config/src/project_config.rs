@@ -37,6 +37,7 @@ use crate::TypegenLanguage;
 use crate::connection_interface::ConnectionInterface;
 use crate::defer_stream_interface::DeferStreamInterface;
 use crate::diagnostic_report_config::DiagnosticReportConfig;
+use crate::lint_rules_config::LintRulesConfig;
 use crate::module_import_config::ModuleImportConfig;
 use crate::module_import_config::ModuleProvider;
 use crate::non_node_id_fields_config::NonNodeIdFieldsConfig;
@@ -357,6 +358,8 @@ pub struct ProjectConfig {
     pub module_import_config: ModuleImportConfig,
     /// Configuration for diagnostic reports.
     pub diagnostic_report_config: DiagnosticReportConfig,
+    /// Configuration for optional lint rules.
+    pub lint_rules: LintRulesConfig,
     /// Configuration for resolvers schema module.
     pub resolvers_schema_module: Option<ResolversSchemaModuleConfig>,
     /// Command to run after code generation.
@@ -394,6 +397,7 @@ impl Default for ProjectConfig {
             js_module_format: Default::default(),
             module_import_config: Default::default(),
             diagnostic_report_config: Default::default(),
+            lint_rules: Default::default(),
             resolvers_schema_module: Default::default(),
             codegen_command: Default::default(),
             get_custom_path_for_artifact: None,
@@ -428,6 +432,7 @@ impl Debug for ProjectConfig {
             js_module_format,
             module_import_config,
             diagnostic_report_config,
+            lint_rules,
             resolvers_schema_module,
             codegen_command,
             get_custom_path_for_artifact: _,
@@ -457,6 +462,7 @@ impl Debug for ProjectConfig {
             .field("js_module_format", js_module_format)
             .field("module_import_config", module_import_config)
             .field("diagnostic_report_config", diagnostic_report_config)
+            .field("lint_rules", lint_rules)
             .field("resolvers_schema_module", resolvers_schema_module)
             .field("codegen_command", codegen_command)
             .field("relativize_js_module_paths", relativize_js_module_paths)
@@ -0,0 +1,307 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Utilities for providing the call hierarchy feature, where the calls are
+//! the fragment spreads between operations and fragments.
+
+use common::Location as IRLocation;
+use dependency_analyzer::get_ir_selection_references;
+use graphql_ir::ExecutableDefinitionName;
+use graphql_ir::FragmentDefinitionName;
+use graphql_ir::OperationDefinitionName;
+use graphql_ir::Program;
+use graphql_ir::Selection;
+use lsp_types::CallHierarchyIncomingCall;
+use lsp_types::CallHierarchyItem;
+use lsp_types::CallHierarchyOutgoingCall;
+use lsp_types::SymbolKind;
+use lsp_types::request::CallHierarchyIncomingCalls;
+use lsp_types::request::CallHierarchyOutgoingCalls;
+use lsp_types::request::CallHierarchyPrepare;
+use lsp_types::request::Request;
+use schema::Schema;
+
+use crate::FeatureResolutionInfo;
+use crate::lsp_runtime_error::LSPRuntimeError;
+use crate::lsp_runtime_error::LSPRuntimeResult;
+use crate::node_resolution_info::NodeKind;
+use crate::references::ReferenceFinder;
+use crate::server::GlobalState;
+
+/// A fragment spread edge of the dependency graph, from the perspective of
+/// the definition on the other end of the edge.
+#[derive(Debug, PartialEq)]
+pub(crate) struct DefinitionCall {
+    pub(crate) definition: ExecutableDefinitionName,
+    /// Locations of the fragment spreads, within the spreading definition
+    pub(crate) spread_locations: Vec<IRLocation>,
+}
+
+/// The definition of an item created by [`on_prepare_call_hierarchy`], that
+/// is stored in the data of the item, rather than derived from its kind and
+/// name.
+pub(crate) fn get_item_definition(
+    item: &CallHierarchyItem,
+) -> LSPRuntimeResult<ExecutableDefinitionName> {
+    let data = item.data.clone().ok_or_else(|| {
+        LSPRuntimeError::UnexpectedError(format!(
+            "Missing data of the call hierarchy item {}.",
+            item.name
+        ))
+    })?;
+    serde_json::from_value(data).map_err(|err| {
+        LSPRuntimeError::UnexpectedError(format!("Invalid call hierarchy item data: {err}"))
+    })
+}
+
+/// Resolve a [`CallHierarchyPrepare`] request to the fragment or operation
+/// at the position, either its definition or a spread of a fragment.
+pub fn on_prepare_call_hierarchy(
+    state: &impl GlobalState,
+    params: <CallHierarchyPrepare as Request>::Params,
+) -> LSPRuntimeResult<<CallHierarchyPrepare as Request>::Result> {
+    let FeatureResolutionInfo::GraphqlNode(node_resolution_info) =
+        state.resolve_node(&params.text_document_position_params)?
+    else {
+        return Ok(None);
+    };
+    let definition: ExecutableDefinitionName = match node_resolution_info.kind {
+        NodeKind::FragmentDefinition(fragment) => {
+            FragmentDefinitionName(fragment.name.value).into()
+        }
+        NodeKind::FragmentSpread(fragment_name) => FragmentDefinitionName(fragment_name).into(),
+        NodeKind::OperationDefinition(operation) => match operation.name {
+            Some(name) => OperationDefinitionName(name.value).into(),
+            None => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+
+    let project_name = state
+        .extract_project_name_from_uri(&params.text_document_position_params.text_document.uri)?;
+    let program = state.get_program(&project_name)?;
+    if get_definition_name_location(&program, definition).is_none() {
+        return Ok(None);
+    }
+
+    Ok(Some(vec![create_call_hierarchy_item(
+        state, &program, definition,
+    )?]))
+}
+
+/// Resolve a [`CallHierarchyIncomingCalls`] request to the operations and
+/// fragments that spread the fragment of the item.
+pub fn on_call_hierarchy_incoming_calls(
+    state: &impl GlobalState,
+    params: <CallHierarchyIncomingCalls as Request>::Params,
+) -> LSPRuntimeResult<<CallHierarchyIncomingCalls as Request>::Result> {
+    let ExecutableDefinitionName::FragmentDefinitionName(fragment_name) =
+        get_item_definition(&params.item)?
+    else {
+        // Operations cannot be spread
+        return Ok(None);
+    };
+    let project_name = state.extract_project_name_from_uri(&params.item.uri)?;
+    let program = state.get_program(&project_name)?;
+
+    let incoming_calls = get_incoming_calls(&program, fragment_name)
+        .into_iter()
+        .map(|call| {
+            Ok(CallHierarchyIncomingCall {
+                from: create_call_hierarchy_item(state, &program, call.definition)?,
+                from_ranges: transform_spread_locations(state, call.spread_locations)?,
+            })
+        })
+        .collect::<LSPRuntimeResult<Vec<_>>>()?;
+
+    Ok(Some(incoming_calls))
+}
+
+/// Resolve a [`CallHierarchyOutgoingCalls`] request to the fragments spread
+/// by the operation or fragment of the item.
+pub fn on_call_hierarchy_outgoing_calls(
+    state: &impl GlobalState,
+    params: <CallHierarchyOutgoingCalls as Request>::Params,
+) -> LSPRuntimeResult<<CallHierarchyOutgoingCalls as Request>::Result> {
+    let project_name = state.extract_project_name_from_uri(&params.item.uri)?;
+    let program = state.get_program(&project_name)?;
+    let definition = get_item_definition(&params.item)?;
+
+    let outgoing_calls = get_outgoing_calls(&program, definition)
+        .ok_or(LSPRuntimeError::ExpectedError)?
+        .into_iter()
+        .map(|call| {
+            Ok(CallHierarchyOutgoingCall {
+                to: create_call_hierarchy_item(state, &program, call.definition)?,
+                from_ranges: transform_spread_locations(state, call.spread_locations)?,
+            })
+        })
+        .collect::<LSPRuntimeResult<Vec<_>>>()?;
+
+    Ok(Some(outgoing_calls))
+}
+
+/// Find the operations and fragments of the program that depend on a fragment.
+pub(crate) fn get_incoming_calls(
+    program: &Program,
+    fragment_name: FragmentDefinitionName,
+) -> Vec<DefinitionCall> {
+    let fragment: ExecutableDefinitionName = fragment_name.into();
+    let operations = program.operations().map(|operation| {
+        (
+            ExecutableDefinitionName::from(operation.name.item),
+            operation.name.location,
+            &operation.selections,
+        )
+    });
+    let fragments = program.fragments().map(|fragment| {
+        (
+            ExecutableDefinitionName::from(fragment.name.item),
+            fragment.name.location,
+            &fragment.selections,
+        )
+    });
+
+    let mut calls = operations
+        .chain(fragments)
+        .filter(|(_, _, selections)| {
+            get_ir_selection_references(&program.schema, selections).contains(&fragment)
+        })
+        .map(|(definition, location, selections)| DefinitionCall {
+            definition,
+            spread_locations: get_spread_locations(selections, fragment_name, location),
+        })
+        .collect::<Vec<_>>();
+    calls.sort_by_key(|call| call.definition.to_string());
+    calls
+}
+
+/// Find the fragments that an operation or fragment of the program depends on.
+/// Returns `None` if the definition is not part of the program.
+pub(crate) fn get_outgoing_calls(
+    program: &Program,
+    definition: ExecutableDefinitionName,
+) -> Option<Vec<DefinitionCall>> {
+    let (location, selections) = match definition {
+        ExecutableDefinitionName::OperationDefinitionName(name) => {
+            let operation = program.operation(name)?;
+            (operation.name.location, &operation.selections)
+        }
+        ExecutableDefinitionName::FragmentDefinitionName(name) => {
+            let fragment = program.fragment(name)?;
+            (fragment.name.location, &fragment.selections)
+        }
+    };
+
+    let mut calls = get_ir_selection_references(&program.schema, selections)
+        .into_iter()
+        .filter_map(|reference| match reference {
+            ExecutableDefinitionName::FragmentDefinitionName(fragment_name)
+                if program.fragment(fragment_name).is_some() =>
+            {
+                Some(DefinitionCall {
+                    definition: reference,
+                    spread_locations: get_spread_locations(selections, fragment_name, location),
+                })
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    calls.sort_by_key(|call| call.definition.to_string());
+    Some(calls)
+}
+
+/// Dependencies on the fragments of Relay Resolvers are implicit, and have no
+/// spread to point to, so they fall back to the name of the definition.
+fn get_spread_locations(
+    selections: &[Selection],
+    fragment_name: FragmentDefinitionName,
+    definition_location: IRLocation,
+) -> Vec<IRLocation> {
+    let spread_locations =
+        ReferenceFinder::get_references_to_fragment_in_selections(selections, fragment_name.0);
+    if spread_locations.is_empty() {
+        vec![definition_location]
+    } else {
+        spread_locations
+    }
+}
+
+fn get_definition_name_location(
+    program: &Program,
+    definition: ExecutableDefinitionName,
+) -> Option<IRLocation> {
+    match definition {
+        ExecutableDefinitionName::OperationDefinitionName(name) => program
+            .operation(name)
+            .map(|operation| operation.name.location),
+        ExecutableDefinitionName::FragmentDefinitionName(name) => program
+            .fragment(name)
+            .map(|fragment| fragment.name.location),
+    }
+}
+
+fn create_call_hierarchy_item(
+    state: &impl GlobalState,
+    program: &Program,
+    definition: ExecutableDefinitionName,
+) -> LSPRuntimeResult<CallHierarchyItem> {
+    let (location, kind, detail) = match definition {
+        ExecutableDefinitionName::OperationDefinitionName(name) => {
+            let operation = program.operation(name).ok_or_else(|| {
+                LSPRuntimeError::UnexpectedError(format!("Unable to find operation {name}."))
+            })?;
+            (
+                operation.name.location,
+                SymbolKind::FUNCTION,
+                operation.kind.to_string(),
+            )
+        }
+        ExecutableDefinitionName::FragmentDefinitionName(name) => {
+            let fragment = program.fragment(name).ok_or_else(|| {
+                LSPRuntimeError::UnexpectedError(format!("Unable to find fragment {name}."))
+            })?;
+            (
+                fragment.name.location,
+                SymbolKind::CLASS,
+                format!(
+                    "fragment on {}",
+                    program.schema.get_type_name(fragment.type_condition)
+                ),
+            )
+        }
+    };
+    let location = state.transform_relay_location_in_editor_to_lsp_location(location)?;
+
+    Ok(CallHierarchyItem {
+        name: definition.to_string(),
+        kind,
+        tags: None,
+        detail: Some(detail),
+        uri: location.uri,
+        range: location.range,
+        selection_range: location.range,
+        data: serde_json::to_value(definition).ok(),
+    })
+}
+
+fn transform_spread_locations(
+    state: &impl GlobalState,
+    spread_locations: Vec<IRLocation>,
+) -> LSPRuntimeResult<Vec<lsp_types::Range>> {
+    spread_locations
+        .into_iter()
+        .map(|location| {
+            state
+                .transform_relay_location_in_editor_to_lsp_location(location)
+                .map(|location| location.range)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test;
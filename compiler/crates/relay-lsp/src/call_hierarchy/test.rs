@@ -0,0 +1,160 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use common::SourceLocationKey;
+use graphql_ir::ExecutableDefinitionName;
+use graphql_ir::FragmentDefinitionName;
+use graphql_ir::OperationDefinitionName;
+use graphql_ir::Program;
+use graphql_ir::build;
+use graphql_syntax::parse_executable;
+use intern::string_key::Intern;
+use lsp_types::CallHierarchyItem;
+use lsp_types::Range;
+use lsp_types::SymbolKind;
+use lsp_types::Uri;
+use relay_test_schema::get_test_schema;
+
+use super::DefinitionCall;
+use super::get_incoming_calls;
+use super::get_item_definition;
+use super::get_outgoing_calls;
+
+const SOURCE: &str = r#"
+    query TestQuery {
+        me {
+            ...Parent_user
+            ... on User {
+                ...Child_user
+            }
+        }
+    }
+
+    fragment Parent_user on User {
+        ...Child_user
+        friends(first: 1) {
+            edges {
+                node {
+                    ...Child_user
+                }
+            }
+        }
+    }
+
+    fragment Child_user on User {
+        name
+    }
+"#;
+
+fn build_test_program() -> Program {
+    let document = parse_executable(SOURCE, SourceLocationKey::Generated).unwrap();
+    let ir = build(&get_test_schema(), &document.definitions).unwrap();
+    Program::from_definitions(get_test_schema(), ir)
+}
+
+/// Returns the name of each call, with the source text of its spreads.
+fn describe_calls(calls: &[DefinitionCall]) -> Vec<(String, Vec<&'static str>)> {
+    calls
+        .iter()
+        .map(|call| {
+            (
+                call.definition.to_string(),
+                call.spread_locations
+                    .iter()
+                    .map(|location| {
+                        &SOURCE[location.span().start as usize..location.span().end as usize]
+                    })
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn incoming_calls() {
+    let program = build_test_program();
+    let calls = get_incoming_calls(&program, FragmentDefinitionName("Child_user".intern()));
+    assert_eq!(
+        describe_calls(&calls),
+        vec![
+            ("Parent_user".to_string(), vec!["Child_user", "Child_user"]),
+            ("TestQuery".to_string(), vec!["Child_user"]),
+        ]
+    );
+
+    let calls = get_incoming_calls(&program, FragmentDefinitionName("Parent_user".intern()));
+    assert_eq!(
+        describe_calls(&calls),
+        vec![("TestQuery".to_string(), vec!["Parent_user"])]
+    );
+}
+
+#[test]
+fn incoming_calls_of_unknown_fragment() {
+    let program = build_test_program();
+    let calls = get_incoming_calls(&program, FragmentDefinitionName("Unknown".intern()));
+    assert!(calls.is_empty());
+}
+
+#[test]
+fn outgoing_calls() {
+    let program = build_test_program();
+    let calls = get_outgoing_calls(
+        &program,
+        OperationDefinitionName("TestQuery".intern()).into(),
+    )
+    .unwrap();
+    assert_eq!(
+        describe_calls(&calls),
+        vec![
+            ("Child_user".to_string(), vec!["Child_user"]),
+            ("Parent_user".to_string(), vec!["Parent_user"]),
+        ]
+    );
+
+    let calls = get_outgoing_calls(
+        &program,
+        FragmentDefinitionName("Child_user".intern()).into(),
+    )
+    .unwrap();
+    assert!(calls.is_empty());
+}
+
+#[test]
+fn outgoing_calls_of_unknown_definition() {
+    let program = build_test_program();
+    assert!(
+        get_outgoing_calls(&program, FragmentDefinitionName("Unknown".intern()).into()).is_none()
+    );
+}
+
+#[test]
+fn item_definition_round_trips_through_data() {
+    let item = |definition: ExecutableDefinitionName| CallHierarchyItem {
+        name: definition.to_string(),
+        // The kind of the item is only presentational, and is not used to
+        // identify the definition.
+        kind: SymbolKind::CLASS,
+        tags: None,
+        detail: None,
+        uri: "file:///test.js".parse::<Uri>().unwrap(),
+        range: Range::default(),
+        selection_range: Range::default(),
+        data: serde_json::to_value(definition).ok(),
+    };
+
+    let operation = OperationDefinitionName("TestQuery".intern()).into();
+    assert_eq!(get_item_definition(&item(operation)).unwrap(), operation);
+    let fragment = FragmentDefinitionName("Child_user".intern()).into();
+    assert_eq!(get_item_definition(&item(fragment)).unwrap(), fragment);
+
+    let item_without_data = CallHierarchyItem {
+        data: None,
+        ..item(fragment)
+    };
+    assert!(get_item_definition(&item_without_data).is_err());
+}
@@ -5,21 +5,32 @@
  * LICENSE file in the root directory of this source tree.
  */
 
-mod create_name_suggestion;
+pub(crate) mod create_name_suggestion;
+mod extract_fragment;
+mod inline_fragment;
+mod quick_fix;
 
 use std::collections::HashMap;
 use std::collections::HashSet;
 
+use common::SourceLocationKey;
 use common::Span;
 use create_name_suggestion::DefinitionNameSuffix;
 use create_name_suggestion::create_default_name;
 use create_name_suggestion::create_default_name_with_index;
 use create_name_suggestion::create_impactful_name;
 use create_name_suggestion::create_name_wrapper;
+use extract_fragment::ExtractFragmentContext;
+use extract_fragment::get_extract_fragment_code_actions;
+use extract_graphql::JavaScriptSourceFeature;
 use graphql_syntax::ExecutableDefinition;
+use graphql_syntax::parse_executable_with_error_recovery_and_parser_features;
+use inline_fragment::InlineFragmentContext;
+use inline_fragment::get_inline_fragment_code_actions;
 use intern::Lookup;
 use lsp_types::CodeAction;
 use lsp_types::CodeActionOrCommand;
+use lsp_types::CodeActionParams;
 use lsp_types::Diagnostic;
 use lsp_types::Position;
 use lsp_types::Range;
@@ -29,6 +40,10 @@ use lsp_types::Uri;
 use lsp_types::WorkspaceEdit;
 use lsp_types::request::CodeActionRequest;
 use lsp_types::request::Request;
+use quick_fix::QuickFixContext;
+use quick_fix::get_quick_fixes;
+use relay_compiler::ProjectName;
+use relay_compiler::get_parser_features;
 use resolution_path::FragmentDefinitionPath;
 use resolution_path::IdentParent;
 use resolution_path::IdentPath;
@@ -43,6 +58,7 @@ use crate::lsp_runtime_error::LSPRuntimeError;
 use crate::lsp_runtime_error::LSPRuntimeResult;
 use crate::server::GlobalState;
 use crate::utils::is_file_uri_in_dir;
+use crate::utils::position_to_offset;
 
 pub fn on_code_action(
     state: &impl GlobalState,
@@ -61,6 +77,23 @@ pub fn on_code_action(
         }
     }
 
+    let mut code_actions = get_graphql_code_actions(state, &params).unwrap_or_default();
+    if let Ok(rename_code_actions) = get_rename_code_actions(state, params) {
+        code_actions.extend(rename_code_actions);
+    }
+
+    if code_actions.is_empty() {
+        Err(LSPRuntimeError::ExpectedError)
+    } else {
+        Ok(Some(code_actions))
+    }
+}
+
+fn get_rename_code_actions(
+    state: &impl GlobalState,
+    params: CodeActionParams,
+) -> LSPRuntimeResult<Vec<CodeActionOrCommand>> {
+    let uri = params.text_document.uri.clone();
     let definitions = state.resolve_executable_definitions(&params.text_document.uri)?;
 
     let text_document_position_params = TextDocumentPositionParams {
@@ -73,9 +106,109 @@ pub fn on_code_action(
     let path = document.resolve((), position_span);
 
     let used_definition_names = get_definition_names(&definitions);
-    let result = get_code_actions(path, used_definition_names, uri, params.range)
+    get_code_actions(path, used_definition_names, uri, params.range)
+        .ok_or(LSPRuntimeError::ExpectedError)
+}
+
+/// Quick fixes at the start of the range, and refactorings of the selected
+/// range of the GraphQL literal.
+fn get_graphql_code_actions(
+    state: &impl GlobalState,
+    params: &CodeActionParams,
+) -> LSPRuntimeResult<Vec<CodeActionOrCommand>> {
+    let uri = &params.text_document.uri;
+    let position = params.range.start;
+    let project_name = state.extract_project_name_from_uri(uri)?;
+    let config = state.get_config();
+    let project_config = config
+        .projects
+        .get(&ProjectName::from(project_name))
+        .ok_or_else(|| {
+            LSPRuntimeError::UnexpectedError(format!(
+                "Unable to get project config for project {project_name}."
+            ))
+        })?;
+    let schema = state.get_schema(&project_name)?;
+    let program = state.get_program(&project_name).ok();
+
+    let javascript_sources = state.get_synced_javascript_sources(uri)?;
+    let (index, graphql_source) = javascript_sources
+        .iter()
+        .enumerate()
+        .find_map(|(index, feature)| match feature {
+            JavaScriptSourceFeature::GraphQL(graphql_source) => {
+                let range = graphql_source.text_source().to_range();
+                (position >= range.start && position <= range.end)
+                    .then_some((index, graphql_source))
+            }
+            _ => None,
+        })
+        .ok_or(LSPRuntimeError::ExpectedError)?;
+    let text_source = graphql_source.text_source();
+    let document = parse_executable_with_error_recovery_and_parser_features(
+        &text_source.text,
+        SourceLocationKey::embedded(uri.as_str(), index),
+        get_parser_features(project_config),
+    )
+    .item;
+    let offset = position_to_offset(&position, 1, text_source.line_index, &text_source.text)
+        .ok_or(LSPRuntimeError::ExpectedError)?;
+
+    let context = QuickFixContext {
+        uri,
+        text_source,
+        schema: &schema,
+        program: program.as_ref(),
+        diagnostics: &params.context.diagnostics,
+    };
+    let mut code_actions = get_quick_fixes(&context, &document, Span::new(offset, offset));
+    if let Some(program) = &program {
+        let context = InlineFragmentContext {
+            uri,
+            text_source,
+            program,
+        };
+        code_actions.extend(get_inline_fragment_code_actions(
+            &context,
+            &document,
+            Span::new(offset, offset),
+        ));
+    }
+
+    if params.range.end > params.range.start {
+        let end_offset = position_to_offset(
+            &params.range.end,
+            1,
+            text_source.line_index,
+            &text_source.text,
+        )
         .ok_or(LSPRuntimeError::ExpectedError)?;
-    Ok(Some(result))
+        let mut used_fragment_names = document
+            .definitions
+            .iter()
+            .filter_map(|definition| match definition {
+                ExecutableDefinition::Fragment(fragment) => Some(fragment.name.value),
+                ExecutableDefinition::Operation(_) => None,
+            })
+            .collect::<HashSet<_>>();
+        if let Some(program) = &program {
+            used_fragment_names.extend(program.fragments().map(|fragment| fragment.name.item.0));
+        }
+        let context = ExtractFragmentContext {
+            uri,
+            text_source,
+            schema: &schema,
+            used_fragment_names: &used_fragment_names,
+            document_end: state.get_synced_javascript_source_end(uri)?,
+        };
+        code_actions.extend(get_extract_fragment_code_actions(
+            &context,
+            &document,
+            Span::new(offset, end_offset),
+        ));
+    }
+
+    Ok(code_actions)
 }
 
 pub fn get_code_actions_from_diagnostic(
@@ -51,6 +51,35 @@ pub fn create_default_fragment_name(file_name: &str) -> Option<String> {
     extract_module_name(file_name)
 }
 
+/// Suggests a name for a new fragment on a type, following the `<Module>_<prop>`
+/// convention, with an index when the name is already used.
+pub fn create_default_fragment_name_for_type(
+    file_name: &str,
+    type_name: &str,
+    is_name_used: impl Fn(&str) -> bool,
+) -> Option<String> {
+    let fragment_name = format!(
+        "{}_{}",
+        extract_module_name(file_name)?,
+        to_prop_name(type_name)
+    );
+    if !is_name_used(&fragment_name) {
+        return Some(fragment_name);
+    }
+    (2..)
+        .map(|index| format!("{fragment_name}{index}"))
+        .find(|name| !is_name_used(name))
+}
+
+/// Props are named after the type of the fragment, in camel case.
+pub fn to_prop_name(type_name: &str) -> String {
+    let mut chars = type_name.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
 /// This function will create a name suggestion for operation/fragment
 /// in a file adding an incremental index.
 /// Suggested name is {prefix}{index}{Query|Mutation|Subscription},
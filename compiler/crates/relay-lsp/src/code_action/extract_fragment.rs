@@ -0,0 +1,352 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! The "extract selection into fragment" refactoring, which moves selected
+//! fields into a new fragment and replaces them with a spread of it.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::Path;
+
+use common::Span;
+use common::TextSource;
+use graphql_ir::ARGUMENT_DEFINITION;
+use graphql_syntax::ExecutableDefinition;
+use graphql_syntax::ExecutableDocument;
+use graphql_syntax::Selection;
+use intern::Lookup;
+use intern::string_key::Intern;
+use intern::string_key::StringKey;
+use lsp_types::CodeAction;
+use lsp_types::CodeActionKind;
+use lsp_types::CodeActionOrCommand;
+use lsp_types::CreateFile;
+use lsp_types::DocumentChangeOperation;
+use lsp_types::DocumentChanges;
+use lsp_types::OneOf;
+use lsp_types::OptionalVersionedTextDocumentIdentifier;
+use lsp_types::Position;
+use lsp_types::Range;
+use lsp_types::ResourceOp;
+use lsp_types::TextDocumentEdit;
+use lsp_types::TextEdit;
+use lsp_types::Uri;
+use lsp_types::WorkspaceEdit;
+use relay_transforms::extract_module_name;
+use schema::SDLSchema;
+use schema::Schema;
+use schema::Type;
+
+use super::create_name_suggestion::create_default_fragment_name_for_type;
+use super::create_name_suggestion::to_prop_name;
+use super::quick_fix::collect_selection_variables;
+use crate::node_resolution_info::create_node_resolution_info;
+
+/// Everything the refactoring needs to know about the document it edits.
+pub(crate) struct ExtractFragmentContext<'a> {
+    pub(crate) uri: &'a Uri,
+    pub(crate) text_source: &'a TextSource,
+    pub(crate) schema: &'a SDLSchema,
+    /// Names of the fragments of the project and of the document, which the
+    /// name of the new fragment must not clash with
+    pub(crate) used_fragment_names: &'a HashSet<StringKey>,
+    /// The end of the JavaScript document, where the new fragment is added
+    pub(crate) document_end: Position,
+}
+
+/// The selections to extract, with the type they are selected on.
+struct ExtractedSelections<'a> {
+    parent_type: Type,
+    selections: &'a [Selection],
+    span: Span,
+}
+
+/// Extract the selections of the span into a fragment, either in the same
+/// document or in a new component file stub next to it.
+pub(crate) fn get_extract_fragment_code_actions(
+    context: &ExtractFragmentContext<'_>,
+    document: &ExecutableDocument,
+    span: Span,
+) -> Vec<CodeActionOrCommand> {
+    let Some(definition) = document
+        .definitions
+        .iter()
+        .find(|definition| definition.location().contains(span))
+    else {
+        return vec![];
+    };
+    let Some(extracted) = find_extracted_selections(context.schema, document, definition, span)
+    else {
+        return vec![];
+    };
+    let Some(module_name) = extract_module_name(context.uri.path().as_str()) else {
+        return vec![];
+    };
+    let type_name = context.schema.get_type_name(extracted.parent_type);
+    let prop_name = to_prop_name(type_name.lookup());
+    let is_name_used = |name: &str| context.used_fragment_names.contains(&name.intern());
+    let Some(fragment_name) = create_default_fragment_name_for_type(
+        context.uri.path().as_str(),
+        type_name.lookup(),
+        is_name_used,
+    ) else {
+        return vec![];
+    };
+
+    let mut code_actions = vec![];
+    let (fragment_text, spread_text) =
+        print_fragment(context, definition, &extracted, &fragment_name, type_name);
+    let mut changes = HashMap::new();
+    changes.insert(
+        context.uri.clone(),
+        vec![
+            TextEdit {
+                range: context.text_source.to_span_range(extracted.span),
+                new_text: spread_text,
+            },
+            TextEdit {
+                range: Range::new(context.document_end, context.document_end),
+                new_text: format!(
+                    "\nconst {fragment_name}Fragment = graphql`\n{}`;\n",
+                    indent(&fragment_text, 2)
+                ),
+            },
+        ],
+    );
+    code_actions.push(create_extract_code_action(
+        format!("Extract selection into fragment `{fragment_name}`"),
+        WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        },
+    ));
+
+    if let Some(component_uri) = get_component_uri(context.uri, &module_name, type_name)
+        && let Some(fragment_name) = create_default_fragment_name_for_type(
+            component_uri.path().as_str(),
+            type_name.lookup(),
+            is_name_used,
+        )
+    {
+        let component_name = format!("{module_name}{type_name}");
+        let (fragment_text, spread_text) =
+            print_fragment(context, definition, &extracted, &fragment_name, type_name);
+        let component_text = format!(
+            "import {{graphql, useFragment}} from 'react-relay';\n\nexport default function {component_name}({{{prop_name}}}) {{\n  const data = useFragment(\n    graphql`\n{}    `,\n    {prop_name},\n  );\n  return null;\n}}\n",
+            indent(&fragment_text, 6)
+        );
+        code_actions.push(create_extract_code_action(
+            format!("Extract selection into fragment `{fragment_name}` in a new component"),
+            WorkspaceEdit {
+                document_changes: Some(DocumentChanges::Operations(vec![
+                    DocumentChangeOperation::Edit(TextDocumentEdit {
+                        text_document: OptionalVersionedTextDocumentIdentifier {
+                            uri: context.uri.clone(),
+                            version: None,
+                        },
+                        edits: vec![OneOf::Left(TextEdit {
+                            range: context.text_source.to_span_range(extracted.span),
+                            new_text: spread_text,
+                        })],
+                    }),
+                    DocumentChangeOperation::Op(ResourceOp::Create(CreateFile {
+                        uri: component_uri.clone(),
+                        options: None,
+                        annotation_id: None,
+                    })),
+                    DocumentChangeOperation::Edit(TextDocumentEdit {
+                        text_document: OptionalVersionedTextDocumentIdentifier {
+                            uri: component_uri,
+                            version: None,
+                        },
+                        edits: vec![OneOf::Left(TextEdit {
+                            range: Range::default(),
+                            new_text: component_text,
+                        })],
+                    }),
+                ])),
+                ..Default::default()
+            },
+        ));
+    }
+
+    code_actions
+}
+
+/// Find the selections that are entirely within the span, in the innermost
+/// selection set that contains it. Selections that are only partially
+/// selected can't be extracted.
+fn find_extracted_selections<'a>(
+    schema: &SDLSchema,
+    document: &ExecutableDocument,
+    definition: &'a ExecutableDefinition,
+    span: Span,
+) -> Option<ExtractedSelections<'a>> {
+    let mut selection_set = match definition {
+        ExecutableDefinition::Operation(operation) => &operation.selections,
+        ExecutableDefinition::Fragment(fragment) => &fragment.selections,
+    };
+
+    loop {
+        let selections = &selection_set.items;
+        let start = selections
+            .iter()
+            .position(|selection| selection.span().end > span.start)?;
+        let end = selections
+            .iter()
+            .rposition(|selection| selection.span().start < span.end)?;
+        if start > end {
+            return None;
+        }
+        let first = selections[start].span();
+        let last = selections[end].span();
+        if span.start <= first.start && last.end <= span.end {
+            // The type of the selection set is the type at its opening brace,
+            // which is within the enclosing selections but none of its own.
+            let parent_type =
+                create_node_resolution_info(document.clone(), selection_set.start.span)
+                    .ok()?
+                    .type_path
+                    .resolve_leaf_type(schema)?;
+            return Some(ExtractedSelections {
+                parent_type,
+                selections: &selections[start..=end],
+                span: Span::new(first.start, last.end),
+            });
+        }
+        if start != end {
+            return None;
+        }
+        let child_selections = match &selections[start] {
+            Selection::LinkedField(linked_field) => &linked_field.selections,
+            Selection::InlineFragment(inline_fragment) => &inline_fragment.selections,
+            _ => return None,
+        };
+        // The span must be within the braces of the selection set
+        if !child_selections.span.contains(span) {
+            return None;
+        }
+        selection_set = child_selections;
+    }
+}
+
+/// Print the new fragment and the spread that replaces the selections. Local
+/// variables of the enclosing fragment that are used by the selections are
+/// defined on the new fragment too, and passed with `@arguments`.
+fn print_fragment(
+    context: &ExtractFragmentContext<'_>,
+    definition: &ExecutableDefinition,
+    extracted: &ExtractedSelections<'_>,
+    fragment_name: &str,
+    type_name: StringKey,
+) -> (String, String) {
+    let text = &context.text_source.text;
+    let mut used_variables = HashSet::new();
+    collect_selection_variables(extracted.selections, &mut used_variables);
+
+    let local_variables = match definition {
+        ExecutableDefinition::Fragment(fragment) => fragment
+            .directives
+            .iter()
+            .filter(|directive| directive.name.value == ARGUMENT_DEFINITION.0)
+            .flat_map(|directive| &directive.arguments)
+            .flat_map(|arguments| &arguments.items)
+            .filter(|argument| used_variables.contains(&argument.name.value))
+            .collect::<Vec<_>>(),
+        ExecutableDefinition::Operation(_) => vec![],
+    };
+
+    let mut fragment_text = format!("fragment {fragment_name} on {type_name}");
+    let mut spread_text = format!("...{fragment_name}");
+    if !local_variables.is_empty() {
+        fragment_text.push_str(&format!(
+            " @{}({})",
+            ARGUMENT_DEFINITION.0,
+            local_variables
+                .iter()
+                .map(|argument| &text[argument.span.start as usize..argument.span.end as usize])
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+        spread_text.push_str(&format!(
+            " @arguments({})",
+            local_variables
+                .iter()
+                .map(|argument| format!("{0}: ${0}", argument.name.value))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+
+    let selections_text = dedent(
+        &text[extracted.span.start as usize..extracted.span.end as usize],
+        get_column(text, extracted.span.start),
+    );
+    fragment_text.push_str(&format!(" {{\n{}}}\n", indent(&selections_text, 2)));
+    (fragment_text, spread_text)
+}
+
+/// The uri of a new component next to the current document, with the same
+/// extension.
+fn get_component_uri(uri: &Uri, module_name: &str, type_name: StringKey) -> Option<Uri> {
+    let path = uri.path().as_str();
+    let directory = &path[..path.rfind('/')?];
+    let extension = Path::new(path).extension()?.to_str()?;
+    let scheme = uri.scheme()?.as_str();
+    format!("{scheme}://{directory}/{module_name}{type_name}.{extension}")
+        .parse()
+        .ok()
+}
+
+fn get_column(text: &str, offset: u32) -> usize {
+    let line_start = text[..offset as usize]
+        .rfind('\n')
+        .map_or(0, |index| index + 1);
+    offset as usize - line_start
+}
+
+/// Remove the indentation of the lines after the first one, which starts at
+/// the given column.
+fn dedent(text: &str, column: usize) -> String {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| {
+            if index == 0 {
+                line
+            } else {
+                let indentation = line.len() - line.trim_start().len();
+                &line[indentation.min(column)..]
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Indent each non-empty line, ending each line with a newline.
+fn indent(text: &str, width: usize) -> String {
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                "\n".to_string()
+            } else {
+                format!("{}{line}\n", " ".repeat(width))
+            }
+        })
+        .collect()
+}
+
+fn create_extract_code_action(title: String, edit: WorkspaceEdit) -> CodeActionOrCommand {
+    CodeActionOrCommand::CodeAction(CodeAction {
+        title,
+        kind: Some(CodeActionKind::REFACTOR_EXTRACT),
+        edit: Some(edit),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,306 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use common::SourceLocationKey;
+use common::Span;
+use common::TextSource;
+use graphql_syntax::parse_executable_with_error_recovery;
+use intern::string_key::Intern;
+use lsp_types::CodeAction;
+use lsp_types::CodeActionOrCommand;
+use lsp_types::DocumentChangeOperation;
+use lsp_types::DocumentChanges;
+use lsp_types::OneOf;
+use lsp_types::Position;
+use lsp_types::ResourceOp;
+use lsp_types::TextEdit;
+use lsp_types::Uri;
+use relay_test_schema::get_test_schema;
+
+use super::ExtractFragmentContext;
+use super::get_extract_fragment_code_actions;
+use crate::utils::get_end_position;
+
+/// Returns the code actions for the selection between the two `|` of the
+/// source, with the source without the markers.
+fn get_code_actions(source: &str, used_fragment_names: &[&str]) -> (Vec<CodeAction>, String) {
+    let start = source.find('|').unwrap() as u32;
+    let end = source.rfind('|').unwrap() as u32 - 1;
+    let source = source.replace('|', "");
+    let document =
+        parse_executable_with_error_recovery(&source, SourceLocationKey::standalone("/test/file"))
+            .item;
+    let text_source = TextSource::from_whole_document(source.clone());
+    let schema = get_test_schema();
+    let uri = Uri::from_str("file:///test/UserCard.js").unwrap();
+    let used_fragment_names = used_fragment_names
+        .iter()
+        .map(|name| name.intern())
+        .collect::<HashSet<_>>();
+
+    let context = ExtractFragmentContext {
+        uri: &uri,
+        text_source: &text_source,
+        schema: &schema,
+        used_fragment_names: &used_fragment_names,
+        document_end: get_end_position(&source),
+    };
+    let code_actions =
+        get_extract_fragment_code_actions(&context, &document, Span::new(start, end))
+            .into_iter()
+            .map(|code_action| {
+                let CodeActionOrCommand::CodeAction(code_action) = code_action else {
+                    panic!("Expected a code action");
+                };
+                code_action
+            })
+            .collect();
+    (code_actions, source)
+}
+
+fn apply_edits(text: &str, mut edits: Vec<TextEdit>) -> String {
+    edits.sort_by_key(|edit| std::cmp::Reverse(edit.range.start));
+    let mut applied = text.to_string();
+    for edit in edits {
+        let start = position_to_index(&applied, edit.range.start);
+        let end = position_to_index(&applied, edit.range.end);
+        applied.replace_range(start..end, &edit.new_text);
+    }
+    applied
+}
+
+fn position_to_index(text: &str, position: Position) -> usize {
+    let line_start: usize = text
+        .split_inclusive('\n')
+        .take(position.line as usize)
+        .map(|line| line.len())
+        .sum();
+    line_start + position.character as usize
+}
+
+/// Applies the edits of the code action that extracts into the same document.
+fn apply_extract_in_document(code_action: &CodeAction, source: &str) -> String {
+    let edits = code_action
+        .edit
+        .as_ref()
+        .unwrap()
+        .changes
+        .as_ref()
+        .unwrap()
+        .get(&Uri::from_str("file:///test/UserCard.js").unwrap())
+        .unwrap()
+        .clone();
+    apply_edits(source, edits)
+}
+
+#[test]
+fn extract_fields_of_fragment() {
+    let (code_actions, source) = get_code_actions(
+        r#"fragment UserCard_user on User {
+  id
+  |name
+  username|
+}"#,
+        &["UserCard_user"],
+    );
+    assert_eq!(
+        code_actions[0].title,
+        "Extract selection into fragment `UserCard_user2`"
+    );
+    assert_eq!(
+        apply_extract_in_document(&code_actions[0], &source),
+        r#"fragment UserCard_user on User {
+  id
+  ...UserCard_user2
+}
+const UserCard_user2Fragment = graphql`
+  fragment UserCard_user2 on User {
+    name
+    username
+  }
+`;
+"#
+    );
+}
+
+#[test]
+fn extract_nested_selections_on_field_type() {
+    let (code_actions, source) = get_code_actions(
+        r#"query UserCardQuery {
+  me {
+    profilePicture(size: 32) {
+      |uri
+      width|
+    }
+  }
+}"#,
+        &["UserCard_image"],
+    );
+    assert_eq!(
+        apply_extract_in_document(&code_actions[0], &source),
+        r#"query UserCardQuery {
+  me {
+    profilePicture(size: 32) {
+      ...UserCard_image2
+    }
+  }
+}
+const UserCard_image2Fragment = graphql`
+  fragment UserCard_image2 on Image {
+    uri
+    width
+  }
+`;
+"#
+    );
+}
+
+#[test]
+fn extract_selections_on_inline_fragment_type() {
+    let (code_actions, source) = get_code_actions(
+        r#"query UserCardQuery {
+  node(id: "4") @include(if: true) {
+    ... on User {
+      |name
+      lastName|
+    }
+  }
+}"#,
+        &[],
+    );
+    assert_eq!(
+        apply_extract_in_document(&code_actions[0], &source),
+        r#"query UserCardQuery {
+  node(id: "4") @include(if: true) {
+    ... on User {
+      ...UserCard_user
+    }
+  }
+}
+const UserCard_userFragment = graphql`
+  fragment UserCard_user on User {
+    name
+    lastName
+  }
+`;
+"#
+    );
+}
+
+#[test]
+fn extract_with_local_arguments() {
+    let (code_actions, source) = get_code_actions(
+        r#"fragment UserCard_user on User @argumentDefinitions(size: {type: "[Int]"}, other: {type: "Int"}) {
+  |profilePicture(size: $size) {
+    uri
+  }|
+}"#,
+        &["UserCard_user"],
+    );
+    assert_eq!(
+        apply_extract_in_document(&code_actions[0], &source),
+        r#"fragment UserCard_user on User @argumentDefinitions(size: {type: "[Int]"}, other: {type: "Int"}) {
+  ...UserCard_user2 @arguments(size: $size)
+}
+const UserCard_user2Fragment = graphql`
+  fragment UserCard_user2 on User @argumentDefinitions(size: {type: "[Int]"}) {
+    profilePicture(size: $size) {
+      uri
+    }
+  }
+`;
+"#
+    );
+}
+
+#[test]
+fn extract_into_new_component() {
+    let (code_actions, source) = get_code_actions(
+        r#"fragment UserCard_user on User {
+  |name|
+}"#,
+        &[],
+    );
+    assert_eq!(
+        code_actions[1].title,
+        "Extract selection into fragment `UserCardUser_user` in a new component"
+    );
+    let Some(DocumentChanges::Operations(operations)) =
+        &code_actions[1].edit.as_ref().unwrap().document_changes
+    else {
+        panic!("Expected document change operations");
+    };
+    let [
+        DocumentChangeOperation::Edit(spread_edit),
+        DocumentChangeOperation::Op(ResourceOp::Create(create_file)),
+        DocumentChangeOperation::Edit(component_edit),
+    ] = operations.as_slice()
+    else {
+        panic!("Unexpected operations {operations:?}");
+    };
+    let get_text_edits = |edits: &[OneOf<TextEdit, _>]| {
+        edits
+            .iter()
+            .map(|edit| match edit {
+                OneOf::Left(edit) => edit.clone(),
+                OneOf::Right(_) => panic!("Expected a text edit"),
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        apply_edits(&source, get_text_edits(&spread_edit.edits)),
+        r#"fragment UserCard_user on User {
+  ...UserCardUser_user
+}"#
+    );
+    assert_eq!(create_file.uri.as_str(), "file:///test/UserCardUser.js");
+    assert_eq!(component_edit.text_document.uri, create_file.uri);
+    assert_eq!(
+        apply_edits("", get_text_edits(&component_edit.edits)),
+        r#"import {graphql, useFragment} from 'react-relay';
+
+export default function UserCardUser({user}) {
+  const data = useFragment(
+    graphql`
+      fragment UserCardUser_user on User {
+        name
+      }
+    `,
+    user,
+  );
+  return null;
+}
+"#
+    );
+}
+
+#[test]
+fn no_extraction_of_partial_selections() {
+    let (code_actions, _) = get_code_actions(
+        r#"fragment UserCard_user on User {
+  id
+  na|me
+  username|
+}"#,
+        &[],
+    );
+    assert!(code_actions.is_empty());
+
+    let (code_actions, _) = get_code_actions(
+        r#"fragment UserCard_user on User {
+  profilePicture(|size: 32) {
+    uri|
+  }
+}"#,
+        &[],
+    );
+    assert!(code_actions.is_empty());
+}
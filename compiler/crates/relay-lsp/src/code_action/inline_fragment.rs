@@ -0,0 +1,412 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! The "inline fragment spread" refactoring, which replaces a fragment spread
+//! with the selections of the fragment.
+
+use std::collections::HashMap;
+
+use common::ArgumentName;
+use common::NamedItem;
+use common::Span;
+use common::TextSource;
+use graphql_ir::ConditionValue;
+use graphql_ir::ConstantValue;
+use graphql_ir::DIRECTIVE_ARGUMENTS;
+use graphql_ir::FragmentDefinition;
+use graphql_ir::FragmentDefinitionName;
+use graphql_ir::FragmentSignature;
+use graphql_ir::FragmentSignatures;
+use graphql_ir::FragmentSpread;
+use graphql_ir::InlineFragment;
+use graphql_ir::Program;
+use graphql_ir::Selection;
+use graphql_ir::TransformedValue;
+use graphql_ir::Transformer;
+use graphql_ir::Value;
+use graphql_ir::Variable;
+use graphql_ir::VariableName;
+use graphql_ir::Visitor;
+use graphql_syntax::ExecutableDefinition;
+use graphql_syntax::ExecutableDocument;
+use graphql_text_printer::print_selection;
+use graphql_text_printer::print_selections;
+use lsp_types::CodeAction;
+use lsp_types::CodeActionKind;
+use lsp_types::CodeActionOrCommand;
+use lsp_types::TextEdit;
+use lsp_types::Uri;
+use lsp_types::WorkspaceEdit;
+use resolution_path::FragmentSpreadPath;
+use resolution_path::IdentParent;
+use resolution_path::IdentPath;
+use resolution_path::ResolutionPath;
+use resolution_path::ResolvePosition;
+use schema::Type;
+
+use crate::call_hierarchy::get_incoming_calls;
+use crate::server::build_ir_for_lsp_with_signatures;
+
+/// Everything the refactoring needs to know about the document it edits.
+pub(crate) struct InlineFragmentContext<'a> {
+    pub(crate) uri: &'a Uri,
+    pub(crate) text_source: &'a TextSource,
+    pub(crate) program: &'a Program,
+}
+
+/// Replace the fragment spread at the position with the selections of the
+/// fragment, with the `@arguments` of the spread applied to its local
+/// variables. Inlining a fragment that is spread elsewhere too duplicates its
+/// selections, so it is offered as a separate, explicit action.
+///
+/// When the spread is the only reference to a fragment defined in the same
+/// document, a second action also removes the definition. A fragment defined
+/// in another document is left alone, as the module that defines it may
+/// still read it.
+pub(crate) fn get_inline_fragment_code_actions(
+    context: &InlineFragmentContext<'_>,
+    document: &ExecutableDocument,
+    position_span: Span,
+) -> Vec<CodeActionOrCommand> {
+    let ResolutionPath::Ident(IdentPath {
+        inner: _,
+        parent:
+            IdentParent::FragmentSpreadName(FragmentSpreadPath {
+                inner: spread,
+                parent: selection_path,
+            }),
+    }) = document.resolve((), position_span)
+    else {
+        return vec![];
+    };
+    let program = context.program;
+    let Some(fragment) = program.fragment(FragmentDefinitionName(spread.name.value)) else {
+        return vec![];
+    };
+    let Some(parent_type) = selection_path.parent.find_parent_type(&program.schema) else {
+        return vec![];
+    };
+    let Some(definition) = document
+        .definitions
+        .iter()
+        .find(|definition| definition.location().contains(spread.span))
+    else {
+        return vec![];
+    };
+    let Some(ir_spread) = find_ir_spread(program, definition, spread.name.span, fragment) else {
+        return vec![];
+    };
+
+    let selections = apply_spread_arguments(fragment, &ir_spread);
+    let directives = spread
+        .directives
+        .iter()
+        .filter(|directive| directive.name.value != *DIRECTIVE_ARGUMENTS)
+        .map(|directive| {
+            context.text_source.text[directive.span.start as usize..directive.span.end as usize]
+                .to_string()
+        })
+        .collect::<Vec<_>>();
+    let inlined_text = if parent_type == fragment.type_condition && directives.is_empty() {
+        let printed = print_selections(&program.schema, &selections);
+        // Remove the braces and the indentation of the printed selection set
+        printed
+            .trim_start_matches(" {")
+            .trim_end_matches('}')
+            .trim_matches('\n')
+            .lines()
+            .map(|line| line.strip_prefix("  ").unwrap_or(line))
+            .collect::<Vec<_>>()
+            .join("\n")
+    } else {
+        let printed = print_selection(
+            &program.schema,
+            &Selection::InlineFragment(
+                InlineFragment {
+                    type_condition: (parent_type != fragment.type_condition)
+                        .then_some(fragment.type_condition),
+                    directives: vec![],
+                    selections,
+                    spread_location: ir_spread.fragment.location,
+                }
+                .into(),
+            ),
+        );
+        insert_directives(printed, fragment.type_condition, parent_type, &directives)
+    };
+    let indentation = get_line_indentation(&context.text_source.text, spread.span.start);
+    let new_text = inlined_text
+        .lines()
+        .collect::<Vec<_>>()
+        .join(&format!("\n{indentation}"));
+
+    let spread_count: usize = get_incoming_calls(program, fragment.name.item)
+        .iter()
+        .map(|call| call.spread_locations.len())
+        .sum();
+    let title = if spread_count > 1 {
+        format!(
+            "Inline a copy of fragment `{}` (spread {spread_count} times)",
+            fragment.name.item
+        )
+    } else {
+        format!("Inline fragment `{}`", fragment.name.item)
+    };
+
+    let inline_edit = TextEdit {
+        range: context.text_source.to_span_range(spread.span),
+        new_text,
+    };
+    let mut code_actions = vec![create_code_action(
+        context.uri,
+        title,
+        vec![inline_edit.clone()],
+    )];
+
+    let local_definition = document.definitions.iter().find(|definition| {
+        matches!(
+            definition,
+            ExecutableDefinition::Fragment(local_fragment)
+                if local_fragment.name.value == fragment.name.item.0
+        )
+    });
+    if let (1, Some(local_definition)) = (spread_count, local_definition) {
+        code_actions.push(create_code_action(
+            context.uri,
+            format!(
+                "Inline fragment `{}` and remove its definition",
+                fragment.name.item
+            ),
+            vec![
+                inline_edit,
+                TextEdit {
+                    range: context
+                        .text_source
+                        .to_span_range(get_definition_removal_span(
+                            &context.text_source.text,
+                            local_definition.location().span(),
+                        )),
+                    new_text: String::new(),
+                },
+            ],
+        ));
+    }
+    code_actions
+}
+
+fn create_code_action(uri: &Uri, title: String, edits: Vec<TextEdit>) -> CodeActionOrCommand {
+    let mut changes = HashMap::new();
+    changes.insert(uri.clone(), edits);
+    CodeActionOrCommand::CodeAction(CodeAction {
+        title,
+        kind: Some(CodeActionKind::REFACTOR_INLINE),
+        edit: Some(WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })
+}
+
+/// The span of the lines of a definition, up to the next definition, so that
+/// no blank lines are left behind. The last definition of the document is
+/// removed along with the blank lines before it instead.
+fn get_definition_removal_span(text: &str, definition_span: Span) -> Span {
+    let start = definition_span.start as usize;
+    let end = definition_span.end as usize;
+    match text[end..].find(|c: char| !c.is_whitespace()) {
+        Some(next_offset) => {
+            let next_start = end + next_offset;
+            let next_line_start = text[..next_start].rfind('\n').map_or(0, |index| index + 1);
+            let line_start = text[..start].rfind('\n').map_or(0, |index| index + 1);
+            // Definitions that share a line with another one are removed
+            // without the rest of the line.
+            let removal_start = if text[line_start..start].trim().is_empty() {
+                line_start
+            } else {
+                start
+            };
+            let removal_end = if next_line_start > end {
+                next_line_start
+            } else {
+                next_start
+            };
+            Span::new(removal_start as u32, removal_end as u32)
+        }
+        None => {
+            let previous_end = text[..start].trim_end().len();
+            Span::new(previous_end as u32, end as u32)
+        }
+    }
+}
+
+/// The printed inline fragment has no directives, as those of the spread are
+/// copied from the source text, after the type condition.
+fn insert_directives(
+    printed: String,
+    type_condition: Type,
+    parent_type: Type,
+    directives: &[String],
+) -> String {
+    if directives.is_empty() {
+        return printed;
+    }
+    let head_end = if type_condition == parent_type {
+        "...".len()
+    } else {
+        printed.find(" {").unwrap_or(0)
+    };
+    format!(
+        "{} {}{}",
+        &printed[..head_end],
+        directives.join(" "),
+        &printed[head_end..]
+    )
+}
+
+/// The arguments of the spread are only known once the definition containing
+/// it is built, with the signature of the fragment.
+fn find_ir_spread(
+    program: &Program,
+    definition: &ExecutableDefinition,
+    name_span: Span,
+    fragment: &FragmentDefinition,
+) -> Option<FragmentSpread> {
+    let mut signatures = FragmentSignatures::default();
+    signatures.insert(
+        fragment.name.item,
+        FragmentSignature {
+            name: fragment.name,
+            variable_definitions: fragment.variable_definitions.clone(),
+            type_condition: fragment.type_condition,
+            directives: fragment.directives.clone(),
+        },
+    );
+    let ir = build_ir_for_lsp_with_signatures(
+        &program.schema,
+        std::slice::from_ref(definition),
+        &signatures,
+    )
+    .ok()?;
+    let mut finder = FragmentSpreadFinder {
+        name_span,
+        spread: None,
+    };
+    for definition in &ir {
+        match definition {
+            graphql_ir::ExecutableDefinition::Operation(operation) => {
+                finder.visit_operation(operation)
+            }
+            graphql_ir::ExecutableDefinition::Fragment(fragment) => finder.visit_fragment(fragment),
+        }
+    }
+    finder.spread
+}
+
+struct FragmentSpreadFinder {
+    name_span: Span,
+    spread: Option<FragmentSpread>,
+}
+
+impl Visitor for FragmentSpreadFinder {
+    const NAME: &'static str = "FragmentSpreadFinder";
+    const VISIT_ARGUMENTS: bool = false;
+    const VISIT_DIRECTIVES: bool = false;
+
+    fn visit_fragment_spread(&mut self, spread: &FragmentSpread) {
+        if spread.fragment.location.span() == self.name_span {
+            self.spread = Some(spread.clone());
+        }
+    }
+}
+
+/// Bind the local variables of the fragment to the values passed with
+/// `@arguments`, or to their default values, the way `apply_fragment_arguments`
+/// does when it inlines fragments.
+fn apply_spread_arguments(
+    fragment: &FragmentDefinition,
+    spread: &FragmentSpread,
+) -> Vec<Selection> {
+    let mut bindings = HashMap::new();
+    for variable_definition in &fragment.variable_definitions {
+        let name = variable_definition.name.item;
+        let default_value = variable_definition
+            .default_value
+            .as_ref()
+            .map(|default_value| Value::Constant(default_value.item.clone()));
+        let value = match spread.arguments.named(ArgumentName(name.0)) {
+            Some(argument) if argument.value.item != Value::Constant(ConstantValue::Null()) => {
+                argument.value.item.clone()
+            }
+            _ => default_value.unwrap_or(Value::Constant(ConstantValue::Null())),
+        };
+        bindings.insert(name, value);
+    }
+    let mut transform = ApplySpreadArgumentsTransform { bindings };
+    transform
+        .transform_selections(&fragment.selections)
+        .replace_or_else(|| fragment.selections.clone())
+}
+
+struct ApplySpreadArgumentsTransform {
+    bindings: HashMap<VariableName, Value>,
+}
+
+impl Transformer<'_> for ApplySpreadArgumentsTransform {
+    const NAME: &'static str = "ApplySpreadArgumentsTransform";
+    const VISIT_ARGUMENTS: bool = true;
+    const VISIT_DIRECTIVES: bool = true;
+
+    fn transform_value(&mut self, value: &Value) -> TransformedValue<Value> {
+        match value {
+            Value::Variable(variable) => match self.bindings.get(&variable.name.item) {
+                Some(Value::Variable(replacement_variable)) => {
+                    TransformedValue::Replace(Value::Variable(Variable {
+                        name: replacement_variable.name,
+                        type_: variable.type_.clone(),
+                    }))
+                }
+                Some(value) => TransformedValue::Replace(value.clone()),
+                // A global variable
+                None => TransformedValue::Keep,
+            },
+            _ => self.default_transform_value(value),
+        }
+    }
+
+    fn transform_condition_value(
+        &mut self,
+        condition_value: &ConditionValue,
+    ) -> TransformedValue<ConditionValue> {
+        let ConditionValue::Variable(variable) = condition_value else {
+            return TransformedValue::Keep;
+        };
+        match self.bindings.get(&variable.name.item) {
+            Some(Value::Variable(replacement_variable)) => {
+                TransformedValue::Replace(ConditionValue::Variable(Variable {
+                    name: replacement_variable.name,
+                    type_: variable.type_.clone(),
+                }))
+            }
+            Some(Value::Constant(ConstantValue::Boolean(value))) => {
+                TransformedValue::Replace(ConditionValue::Constant(*value))
+            }
+            _ => TransformedValue::Keep,
+        }
+    }
+}
+
+fn get_line_indentation(text: &str, offset: u32) -> &str {
+    let line_start = text[..offset as usize]
+        .rfind('\n')
+        .map_or(0, |index| index + 1);
+    let line = &text[line_start..];
+    &line[..line.len() - line.trim_start().len()]
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,294 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::str::FromStr;
+
+use common::SourceLocationKey;
+use common::Span;
+use common::TextSource;
+use graphql_ir::Program;
+use graphql_ir::build;
+use graphql_syntax::parse_executable;
+use graphql_syntax::parse_executable_with_error_recovery;
+use lsp_types::CodeActionOrCommand;
+use lsp_types::Position;
+use lsp_types::Uri;
+use relay_test_schema::get_test_schema;
+
+use super::InlineFragmentContext;
+use super::get_inline_fragment_code_actions;
+
+const FRAGMENTS: &str = r#"
+    fragment Avatar_user on User @argumentDefinitions(
+        size: {type: "[Int]", defaultValue: 32}
+        includeName: {type: "Boolean!", defaultValue: false}
+    ) {
+        name @include(if: $includeName)
+        profilePicture(size: $size) {
+            uri
+        }
+    }
+
+    fragment Name_actor on Actor {
+        name
+    }
+
+    fragment Other_user on User {
+        ...Name_actor
+    }
+"#;
+
+/// Returns the title of the code action at the `|` in the source, and the
+/// source after applying it.
+fn get_inlined(source: &str) -> Option<(String, String)> {
+    let mut inlined = get_all_inlined(source);
+    assert!(inlined.len() <= 1);
+    inlined.pop()
+}
+
+/// Returns the titles of all the code actions at the `|` in the source, and
+/// the source after applying each of them.
+fn get_all_inlined(source: &str) -> Vec<(String, String)> {
+    let pos = source.find('|').unwrap() as u32;
+    let source = source.replace('|', "");
+    let schema = get_test_schema();
+    // The program contains the document too, unless it spreads unknown
+    // fragments
+    let definitions = parse_executable(
+        &format!("{FRAGMENTS}{source}"),
+        SourceLocationKey::Generated,
+    )
+    .unwrap()
+    .definitions;
+    let ir = build(&schema, &definitions).unwrap_or_else(|_| {
+        let definitions = parse_executable(FRAGMENTS, SourceLocationKey::Generated)
+            .unwrap()
+            .definitions;
+        build(&schema, &definitions).unwrap()
+    });
+    let program = Program::from_definitions(schema.clone(), ir);
+    let document =
+        parse_executable_with_error_recovery(&source, SourceLocationKey::standalone("/test/file"))
+            .item;
+    let text_source = TextSource::from_whole_document(source.clone());
+    let uri = Uri::from_str("file:///test/file.js").unwrap();
+
+    let context = InlineFragmentContext {
+        uri: &uri,
+        text_source: &text_source,
+        program: &program,
+    };
+    get_inline_fragment_code_actions(&context, &document, Span::new(pos, pos))
+        .into_iter()
+        .map(|code_action| {
+            let CodeActionOrCommand::CodeAction(code_action) = code_action else {
+                panic!("Expected a code action");
+            };
+            let mut edits = code_action
+                .edit
+                .unwrap()
+                .changes
+                .unwrap()
+                .remove(&uri)
+                .unwrap();
+            // Apply the edits from the end of the source, so that the ranges
+            // of the remaining edits stay valid
+            edits.sort_by_key(|edit| std::cmp::Reverse(edit.range.start));
+            let mut applied = source.clone();
+            for edit in edits {
+                let start = position_to_index(&source, edit.range.start);
+                let end = position_to_index(&source, edit.range.end);
+                applied.replace_range(start..end, &edit.new_text);
+            }
+            (code_action.title, applied)
+        })
+        .collect()
+}
+
+fn position_to_index(text: &str, position: Position) -> usize {
+    let line_start: usize = text
+        .split_inclusive('\n')
+        .take(position.line as usize)
+        .map(|line| line.len())
+        .sum();
+    line_start + position.character as usize
+}
+
+#[test]
+fn inline_spread_with_arguments() {
+    let (title, inlined) = get_inlined(
+        r#"query TestQuery($cond: Boolean!) {
+  me {
+    id
+    ...Ava|tar_user @arguments(size: [64], includeName: $cond)
+  }
+}"#,
+    )
+    .unwrap();
+    assert_eq!(title, "Inline fragment `Avatar_user`");
+    assert_eq!(
+        inlined,
+        r#"query TestQuery($cond: Boolean!) {
+  me {
+    id
+    name @include(if: $cond)
+    profilePicture(size: [64]) {
+      uri
+    }
+  }
+}"#
+    );
+}
+
+#[test]
+fn inline_spread_with_default_arguments() {
+    let (_, inlined) = get_inlined(
+        r#"query TestQuery {
+  me {
+    ...Avatar_u|ser
+  }
+}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        inlined,
+        r#"query TestQuery {
+  me {
+    name @include(if: false)
+    profilePicture(size: 32) {
+      uri
+    }
+  }
+}"#
+    );
+}
+
+#[test]
+fn inline_spread_on_other_type_with_directives() {
+    let (title, inlined) = get_inlined(
+        r#"query TestQuery($cond: Boolean!) {
+  node(id: "1") {
+    ...Name_act|or @include(if: $cond)
+  }
+}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        title,
+        "Inline a copy of fragment `Name_actor` (spread 2 times)"
+    );
+    assert_eq!(
+        inlined,
+        r#"query TestQuery($cond: Boolean!) {
+  node(id: "1") {
+    ... on Actor @include(if: $cond) {
+      name
+    }
+  }
+}"#
+    );
+}
+
+#[test]
+fn no_inlining_of_unknown_fragment() {
+    assert!(
+        get_inlined(
+            r#"query TestQuery {
+  me {
+    ...Unkn|own_user
+  }
+}"#
+        )
+        .is_none()
+    );
+}
+
+#[test]
+fn inline_sole_spread_and_remove_definition() {
+    let inlined = get_all_inlined(
+        r#"query TestQuery {
+  me {
+    ...Loc|al_user
+  }
+}
+
+fragment Local_user on User {
+  name
+}
+
+fragment Unrelated_user on User {
+  id
+}
+"#,
+    );
+    assert_eq!(
+        inlined,
+        vec![
+            (
+                "Inline fragment `Local_user`".to_string(),
+                r#"query TestQuery {
+  me {
+    name
+  }
+}
+
+fragment Local_user on User {
+  name
+}
+
+fragment Unrelated_user on User {
+  id
+}
+"#
+                .to_string()
+            ),
+            (
+                "Inline fragment `Local_user` and remove its definition".to_string(),
+                r#"query TestQuery {
+  me {
+    name
+  }
+}
+
+fragment Unrelated_user on User {
+  id
+}
+"#
+                .to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn inline_sole_spread_and_remove_last_definition() {
+    let inlined = get_all_inlined(
+        r#"query TestQuery {
+  me {
+    ...Loc|al_user
+  }
+}
+
+fragment Local_user on User {
+  name
+}
+"#,
+    );
+    assert_eq!(
+        inlined[1],
+        (
+            "Inline fragment `Local_user` and remove its definition".to_string(),
+            r#"query TestQuery {
+  me {
+    name
+  }
+}
+"#
+            .to_string()
+        )
+    );
+}
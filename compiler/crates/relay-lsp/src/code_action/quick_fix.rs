@@ -0,0 +1,585 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Quick fixes for common compiler diagnostics, computed from the GraphQL
+//! document at the position of the code action request. A quick fix is only
+//! offered when the request carries the diagnostic it resolves.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use common::ArgumentName;
+use common::DirectiveName;
+use common::Span;
+use common::TextSource;
+use graphql_ir::ARGUMENT_DEFINITION;
+use graphql_ir::FragmentDefinitionName;
+use graphql_ir::Program;
+use graphql_syntax::Argument;
+use graphql_syntax::Directive;
+use graphql_syntax::ExecutableDefinition;
+use graphql_syntax::ExecutableDocument;
+use graphql_syntax::FragmentDefinition;
+use graphql_syntax::FragmentSpread;
+use graphql_syntax::Identifier;
+use graphql_syntax::List;
+use graphql_syntax::Selection;
+use graphql_syntax::Value;
+use intern::Lookup;
+use intern::string_key::Intern;
+use intern::string_key::StringKey;
+use lsp_types::CodeAction;
+use lsp_types::CodeActionOrCommand;
+use lsp_types::Diagnostic;
+use lsp_types::Range;
+use lsp_types::TextEdit;
+use lsp_types::Uri;
+use lsp_types::WorkspaceEdit;
+use relay_transforms::CATCH_DIRECTIVE_NAME;
+use relay_transforms::FRAGMENT_ALIAS_DIRECTIVE_NAME;
+use relay_transforms::FRAGMENT_DANGEROUSLY_UNALIAS_DIRECTIVE_NAME;
+use relay_transforms::REQUIRED_DIRECTIVE_NAME;
+use resolution_path::ArgumentParent;
+use resolution_path::ArgumentPath;
+use resolution_path::FragmentSpreadPath;
+use resolution_path::IdentParent;
+use resolution_path::IdentPath;
+use resolution_path::LinkedFieldPath;
+use resolution_path::ResolutionPath;
+use resolution_path::ResolvePosition;
+use resolution_path::ScalarFieldPath;
+use resolution_path::ValueParent;
+use resolution_path::ValuePath;
+use resolution_path::VariableIdentifierParent;
+use resolution_path::VariableIdentifierPath;
+use schema::SDLSchema;
+use schema::Schema;
+use schema::Type;
+
+/// Everything a quick fix needs to know about the document it edits.
+pub(crate) struct QuickFixContext<'a> {
+    pub(crate) uri: &'a Uri,
+    pub(crate) text_source: &'a TextSource,
+    pub(crate) schema: &'a SDLSchema,
+    /// The program of the last build, used to infer the types of variables
+    pub(crate) program: Option<&'a Program>,
+    /// The diagnostics of the request, which the quick fixes resolve
+    pub(crate) diagnostics: &'a [Diagnostic],
+}
+
+pub(crate) fn get_quick_fixes(
+    context: &QuickFixContext<'_>,
+    document: &ExecutableDocument,
+    position_span: Span,
+) -> Vec<CodeActionOrCommand> {
+    let Some(definition) = document
+        .definitions
+        .iter()
+        .find(|definition| definition.location().contains(position_span))
+    else {
+        return vec![];
+    };
+
+    let mut quick_fixes = vec![];
+    if let ExecutableDefinition::Fragment(fragment) = definition {
+        quick_fixes.extend(remove_unused_argument_definition(
+            context,
+            fragment,
+            position_span,
+        ));
+    }
+
+    match document.resolve((), position_span) {
+        ResolutionPath::VariableIdentifier(VariableIdentifierPath {
+            inner: variable,
+            parent: VariableIdentifierParent::Value(value_path),
+        }) => {
+            if let ExecutableDefinition::Fragment(fragment) = definition {
+                quick_fixes.extend(add_argument_definition(
+                    context,
+                    fragment,
+                    variable.name,
+                    &value_path,
+                ));
+            }
+        }
+        ResolutionPath::Ident(IdentPath {
+            inner: _,
+            parent: IdentParent::FragmentSpreadName(FragmentSpreadPath { inner: spread, .. }),
+        }) => {
+            quick_fixes.extend(add_alias_to_spread(context, spread));
+        }
+        ResolutionPath::Ident(IdentPath {
+            inner: name,
+            parent:
+                IdentParent::ScalarFieldName(ScalarFieldPath {
+                    inner: field,
+                    parent: selection_path,
+                }),
+        }) => {
+            if let Some(parent_type) = selection_path.parent.find_parent_type(context.schema) {
+                quick_fixes.extend(get_field_quick_fixes(
+                    context,
+                    parent_type,
+                    field.alias.as_ref().map_or(name.span, |alias| alias.span),
+                    name,
+                    &field.arguments,
+                    &field.directives,
+                ));
+            }
+        }
+        ResolutionPath::Ident(IdentPath {
+            inner: name,
+            parent:
+                IdentParent::LinkedFieldName(LinkedFieldPath {
+                    inner: field,
+                    parent: selection_path,
+                }),
+        }) => {
+            if let Some(parent_type) = selection_path.parent.find_parent_type(context.schema) {
+                quick_fixes.extend(get_field_quick_fixes(
+                    context,
+                    parent_type,
+                    field.alias.as_ref().map_or(name.span, |alias| alias.span),
+                    name,
+                    &field.arguments,
+                    &field.directives,
+                ));
+            }
+        }
+        _ => {}
+    }
+
+    quick_fixes
+}
+
+/// Define a variable of a fragment, that an operation reports as undefined,
+/// as a local variable in `@argumentDefinitions`.
+fn add_argument_definition(
+    context: &QuickFixContext<'_>,
+    fragment: &FragmentDefinition,
+    variable_name: StringKey,
+    value_path: &ValuePath<'_>,
+) -> Option<CodeActionOrCommand> {
+    let argument_definitions = find_directive(&fragment.directives, *ARGUMENT_DEFINITION);
+    if argument_definitions
+        .and_then(|directive| directive.arguments.as_ref())
+        .is_some_and(|arguments| {
+            arguments
+                .items
+                .iter()
+                .any(|argument| argument.name.value == variable_name)
+        })
+    {
+        return None;
+    }
+    // The operation reports the first usage of each undefined variable, which
+    // may be another usage within this fragment than the one at the cursor.
+    let quoted_variable = format!("'${variable_name}'");
+    let diagnostic = find_diagnostic(context, fragment.location.span(), |message| {
+        message.contains("references undefined variable") && message.contains(&quoted_variable)
+    })?;
+
+    // The variable is undefined in the operation of the diagnostic, so its
+    // inferred type is only used when the argument it is passed to is unknown.
+    let type_string = get_expected_variable_type(context.schema, value_path).or_else(|| {
+        let fragment = context
+            .program?
+            .fragment(FragmentDefinitionName(fragment.name.value))?;
+        fragment
+            .used_global_variables
+            .iter()
+            .find(|variable| variable.name.item.0 == variable_name)
+            .map(|variable| context.schema.get_type_string(&variable.type_))
+    })?;
+    let entry = format!("{variable_name}: {{type: \"{type_string}\"}}");
+
+    let (span, new_text) = match argument_definitions {
+        None => (
+            end_of(fragment.type_condition.span),
+            format!(" @{}({entry})", ARGUMENT_DEFINITION.0),
+        ),
+        Some(directive) => match &directive.arguments {
+            None => (end_of(directive.name.span), format!("({entry})")),
+            Some(arguments) => match arguments.items.last() {
+                None => (end_of(arguments.start.span), entry),
+                Some(last) => (end_of(last.span), format!(", {entry}")),
+            },
+        },
+    };
+
+    Some(create_quick_fix(
+        context,
+        format!("Add `${variable_name}` to @{}", ARGUMENT_DEFINITION.0),
+        vec![(span, new_text)],
+        diagnostic,
+    ))
+}
+
+/// The type of the argument that a variable is passed to, if known.
+fn get_expected_variable_type(schema: &SDLSchema, value_path: &ValuePath<'_>) -> Option<String> {
+    let ValueParent::ArgumentValue(ArgumentPath {
+        inner: argument,
+        parent,
+    }) = &value_path.parent
+    else {
+        return None;
+    };
+    let argument_name = ArgumentName(argument.name.value);
+    let argument_type = match parent {
+        ArgumentParent::ScalarField(ScalarFieldPath {
+            inner: field,
+            parent: selection_path,
+        }) => {
+            let parent_type = selection_path.parent.find_parent_type(schema)?;
+            let field = schema.field(schema.named_field(parent_type, field.name.value)?);
+            &field.arguments.named(argument_name)?.type_
+        }
+        ArgumentParent::LinkedField(LinkedFieldPath {
+            inner: field,
+            parent: selection_path,
+        }) => {
+            let parent_type = selection_path.parent.find_parent_type(schema)?;
+            let field = schema.field(schema.named_field(parent_type, field.name.value)?);
+            &field.arguments.named(argument_name)?.type_
+        }
+        ArgumentParent::Directive(directive_path) => {
+            let directive = schema.get_directive(DirectiveName(directive_path.inner.name.value))?;
+            &directive.arguments.named(argument_name)?.type_
+        }
+        _ => return None,
+    };
+    Some(schema.get_type_string(argument_type))
+}
+
+/// Remove a local variable of `@argumentDefinitions` that is never used in
+/// the selections of the fragment.
+fn remove_unused_argument_definition(
+    context: &QuickFixContext<'_>,
+    fragment: &FragmentDefinition,
+    position_span: Span,
+) -> Option<CodeActionOrCommand> {
+    let directive = find_directive(&fragment.directives, *ARGUMENT_DEFINITION)?;
+    let arguments = directive.arguments.as_ref()?;
+    let index = arguments
+        .items
+        .iter()
+        .position(|argument| argument.span.contains(position_span))?;
+    let variable_name = arguments.items[index].name.value;
+    let unused_message = format!("Variable `${variable_name}` is never used in fragment");
+    let diagnostic = find_diagnostic(context, arguments.items[index].span, |message| {
+        message.starts_with(&unused_message)
+    })?;
+
+    // The diagnostic may be stale while the document is being edited.
+    let mut used_variables = HashSet::new();
+    collect_selection_variables(&fragment.selections.items, &mut used_variables);
+    if used_variables.contains(&variable_name) {
+        return None;
+    }
+
+    let span = if arguments.items.len() == 1 {
+        Span::new(
+            directive_removal_start(context, directive),
+            directive.span.end,
+        )
+    } else if let Some(next) = arguments.items.get(index + 1) {
+        Span::new(arguments.items[index].span.start, next.span.start)
+    } else {
+        Span::new(
+            arguments.items[index - 1].span.end,
+            arguments.items[index].span.end,
+        )
+    };
+
+    Some(create_quick_fix(
+        context,
+        format!("Remove unused variable `${variable_name}`"),
+        vec![(span, "".to_string())],
+        diagnostic,
+    ))
+}
+
+/// Include the whitespace before a directive when removing it.
+fn directive_removal_start(context: &QuickFixContext<'_>, directive: &Directive) -> u32 {
+    let preceding_text = &context.text_source.text[..directive.span.start as usize];
+    (preceding_text.trim_end_matches([' ', '\t']).len()) as u32
+}
+
+pub(super) fn collect_selection_variables(
+    selections: &[Selection],
+    variables: &mut HashSet<StringKey>,
+) {
+    for selection in selections {
+        for directive in selection.directives() {
+            collect_argument_variables(&directive.arguments, variables);
+        }
+        match selection {
+            Selection::ScalarField(field) => {
+                collect_argument_variables(&field.arguments, variables);
+            }
+            Selection::LinkedField(field) => {
+                collect_argument_variables(&field.arguments, variables);
+                collect_selection_variables(&field.selections.items, variables);
+            }
+            Selection::FragmentSpread(spread) => {
+                collect_argument_variables(&spread.arguments, variables);
+            }
+            Selection::InlineFragment(inline_fragment) => {
+                collect_selection_variables(&inline_fragment.selections.items, variables);
+            }
+        }
+    }
+}
+
+fn collect_argument_variables(
+    arguments: &Option<List<Argument>>,
+    variables: &mut HashSet<StringKey>,
+) {
+    for argument in arguments.iter().flat_map(|arguments| &arguments.items) {
+        collect_value_variables(&argument.value, variables);
+    }
+}
+
+fn collect_value_variables(value: &Value, variables: &mut HashSet<StringKey>) {
+    match value {
+        Value::Constant(_) => {}
+        Value::Variable(variable) => {
+            variables.insert(variable.name);
+        }
+        Value::List(list) => {
+            for item in &list.items {
+                collect_value_variables(item, variables);
+            }
+        }
+        Value::Object(object) => {
+            for argument in &object.items {
+                collect_value_variables(&argument.value, variables);
+            }
+        }
+    }
+}
+
+/// Add `@alias` to a fragment spread that must be aliased, because it is
+/// conditionally fetched or its type is not a subtype of the parent type.
+fn add_alias_to_spread(
+    context: &QuickFixContext<'_>,
+    spread: &FragmentSpread,
+) -> Option<CodeActionOrCommand> {
+    if find_directive(&spread.directives, *FRAGMENT_ALIAS_DIRECTIVE_NAME).is_some()
+        || find_directive(
+            &spread.directives,
+            *FRAGMENT_DANGEROUSLY_UNALIAS_DIRECTIVE_NAME,
+        )
+        .is_some()
+    {
+        return None;
+    }
+    let diagnostic = find_diagnostic(context, spread.name.span, |message| {
+        message.starts_with("Expected `@alias` directive.")
+    })?;
+    let end = spread
+        .arguments
+        .as_ref()
+        .map_or(spread.name.span, |arguments| arguments.span);
+
+    Some(create_quick_fix(
+        context,
+        format!("Add @alias to `...{}`", spread.name.value),
+        vec![(
+            end_of(end),
+            format!(" @{}", FRAGMENT_ALIAS_DIRECTIVE_NAME.0),
+        )],
+        diagnostic,
+    ))
+}
+
+/// Wrap a field in `@required` or `@catch`, and replace a deprecated field
+/// with the replacement suggested by its deprecation reason. `alias_or_name`
+/// is the span the diagnostics of the field are reported at.
+fn get_field_quick_fixes(
+    context: &QuickFixContext<'_>,
+    parent_type: Type,
+    alias_or_name: Span,
+    name: &Identifier,
+    arguments: &Option<List<Argument>>,
+    directives: &[Directive],
+) -> Vec<CodeActionOrCommand> {
+    let schema = context.schema;
+    let Some(field_id) = schema.named_field(parent_type, name.value) else {
+        return vec![];
+    };
+    let field = schema.field(field_id);
+    let directive_start = end_of(
+        arguments
+            .as_ref()
+            .map_or(name.span, |arguments| arguments.span),
+    );
+
+    let mut quick_fixes = vec![];
+    // Other references to the same field are `@required`, and are reported
+    // with this one as "but not @required here".
+    if !field.type_.is_non_null()
+        && find_directive(directives, *REQUIRED_DIRECTIVE_NAME).is_none()
+        && let Some(diagnostic) = find_diagnostic(context, alias_or_name, |message| {
+            message.starts_with("All references to a field must have matching @required")
+        })
+    {
+        for action in ["THROW", "LOG"] {
+            quick_fixes.push(create_quick_fix(
+                context,
+                format!(
+                    "Add @{}(action: {action}) to `{}`",
+                    REQUIRED_DIRECTIVE_NAME.0, name.value
+                ),
+                vec![(
+                    directive_start,
+                    format!(" @{}(action: {action})", REQUIRED_DIRECTIVE_NAME.0),
+                )],
+                diagnostic,
+            ));
+        }
+    }
+    if find_directive(directives, *CATCH_DIRECTIVE_NAME).is_none()
+        && let Some(diagnostic) = find_diagnostic(context, alias_or_name, |message| {
+            message.contains("to be annotated with `@catch`")
+        })
+    {
+        quick_fixes.push(create_quick_fix(
+            context,
+            format!("Add @{} to `{}`", CATCH_DIRECTIVE_NAME.0, name.value),
+            vec![(directive_start, format!(" @{}", CATCH_DIRECTIVE_NAME.0))],
+            diagnostic,
+        ));
+    }
+    let deprecated_message = format!(
+        "The field `{}.{}` is deprecated.",
+        schema.get_type_name(parent_type),
+        name.value
+    );
+    if let Some(replacement) = field
+        .deprecated()
+        .and_then(|deprecation| deprecation.reason)
+        .and_then(|reason| find_replacement_field(schema, parent_type, name.value, reason))
+        && let Some(diagnostic) = find_diagnostic(context, name.span, |message| {
+            message.starts_with(&deprecated_message)
+        })
+    {
+        quick_fixes.push(create_quick_fix(
+            context,
+            format!(
+                "Replace deprecated field `{}` with `{replacement}`",
+                name.value
+            ),
+            vec![(name.span, replacement.to_string())],
+            diagnostic,
+        ));
+    }
+    quick_fixes
+}
+
+/// Find the field suggested by a deprecation reason, such as "Use `name`
+/// instead": the first identifier in backticks, or following "use", that is
+/// a field of the same type which is not deprecated itself.
+fn find_replacement_field(
+    schema: &SDLSchema,
+    parent_type: Type,
+    field_name: StringKey,
+    reason: StringKey,
+) -> Option<StringKey> {
+    let reason = reason.lookup();
+    let quoted = reason.split('`').skip(1).step_by(2);
+    let mut words = reason.split_whitespace();
+    let mut after_use = vec![];
+    while let Some(word) = words.next() {
+        if word.eq_ignore_ascii_case("use")
+            && let Some(next) = words.next()
+        {
+            after_use.push(next);
+        }
+    }
+
+    quoted
+        .chain(after_use)
+        .map(|candidate| candidate.trim_matches(|c: char| !c.is_ascii_alphanumeric() && c != '_'))
+        .find_map(|candidate| {
+            let candidate = schema.named_field(parent_type, candidate.intern())?;
+            let candidate = schema.field(candidate);
+            (candidate.name.item != field_name && candidate.deprecated().is_none())
+                .then_some(candidate.name.item)
+        })
+}
+
+fn find_directive(directives: &[Directive], name: DirectiveName) -> Option<&Directive> {
+    directives
+        .iter()
+        .find(|directive| directive.name.value == name.0)
+}
+
+fn end_of(span: Span) -> Span {
+    Span::new(span.end, span.end)
+}
+
+/// The diagnostic of the request, with a message accepted by
+/// `matches_message`, that is reported at `span` or annotates it.
+fn find_diagnostic<'a>(
+    context: &QuickFixContext<'a>,
+    span: Span,
+    matches_message: impl Fn(&str) -> bool,
+) -> Option<&'a Diagnostic> {
+    let range = context.text_source.to_span_range(span);
+    context.diagnostics.iter().find(|diagnostic| {
+        matches_message(&diagnostic.message)
+            && (ranges_overlap(diagnostic.range, range)
+                || diagnostic
+                    .related_information
+                    .iter()
+                    .flatten()
+                    .any(|related| {
+                        related.location.uri == *context.uri
+                            && ranges_overlap(related.location.range, range)
+                    }))
+    })
+}
+
+fn ranges_overlap(a: Range, b: Range) -> bool {
+    a.start <= b.end && b.start <= a.end
+}
+
+fn create_quick_fix(
+    context: &QuickFixContext<'_>,
+    title: String,
+    edits: Vec<(Span, String)>,
+    diagnostic: &Diagnostic,
+) -> CodeActionOrCommand {
+    let text_edits = edits
+        .into_iter()
+        .map(|(span, new_text)| TextEdit {
+            range: context.text_source.to_span_range(span),
+            new_text,
+        })
+        .collect();
+    let mut changes = HashMap::new();
+    changes.insert(context.uri.clone(), text_edits);
+
+    CodeActionOrCommand::CodeAction(CodeAction {
+        title,
+        kind: Some(lsp_types::CodeActionKind::QUICKFIX),
+        diagnostics: Some(vec![diagnostic.clone()]),
+        edit: Some(WorkspaceEdit {
+            changes: Some(changes),
+            document_changes: None,
+            ..Default::default()
+        }),
+        command: None,
+        is_preferred: Some(false),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod test;
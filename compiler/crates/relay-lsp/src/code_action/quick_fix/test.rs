@@ -0,0 +1,378 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::str::FromStr;
+
+use common::SourceLocationKey;
+use common::Span;
+use common::TextSource;
+use graphql_syntax::parse_executable_with_error_recovery;
+use lsp_types::CodeActionOrCommand;
+use lsp_types::Diagnostic;
+use lsp_types::DiagnosticRelatedInformation;
+use lsp_types::Location;
+use lsp_types::Position;
+use lsp_types::Range;
+use lsp_types::Uri;
+use relay_test_schema::get_test_schema_with_extensions;
+
+use super::QuickFixContext;
+use super::get_quick_fixes;
+
+const EXTENSIONS: &str = r#"
+    extend type User {
+        fullName: String @deprecated(reason: "Use `name` instead.")
+        oldName: String @deprecated(reason: "No longer supported.")
+    }
+"#;
+
+const URI: &str = "file:///test/file.js";
+
+/// A diagnostic of the request, reported at the first occurrence of `at` in
+/// the source, and annotating the first occurrence of `related_at`.
+struct TestDiagnostic<'a> {
+    message: &'a str,
+    at: &'a str,
+    related_at: Option<&'a str>,
+}
+
+fn reported_at<'a>(message: &'a str, at: &'a str) -> TestDiagnostic<'a> {
+    TestDiagnostic {
+        message,
+        at,
+        related_at: None,
+    }
+}
+
+fn range_of(text_source: &TextSource, text: &str) -> Range {
+    let start = text_source
+        .text
+        .find(text)
+        .unwrap_or_else(|| panic!("No `{text}` in the source"));
+    text_source.to_span_range(Span::new(start as u32, (start + text.len()) as u32))
+}
+
+/// Returns the titles of the quick fixes at the `|` in the source, for the
+/// given diagnostics of the request, and the source after applying each of
+/// them.
+fn get_applied_quick_fixes(
+    source: &str,
+    diagnostics: &[TestDiagnostic<'_>],
+) -> Vec<(String, String)> {
+    let pos = source.find('|').unwrap() as u32;
+    let source = source.replace('|', "");
+    let document =
+        parse_executable_with_error_recovery(&source, SourceLocationKey::standalone("/test/file"))
+            .item;
+    let text_source = TextSource::from_whole_document(source.clone());
+    let schema = get_test_schema_with_extensions(EXTENSIONS);
+    let uri = Uri::from_str(URI).unwrap();
+    let diagnostics = diagnostics
+        .iter()
+        .map(|diagnostic| Diagnostic {
+            range: range_of(&text_source, diagnostic.at),
+            message: diagnostic.message.to_string(),
+            related_information: diagnostic.related_at.map(|related_at| {
+                vec![DiagnosticRelatedInformation {
+                    location: Location {
+                        uri: uri.clone(),
+                        range: range_of(&text_source, related_at),
+                    },
+                    message: "related location".to_string(),
+                }]
+            }),
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+
+    let context = QuickFixContext {
+        uri: &uri,
+        text_source: &text_source,
+        schema: &schema,
+        program: None,
+        diagnostics: &diagnostics,
+    };
+    get_quick_fixes(&context, &document, Span::new(pos, pos))
+        .into_iter()
+        .map(|quick_fix| {
+            let CodeActionOrCommand::CodeAction(code_action) = quick_fix else {
+                panic!("Expected a code action");
+            };
+            assert_eq!(
+                code_action.diagnostics.map(|diagnostics| diagnostics.len()),
+                Some(1),
+                "Expected `{}` to resolve a single diagnostic",
+                code_action.title
+            );
+            let mut edits = code_action
+                .edit
+                .unwrap()
+                .changes
+                .unwrap()
+                .remove(&uri)
+                .unwrap();
+            edits.sort_by_key(|edit| std::cmp::Reverse(edit.range.start));
+            let mut applied = source.clone();
+            for edit in edits {
+                let start = position_to_index(&applied, edit.range.start);
+                let end = position_to_index(&applied, edit.range.end);
+                applied.replace_range(start..end, &edit.new_text);
+            }
+            (code_action.title, applied)
+        })
+        .collect()
+}
+
+fn position_to_index(text: &str, position: Position) -> usize {
+    let line_start: usize = text
+        .split_inclusive('\n')
+        .take(position.line as usize)
+        .map(|line| line.len())
+        .sum();
+    line_start + position.character as usize
+}
+
+fn find_quick_fix(quick_fixes: &[(String, String)], title: &str) -> String {
+    quick_fixes
+        .iter()
+        .find(|(quick_fix_title, _)| quick_fix_title == title)
+        .unwrap_or_else(|| panic!("No quick fix `{title}` in {quick_fixes:?}"))
+        .1
+        .clone()
+}
+
+const UNDEFINED_SIZE: &str = "Operation 'TestQuery' references undefined variable: '$size'.";
+const UNUSED_VARIABLE: &str = "Variable `$unused` is never used in fragment `Test`. `@argumentDefinitions` defines local variables, global variables are implicitly available.";
+const EXPECTED_ALIAS: &str = "Expected `@alias` directive. Fragment spreads with (or within an inline fragment with) `@include` are conditionally fetched. Add `@alias` to this spread to expose the fragment reference as a nullable property.";
+const REQUIRED_MISMATCH: &str = "All references to a field must have matching @required declarations. The field 'name` is @required here";
+const MISSING_CATCH: &str =
+    "Expected client-defined field within `@throwOnFieldError` to be annotated with `@catch`.";
+
+fn assert_no_quick_fixes(quick_fixes: &[(String, String)]) {
+    assert!(
+        quick_fixes.is_empty(),
+        "Expected no quick fixes, got {quick_fixes:?}"
+    );
+}
+
+#[test]
+fn add_argument_definition() {
+    let source = r#"fragment Test on User {
+  profilePicture(size: $si|ze) { uri }
+}"#;
+    let quick_fixes = get_applied_quick_fixes(source, &[reported_at(UNDEFINED_SIZE, "$size")]);
+    assert_eq!(
+        find_quick_fix(&quick_fixes, "Add `$size` to @argumentDefinitions"),
+        r#"fragment Test on User @argumentDefinitions(size: {type: "[Int]"}) {
+  profilePicture(size: $size) { uri }
+}"#
+    );
+
+    assert_no_quick_fixes(&get_applied_quick_fixes(source, &[]));
+}
+
+#[test]
+fn add_argument_definition_to_existing_directive() {
+    let quick_fixes = get_applied_quick_fixes(
+        r#"fragment Test on User @argumentDefinitions(preset: {type: "PhotoSize"}) {
+  profilePicture(size: $si|ze, preset: $preset) { uri }
+}"#,
+        &[reported_at(UNDEFINED_SIZE, "$size")],
+    );
+    assert_eq!(
+        find_quick_fix(&quick_fixes, "Add `$size` to @argumentDefinitions"),
+        r#"fragment Test on User @argumentDefinitions(preset: {type: "PhotoSize"}, size: {type: "[Int]"}) {
+  profilePicture(size: $size, preset: $preset) { uri }
+}"#
+    );
+}
+
+#[test]
+fn no_argument_definition_for_variable_undefined_in_other_fragment() {
+    let quick_fixes = get_applied_quick_fixes(
+        r#"fragment Test on User {
+  profilePicture(size: $si|ze) { uri }
+}
+
+fragment Other on User {
+  avatar: profilePicture(size: $size) { uri }
+}"#,
+        &[reported_at(
+            UNDEFINED_SIZE,
+            "avatar: profilePicture(size: $size)",
+        )],
+    );
+    assert_no_quick_fixes(&quick_fixes);
+}
+
+#[test]
+fn remove_unused_argument_definition() {
+    let quick_fixes = get_applied_quick_fixes(
+        r#"fragment Test on User @argumentDefinitions(
+  size: {type: "[Int]"}
+  unu|sed: {type: "Int"}
+) {
+  profilePicture(size: $size) { uri }
+}"#,
+        &[reported_at(UNUSED_VARIABLE, "unused")],
+    );
+    assert_eq!(
+        find_quick_fix(&quick_fixes, "Remove unused variable `$unused`"),
+        r#"fragment Test on User @argumentDefinitions(
+  size: {type: "[Int]"}
+) {
+  profilePicture(size: $size) { uri }
+}"#
+    );
+
+    let source = r#"fragment Test on User @argumentDefinitions(unu|sed: {type: "Int"}) {
+  name
+}"#;
+    let quick_fixes = get_applied_quick_fixes(source, &[reported_at(UNUSED_VARIABLE, "unused")]);
+    assert_eq!(
+        find_quick_fix(&quick_fixes, "Remove unused variable `$unused`"),
+        r#"fragment Test on User {
+  name
+}"#
+    );
+
+    assert_no_quick_fixes(&get_applied_quick_fixes(source, &[]));
+}
+
+#[test]
+fn no_removal_of_used_argument_definition() {
+    let quick_fixes = get_applied_quick_fixes(
+        r#"fragment Test on User @argumentDefinitions(si|ze: {type: "[Int]"}) {
+  profilePicture(size: $size) { uri }
+}"#,
+        &[],
+    );
+    assert_no_quick_fixes(&quick_fixes);
+}
+
+#[test]
+fn add_alias_to_conditional_spread() {
+    let source = r#"query Test($cond: Boolean!) {
+  me {
+    ... @include(if: $cond) {
+      ...Fr|ag_user
+    }
+  }
+}"#;
+    let quick_fixes = get_applied_quick_fixes(source, &[reported_at(EXPECTED_ALIAS, "Frag_user")]);
+    assert_eq!(
+        find_quick_fix(&quick_fixes, "Add @alias to `...Frag_user`"),
+        r#"query Test($cond: Boolean!) {
+  me {
+    ... @include(if: $cond) {
+      ...Frag_user @alias
+    }
+  }
+}"#
+    );
+
+    assert_no_quick_fixes(&get_applied_quick_fixes(source, &[]));
+}
+
+#[test]
+fn add_required_to_field() {
+    let source = r#"fragment Test on User {
+  na|me
+}
+
+fragment Other on User {
+  name @required(action: LOG)
+}"#;
+    let quick_fixes = get_applied_quick_fixes(
+        source,
+        &[TestDiagnostic {
+            message: REQUIRED_MISMATCH,
+            at: "name @required",
+            related_at: Some("name"),
+        }],
+    );
+    assert_eq!(
+        quick_fixes
+            .iter()
+            .map(|(title, _)| title.as_str())
+            .collect::<Vec<_>>(),
+        vec![
+            "Add @required(action: THROW) to `name`",
+            "Add @required(action: LOG) to `name`",
+        ]
+    );
+    assert_eq!(
+        find_quick_fix(&quick_fixes, "Add @required(action: LOG) to `name`"),
+        r#"fragment Test on User {
+  name @required(action: LOG)
+}
+
+fragment Other on User {
+  name @required(action: LOG)
+}"#
+    );
+
+    assert_no_quick_fixes(&get_applied_quick_fixes(source, &[]));
+}
+
+#[test]
+fn add_catch_to_field() {
+    let source = r#"fragment Test on User {
+  profile|Picture(size: 32) @required(action: LOG) { uri }
+}"#;
+    let quick_fixes =
+        get_applied_quick_fixes(source, &[reported_at(MISSING_CATCH, "profilePicture")]);
+    assert_eq!(
+        find_quick_fix(&quick_fixes, "Add @catch to `profilePicture`"),
+        r#"fragment Test on User {
+  profilePicture(size: 32) @catch @required(action: LOG) { uri }
+}"#
+    );
+
+    assert_no_quick_fixes(&get_applied_quick_fixes(source, &[]));
+    // A diagnostic of another field is not resolved by this fix.
+    assert_no_quick_fixes(&get_applied_quick_fixes(
+        source,
+        &[reported_at(MISSING_CATCH, "uri")],
+    ));
+}
+
+#[test]
+fn replace_deprecated_field() {
+    let source = r#"fragment Test on User {
+  full|Name
+}"#;
+    let quick_fixes = get_applied_quick_fixes(
+        source,
+        &[reported_at(
+            "The field `User.fullName` is deprecated. Deprecation reason: \"Use `name` instead.\"",
+            "fullName",
+        )],
+    );
+    assert_eq!(
+        find_quick_fix(
+            &quick_fixes,
+            "Replace deprecated field `fullName` with `name`"
+        ),
+        r#"fragment Test on User {
+  name
+}"#
+    );
+
+    assert_no_quick_fixes(&get_applied_quick_fixes(source, &[]));
+
+    let quick_fixes = get_applied_quick_fixes(
+        r#"fragment Test on User {
+  old|Name
+}"#,
+        &[reported_at(
+            "The field `User.oldName` is deprecated. Deprecation reason: \"No longer supported.\"",
+            "oldName",
+        )],
+    );
+    assert_no_quick_fixes(&quick_fixes);
+}
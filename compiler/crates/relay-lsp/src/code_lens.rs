@@ -0,0 +1,244 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Utilities for providing the code lens feature
+
+use std::path::Path;
+
+use common::Location as IRLocation;
+use common::SourceLocationKey;
+use common::TextSource;
+use extract_graphql::JavaScriptSourceFeature;
+use graphql_ir::FragmentDefinitionName;
+use graphql_ir::OperationDefinitionName;
+use graphql_ir::Program;
+use graphql_syntax::ExecutableDefinition;
+use graphql_syntax::ExecutableDocument;
+use graphql_syntax::parse_executable_with_error_recovery_and_parser_features;
+use intern::string_key::Intern;
+use intern::string_key::StringKey;
+use lsp_types::CodeLens;
+use lsp_types::Command;
+use lsp_types::Location;
+use lsp_types::Position;
+use lsp_types::TextDocumentIdentifier;
+use lsp_types::TextDocumentPositionParams;
+use lsp_types::Uri;
+use lsp_types::request::CodeLensRequest;
+use lsp_types::request::CodeLensResolve;
+use lsp_types::request::Request;
+use relay_compiler::ProjectConfig;
+use relay_compiler::ProjectName;
+use relay_compiler::get_parser_features;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::lsp_runtime_error::LSPRuntimeError;
+use crate::lsp_runtime_error::LSPRuntimeResult;
+use crate::references::ReferenceFinder;
+use crate::server::GlobalState;
+use crate::utils::is_file_uri_in_dir;
+use crate::utils::path_to_file_uri;
+
+/// Client command that shows a list of locations, with the arguments of
+/// VS Code's `editor.action.showReferences`: a uri, a position and locations.
+pub const SHOW_REFERENCES_COMMAND: &str = "relay.showReferences";
+/// Client command that copies the full text of the operation at a position,
+/// using the `relay/printOperation` request.
+pub const COPY_OPERATION_COMMAND: &str = "relay.copyOperation";
+/// Client command that opens the generated artifact with the uri argument.
+pub const OPEN_ARTIFACT_COMMAND: &str = "relay.openArtifact";
+
+/// Data attached to the fragment spread count lens, the count is computed
+/// when the lens is resolved.
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FragmentSpreadsLensData {
+    uri: Uri,
+    fragment_name: String,
+}
+
+/// Resolve a [`CodeLensRequest`] to the lenses of each fragment and operation
+/// of a document: the number of spreads of a fragment, the path of the
+/// generated artifact, and an action to copy the full text of an operation.
+pub fn on_code_lens(
+    state: &impl GlobalState,
+    params: <CodeLensRequest as Request>::Params,
+) -> LSPRuntimeResult<<CodeLensRequest as Request>::Result> {
+    let uri = params.text_document.uri;
+    if !is_file_uri_in_dir(&state.root_dir(), &uri) {
+        return Err(LSPRuntimeError::ExpectedError);
+    }
+
+    let javascript_sources = state.get_synced_javascript_sources(&uri)?;
+    let project_name = state.extract_project_name_from_uri(&uri)?;
+    let config = state.get_config();
+    let project_config = config
+        .projects
+        .get(&ProjectName::from(project_name))
+        .ok_or_else(|| {
+            LSPRuntimeError::UnexpectedError(format!(
+                "Unable to get project config for project {project_name}."
+            ))
+        })?;
+    let parser_features = get_parser_features(project_config);
+    // Artifact paths are only known once the project has been built
+    let program = state.get_program(&project_name).ok();
+
+    let mut code_lenses = vec![];
+    for (index, feature) in javascript_sources.iter().enumerate() {
+        let JavaScriptSourceFeature::GraphQL(graphql_source) = feature else {
+            continue;
+        };
+        let text_source = graphql_source.text_source();
+        let document = parse_executable_with_error_recovery_and_parser_features(
+            &text_source.text,
+            SourceLocationKey::embedded(uri.as_str(), index),
+            parser_features,
+        )
+        .item;
+
+        code_lenses.extend(get_code_lenses(
+            &uri,
+            &document,
+            text_source,
+            program.as_ref(),
+            project_config,
+            &state.root_dir(),
+        ));
+    }
+
+    Ok(Some(code_lenses))
+}
+
+/// The lenses of the definitions of a GraphQL document of the file at `uri`.
+/// The fragment spread count lenses are resolved with
+/// [`get_fragment_spreads_command`], and the artifact lenses are only added
+/// for the definitions of the built `program`.
+pub fn get_code_lenses(
+    uri: &Uri,
+    document: &ExecutableDocument,
+    text_source: &TextSource,
+    program: Option<&Program>,
+    project_config: &ProjectConfig,
+    root_dir: &Path,
+) -> Vec<CodeLens> {
+    let mut code_lenses = vec![];
+    for definition in &document.definitions {
+        let Some(name) = definition.name_identifier() else {
+            continue;
+        };
+        let range = text_source.to_span_range(name.span);
+
+        let artifact_name = match definition {
+            ExecutableDefinition::Fragment(_) => {
+                code_lenses.push(CodeLens {
+                    range,
+                    command: None,
+                    data: serde_json::to_value(FragmentSpreadsLensData {
+                        uri: uri.clone(),
+                        fragment_name: name.value.to_string(),
+                    })
+                    .ok(),
+                });
+                program
+                    .and_then(|program| program.fragment(FragmentDefinitionName(name.value)))
+                    .map(|fragment| fragment.name.map(|name| name.0))
+            }
+            ExecutableDefinition::Operation(_) => {
+                code_lenses.push(CodeLens {
+                    range,
+                    command: Some(Command {
+                        title: "Copy full operation text".to_string(),
+                        command: COPY_OPERATION_COMMAND.to_string(),
+                        arguments: serde_json::to_value(TextDocumentPositionParams {
+                            text_document: TextDocumentIdentifier { uri: uri.clone() },
+                            position: range.start,
+                        })
+                        .ok()
+                        .map(|position| vec![position]),
+                    }),
+                    data: None,
+                });
+                program
+                    .and_then(|program| program.operation(OperationDefinitionName(name.value)))
+                    .map(|operation| operation.name.map(|name| name.0))
+            }
+        };
+
+        if let Some(artifact_name) = artifact_name {
+            let artifact_path = project_config.artifact_path_for_definition(artifact_name);
+            code_lenses.push(CodeLens {
+                range,
+                command: Some(Command {
+                    title: format!("Artifact: {}", artifact_path.display()),
+                    command: OPEN_ARTIFACT_COMMAND.to_string(),
+                    arguments: path_to_file_uri(&root_dir.join(&artifact_path))
+                        .map(|artifact_uri| vec![serde_json::json!(artifact_uri)]),
+                }),
+                data: None,
+            });
+        }
+    }
+    code_lenses
+}
+
+/// Resolve a [`CodeLensResolve`] request by counting the spreads of the
+/// fragment of the lens, in the same way as the references request.
+pub fn on_code_lens_resolve(
+    state: &impl GlobalState,
+    params: <CodeLensResolve as Request>::Params,
+) -> LSPRuntimeResult<<CodeLensResolve as Request>::Result> {
+    let mut code_lens = params;
+    let Some(data) = code_lens.data.take() else {
+        return Ok(code_lens);
+    };
+    let data: FragmentSpreadsLensData = serde_json::from_value(data).map_err(|err| {
+        LSPRuntimeError::UnexpectedError(format!("Invalid code lens data: {err}"))
+    })?;
+
+    let project_name = state.extract_project_name_from_uri(&data.uri)?;
+    let program = state.get_program(&project_name)?;
+    code_lens.command = Some(get_fragment_spreads_command(
+        &program,
+        &data.uri,
+        data.fragment_name.as_str().intern(),
+        code_lens.range.start,
+        |location| state.transform_relay_location_in_editor_to_lsp_location(location),
+    )?);
+
+    Ok(code_lens)
+}
+
+/// The command of a fragment spread count lens at `position`, that shows the
+/// spreads of the fragment.
+pub fn get_fragment_spreads_command(
+    program: &Program,
+    uri: &Uri,
+    fragment_name: StringKey,
+    position: Position,
+    transform_location: impl Fn(IRLocation) -> LSPRuntimeResult<Location>,
+) -> LSPRuntimeResult<Command> {
+    let locations = ReferenceFinder::get_references_to_fragment(program, fragment_name)
+        .into_iter()
+        .map(transform_location)
+        .collect::<LSPRuntimeResult<Vec<_>>>()?;
+
+    let title = match locations.len() {
+        1 => "1 spread".to_string(),
+        count => format!("{count} spreads"),
+    };
+    Ok(Command {
+        title,
+        command: SHOW_REFERENCES_COMMAND.to_string(),
+        arguments: Some(vec![
+            serde_json::json!(uri),
+            serde_json::json!(position),
+            serde_json::json!(locations),
+        ]),
+    })
+}
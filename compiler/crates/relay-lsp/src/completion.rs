@@ -36,6 +36,7 @@ use graphql_syntax::Selection;
 use graphql_syntax::TokenKind;
 use graphql_syntax::Value;
 use intern::Lookup;
+use intern::string_key::Intern;
 use intern::string_key::StringKey;
 use log::debug;
 use lsp_types::CompletionItem;
@@ -61,6 +62,7 @@ use schema::TypeWithFields;
 
 use crate::LSPRuntimeError;
 use crate::SchemaDocumentation;
+use crate::code_action::create_name_suggestion::create_default_fragment_name_for_type;
 use crate::lsp_runtime_error::LSPRuntimeResult;
 use crate::node_resolution_info::TypePath;
 use crate::node_resolution_info::TypePathItem;
@@ -1340,6 +1342,12 @@ pub fn on_completion(
                 schema,
                 state.get_schema_documentation(project_name.lookup()),
                 &state.get_program(&project_name)?,
+                params
+                    .text_document_position
+                    .text_document
+                    .uri
+                    .path()
+                    .as_str(),
             )
             .unwrap_or_else(Vec::new);
             Ok(Some(CompletionResponse::Array(items)))
@@ -1374,11 +1382,95 @@ fn resolve_completion_items(
     schema: &SDLSchema,
     schema_documentation: impl SchemaDocumentation,
     progam: &Program,
+    file_name: &str,
 ) -> Option<Vec<CompletionItem>> {
+    // The fragments of the document are not part of the program yet when
+    // they have just been written
+    let document_fragment_names = document
+        .definitions
+        .iter()
+        .filter_map(|definition| match definition {
+            ExecutableDefinition::Fragment(fragment) => Some(fragment.name.value),
+            ExecutableDefinition::Operation(_) => None,
+        })
+        .collect::<Vec<_>>();
     let completion_request = CompletionRequestBuilder::new(project_name)
-        .create_completion_request(document, position_span);
-    completion_request.and_then(|completion_request| {
-        completion_items_for_request(completion_request, schema, schema_documentation, progam)
+        .create_completion_request(document, position_span)?;
+    let new_fragment_item = match completion_request.kind {
+        CompletionKind::FragmentSpread => {
+            let is_name_used = |name: &str| {
+                let name = name.intern();
+                document_fragment_names.contains(&name)
+                    || progam.fragment(FragmentDefinitionName(name)).is_some()
+            };
+            TypePath(completion_request.type_path.0.clone())
+                .resolve_leaf_type(schema)
+                .and_then(|type_| {
+                    resolve_completion_item_for_new_fragment(type_, schema, file_name, is_name_used)
+                })
+        }
+        _ => None,
+    };
+    let mut items =
+        completion_items_for_request(completion_request, schema, schema_documentation, progam)?;
+    items.extend(new_fragment_item);
+    Some(items)
+}
+
+/// Suggests the name of a fragment that does not exist yet, following the
+/// naming convention of the module of the document, so that the spread can be
+/// written before the fragment.
+fn resolve_completion_item_for_new_fragment(
+    type_: Type,
+    schema: &SDLSchema,
+    file_name: &str,
+    is_name_used: impl Fn(&str) -> bool,
+) -> Option<CompletionItem> {
+    let type_name = schema.get_type_name(type_);
+    let fragment_name =
+        create_default_fragment_name_for_type(file_name, type_name.lookup(), is_name_used)?;
+    // The name is a placeholder of the snippet, to be edited right away
+    let insert_text = format!("${{1:{fragment_name}}}");
+    Some(CompletionItem {
+        label: fragment_name,
+        kind: Some(CompletionItemKind::SNIPPET),
+        detail: Some(format!("New fragment on {type_name}")),
+        sort_text: Some("~".to_string()),
+        insert_text: Some(insert_text),
+        insert_text_format: Some(InsertTextFormat::SNIPPET),
+        ..Default::default()
+    })
+}
+
+/// The argument list of a field, directive or fragment spread that contains
+/// a position, as resolved for argument completions.
+pub(crate) struct ArgumentListAtPosition {
+    pub kind: ArgumentKind,
+    pub type_path: TypePath,
+    /// The argument whose value contains the position, if any.
+    pub argument_name: Option<StringKey>,
+}
+
+pub(crate) fn resolve_argument_list_at_position(
+    document: ExecutableDocument,
+    position_span: Span,
+    project_name: StringKey,
+) -> Option<ArgumentListAtPosition> {
+    let request = CompletionRequestBuilder::new(project_name)
+        .create_completion_request(document, position_span)?;
+    let (kind, argument_name) = match request.kind {
+        CompletionKind::ArgumentName { kind, .. } => (kind, None),
+        CompletionKind::ArgumentValue {
+            kind,
+            argument_name,
+            ..
+        } => (kind, Some(argument_name)),
+        _ => return None,
+    };
+    Some(ArgumentListAtPosition {
+        kind,
+        type_path: request.type_path,
+        argument_name,
     })
 }
 
@@ -18,6 +18,7 @@ use graphql_syntax::parse_executable_with_error_recovery;
 use intern::string_key::Intern;
 use lsp_types::CompletionItem;
 use lsp_types::Documentation;
+use lsp_types::InsertTextFormat;
 use relay_test_schema::get_test_schema;
 
 use super::make_markdown_table_documentation;
@@ -48,6 +49,7 @@ fn parse_and_resolve_completion_items(
         &Arc::clone(&test_schema),
         Arc::clone(&test_schema),
         &program.unwrap_or_else(|| Program::new(Arc::clone(&test_schema))),
+        "/test/file.js",
     )
 }
 
@@ -548,7 +550,10 @@ fn fragment_spread_on_the_same_type() {
       "#,
         )),
     );
-    assert_labels(items.unwrap(), vec!["TestFragment", "TestFragment2"]);
+    assert_labels(
+        items.unwrap(),
+        vec!["TestFragment", "TestFragment2", "file_viewer"],
+    );
 }
 
 #[test]
@@ -571,7 +576,42 @@ fn fragment_spread_on_interface() {
     "#,
         )),
     );
-    assert_labels(items.unwrap(), vec!["TestFragment", "TestFragment2"]);
+    assert_labels(
+        items.unwrap(),
+        vec!["TestFragment", "TestFragment2", "file_actor"],
+    );
+}
+
+#[test]
+fn fragment_spread_of_new_fragment_with_used_name() {
+    let items = parse_and_resolve_completion_items(
+        r#"
+            fragment file_user on User {
+               ...f|
+            }
+        "#,
+        Some(build_test_program(
+            r#"
+        fragment file_user2 on User {
+           __typename
+        }
+    "#,
+        )),
+    );
+    let items = items.unwrap();
+    assert_labels(items.clone(), vec!["file_user2", "file_user3"]);
+    let new_fragment_item = items
+        .iter()
+        .find(|item| item.label == "file_user3")
+        .unwrap();
+    assert_eq!(
+        new_fragment_item.insert_text.as_deref(),
+        Some("${1:file_user3}")
+    );
+    assert_eq!(
+        new_fragment_item.insert_text_format,
+        Some(InsertTextFormat::SNIPPET)
+    );
 }
 
 #[test]
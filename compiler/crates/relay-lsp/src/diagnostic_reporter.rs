@@ -6,8 +6,13 @@
  */
 
 //! Utilities for reporting errors to an LSP client
+use std::hash::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
 
 use common::Diagnostic as CompilerDiagnostic;
 use common::DiagnosticRelatedInformation;
@@ -21,6 +26,8 @@ use dunce::canonicalize;
 use extract_graphql::JavaScriptSourceFeature;
 use lsp_server::Message;
 use lsp_server::Notification as ServerNotification;
+use lsp_server::Request as ServerRequest;
+use lsp_server::RequestId;
 use lsp_types::Diagnostic;
 use lsp_types::DiagnosticRelatedInformation as LspDiagnosticRelatedInformation;
 use lsp_types::DiagnosticSeverity;
@@ -32,6 +39,8 @@ use lsp_types::Range;
 use lsp_types::Uri;
 use lsp_types::notification::Notification;
 use lsp_types::notification::PublishDiagnostics;
+use lsp_types::request::Request;
+use lsp_types::request::WorkspaceDiagnosticRefresh;
 use relay_compiler::FsSourceReader;
 use relay_compiler::SourceReader;
 use relay_compiler::errors::BuildProjectError;
@@ -56,11 +65,63 @@ struct DiagnosticSet {
     regular_diagnostics: Vec<Diagnostic>,
 }
 
+impl DiagnosticSet {
+    /// The quick diagnostics, followed by the regular diagnostics that are
+    /// not duplicates of them.
+    fn merged(&self) -> Vec<Diagnostic> {
+        let mut next_diagnostics = self.quick_diagnostics.clone();
+        for diagnostic in &self.regular_diagnostics {
+            if !next_diagnostics
+                .iter()
+                .any(|prev_diag| prev_diag.eq(diagnostic))
+            {
+                next_diagnostics.push(diagnostic.clone());
+            }
+        }
+        next_diagnostics
+    }
+}
+
+/// The diagnostics of a document, for the pull model of the LSP
+/// (`textDocument/diagnostic` and `workspace/diagnostic` requests).
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentDiagnostics {
+    pub uri: Uri,
+    /// Identifies the diagnostics, so that clients can skip the diagnostics
+    /// they already have. It only changes when the diagnostics change.
+    pub result_id: String,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl DocumentDiagnostics {
+    pub fn new(uri: Uri, diagnostics: Vec<Diagnostic>) -> Self {
+        let mut hasher = DefaultHasher::new();
+        serde_json::to_string(&diagnostics)
+            .unwrap_or_default()
+            .hash(&mut hasher);
+        Self {
+            uri,
+            result_id: format!("{:x}", hasher.finish()),
+            diagnostics,
+        }
+    }
+}
+
 pub struct DiagnosticReporter {
     active_diagnostics: DashMap<Uri, DiagnosticSet>,
     sender: Option<Sender<Message>>,
     root_dir: PathBuf,
     source_reader: Box<dyn SourceReader + Send + Sync>,
+    /// Clients that pull diagnostics are not sent `publishDiagnostics`
+    /// notifications, but are asked to pull them again when they change.
+    pull_diagnostics: Option<PullDiagnostics>,
+}
+
+struct PullDiagnostics {
+    refresh_support: bool,
+    refresh_request_count: AtomicU64,
+    /// Fingerprint of the build diagnostics when they were last committed
+    build_diagnostics_fingerprint: AtomicU64,
 }
 
 impl DiagnosticReporter {
@@ -70,14 +131,29 @@ impl DiagnosticReporter {
             sender,
             root_dir,
             source_reader: Box::new(FsSourceReader),
+            pull_diagnostics: None,
         }
     }
 
+    /// Stop publishing diagnostics, for clients that pull them instead.
+    /// Clients with `refresh_support` are asked to pull the diagnostics again
+    /// whenever a build or the validation of an edited document changes them.
+    pub fn enable_pull_diagnostics(&mut self, refresh_support: bool) {
+        let build_diagnostics_fingerprint = self.get_build_diagnostics_fingerprint();
+        self.pull_diagnostics = Some(PullDiagnostics {
+            refresh_support,
+            refresh_request_count: AtomicU64::new(0),
+            build_diagnostics_fingerprint: AtomicU64::new(build_diagnostics_fingerprint),
+        });
+    }
+
     pub fn clear_regular_diagnostics(&self) {
         for mut r in self.active_diagnostics.iter_mut() {
             let (uri, diagnostics) = r.pair_mut();
             diagnostics.regular_diagnostics.clear();
-            self.publish_diagnostics_set(uri, diagnostics);
+            if self.pull_diagnostics.is_none() {
+                self.publish_diagnostics_set(uri, diagnostics);
+            }
         }
         self.active_diagnostics.retain(|_, diagnostics| {
             !diagnostics.regular_diagnostics.is_empty() || !diagnostics.quick_diagnostics.is_empty()
@@ -112,6 +188,17 @@ impl DiagnosticReporter {
     }
 
     pub fn commit_diagnostics(&self) {
+        if let Some(pull_diagnostics) = &self.pull_diagnostics {
+            let fingerprint = self.get_build_diagnostics_fingerprint();
+            if pull_diagnostics
+                .build_diagnostics_fingerprint
+                .swap(fingerprint, Ordering::Relaxed)
+                != fingerprint
+            {
+                self.request_diagnostics_refresh();
+            }
+            return;
+        }
         for r in self.active_diagnostics.iter() {
             let (uri, diagnostics) = r.pair();
             self.publish_diagnostics_set(uri, diagnostics)
@@ -132,7 +219,7 @@ impl DiagnosticReporter {
                 let data = e.get_mut();
                 if data.quick_diagnostics != diagnostics {
                     data.quick_diagnostics = diagnostics;
-                    self.publish_diagnostics_set(uri, data);
+                    self.notify_diagnostics_changed(uri, data);
                 }
             }
             Entry::Vacant(e) => {
@@ -141,7 +228,7 @@ impl DiagnosticReporter {
                         regular_diagnostics: vec![],
                         quick_diagnostics: diagnostics,
                     };
-                    self.publish_diagnostics_set(uri, &data);
+                    self.notify_diagnostics_changed(uri, &data);
                     e.insert(data);
                 }
             }
@@ -153,28 +240,73 @@ impl DiagnosticReporter {
             && !diagnostics.quick_diagnostics.is_empty()
         {
             diagnostics.quick_diagnostics.clear();
-            self.publish_diagnostics_set(uri, &diagnostics)
+            self.notify_diagnostics_changed(uri, &diagnostics)
         }
     }
 
-    fn publish_diagnostics_set(&self, uri: &Uri, diagnostics: &DiagnosticSet) {
-        let mut next_diagnostics = diagnostics.quick_diagnostics.clone();
-        for diagnostic in &diagnostics.regular_diagnostics {
-            if !next_diagnostics
-                .iter()
-                .any(|prev_diag| prev_diag.eq(diagnostic))
-            {
-                next_diagnostics.push(diagnostic.clone());
-            }
+    fn notify_diagnostics_changed(&self, uri: &Uri, diagnostics: &DiagnosticSet) {
+        // The quick diagnostics are computed after the document was edited,
+        // so a client may have pulled the diagnostics of the document before
+        // they changed.
+        if self.pull_diagnostics.is_some() {
+            self.request_diagnostics_refresh();
+        } else {
+            self.publish_diagnostics_set(uri, diagnostics);
         }
+    }
+
+    /// Identifies the diagnostics of the last build, of all the documents.
+    fn get_build_diagnostics_fingerprint(&self) -> u64 {
+        let mut build_diagnostics = self
+            .active_diagnostics
+            .iter()
+            .filter(|r| !r.value().regular_diagnostics.is_empty())
+            .map(|r| {
+                (
+                    r.key().to_string(),
+                    serde_json::to_string(&r.value().regular_diagnostics).unwrap_or_default(),
+                )
+            })
+            .collect::<Vec<_>>();
+        build_diagnostics.sort();
+        let mut hasher = DefaultHasher::new();
+        build_diagnostics.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn publish_diagnostics_set(&self, uri: &Uri, diagnostics: &DiagnosticSet) {
         let params = PublishDiagnosticsParams {
-            diagnostics: next_diagnostics,
+            diagnostics: diagnostics.merged(),
             uri: uri.clone(),
             version: None,
         };
         publish_diagnostic(params, &self.sender).ok();
     }
 
+    /// Ask the client to pull the diagnostics again, with a
+    /// `workspace/diagnostic/refresh` request, if it advertised support for
+    /// it.
+    fn request_diagnostics_refresh(&self) {
+        let Some(pull_diagnostics) = &self.pull_diagnostics else {
+            return;
+        };
+        if !pull_diagnostics.refresh_support {
+            return;
+        }
+        let Some(sender) = &self.sender else {
+            return;
+        };
+        let count = pull_diagnostics
+            .refresh_request_count
+            .fetch_add(1, Ordering::Relaxed);
+        let request = ServerRequest::new(
+            RequestId::from(format!("relay-diagnostic-refresh-{count}")),
+            WorkspaceDiagnosticRefresh::METHOD.to_string(),
+            (),
+        );
+        sender.send(Message::Request(request)).unwrap_or(());
+    }
+
     #[cfg(test)]
     fn set_source_reader(&mut self, source_reader: Box<dyn SourceReader + Send + Sync>) {
         self.source_reader = source_reader;
@@ -310,23 +442,32 @@ impl DiagnosticReporter {
         let mut result = Vec::new();
         for r in self.active_diagnostics.iter() {
             let (uri, diagnostics) = r.pair();
-            let mut next_diagnostics = diagnostics.quick_diagnostics.clone();
-            for diagnostic in &diagnostics.regular_diagnostics {
-                if !next_diagnostics
-                    .iter()
-                    .any(|prev_diag| prev_diag.eq(diagnostic))
-                {
-                    next_diagnostics.push(diagnostic.clone());
-                }
-            }
             result.push(PublishDiagnosticsParams {
-                diagnostics: next_diagnostics,
+                diagnostics: diagnostics.merged(),
                 uri: uri.clone(),
                 version: None,
             });
         }
         result
     }
+
+    /// The current diagnostics of a document, which are empty if it has none.
+    pub fn get_document_diagnostics(&self, uri: &Uri) -> DocumentDiagnostics {
+        let diagnostics = self
+            .active_diagnostics
+            .get(uri)
+            .map(|diagnostics| diagnostics.merged())
+            .unwrap_or_default();
+        DocumentDiagnostics::new(uri.clone(), diagnostics)
+    }
+
+    /// The current diagnostics of all documents that have any.
+    pub fn get_workspace_diagnostics(&self) -> Vec<DocumentDiagnostics> {
+        self.active_diagnostics
+            .iter()
+            .map(|r| DocumentDiagnostics::new(r.key().clone(), r.value().merged()))
+            .collect()
+    }
 }
 
 /// Checks if `inner` range is within the `outer` range.
@@ -363,6 +504,7 @@ mod tests {
     use common::SourceLocationKey;
     use common::Span;
     use intern::string_key::Intern;
+    use lsp_server::Message;
     use lsp_types::Position;
     use lsp_types::Range;
     use relay_compiler::SourceReader;
@@ -410,6 +552,83 @@ mod tests {
         assert_eq!(reporter.active_diagnostics.len(), 0);
     }
 
+    #[test]
+    fn refresh_pulled_diagnostics_on_build_diagnostics_change() {
+        let (sender, receiver) = crossbeam::channel::unbounded();
+        let mut reporter = DiagnosticReporter::new(PathBuf::from("/tmp"), Some(sender));
+        reporter.set_source_reader(Box::new(MockSourceReader("Content".to_string())));
+        reporter.enable_pull_diagnostics(true);
+        let location = Location::new(
+            SourceLocationKey::Standalone {
+                path: "foo.txt".intern(),
+            },
+            Span { start: 0, end: 1 },
+        );
+
+        // A build without diagnostics does not change them
+        reporter.clear_regular_diagnostics();
+        reporter.commit_diagnostics();
+        assert!(receiver.try_recv().is_err());
+
+        reporter.clear_regular_diagnostics();
+        reporter.report_diagnostic(&Diagnostic::error("test message", location));
+        reporter.commit_diagnostics();
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(Message::Request(request)) if request.method == "workspace/diagnostic/refresh"
+        ));
+
+        // The same diagnostics again
+        reporter.clear_regular_diagnostics();
+        reporter.report_diagnostic(&Diagnostic::error("test message", location));
+        reporter.commit_diagnostics();
+        assert!(receiver.try_recv().is_err());
+
+        // The quick diagnostics of an edited document change
+        let uri = reporter.url_from_location(location).unwrap();
+        let quick_diagnostics = vec![lsp_types::Diagnostic {
+            message: "quick message".to_string(),
+            ..Default::default()
+        }];
+        reporter.update_quick_diagnostics_for_uri(&uri, quick_diagnostics.clone());
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(Message::Request(request)) if request.method == "workspace/diagnostic/refresh"
+        ));
+
+        // The same quick diagnostics again
+        reporter.update_quick_diagnostics_for_uri(&uri, quick_diagnostics);
+        assert!(receiver.try_recv().is_err());
+
+        reporter.clear_quick_diagnostics_for_uri(&uri);
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(Message::Request(request)) if request.method == "workspace/diagnostic/refresh"
+        ));
+        reporter.clear_quick_diagnostics_for_uri(&uri);
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn do_not_refresh_pulled_diagnostics_without_refresh_support() {
+        let (sender, receiver) = crossbeam::channel::unbounded();
+        let mut reporter = DiagnosticReporter::new(PathBuf::from("/tmp"), Some(sender));
+        reporter.set_source_reader(Box::new(MockSourceReader("Content".to_string())));
+        reporter.enable_pull_diagnostics(false);
+
+        reporter.report_diagnostic(&Diagnostic::error(
+            "test message",
+            Location::new(
+                SourceLocationKey::Standalone {
+                    path: "foo.txt".intern(),
+                },
+                Span { start: 0, end: 1 },
+            ),
+        ));
+        reporter.commit_diagnostics();
+        assert!(receiver.try_recv().is_err());
+    }
+
     #[test]
     fn sub_range_inner_directly_below() {
         let cursor = Range::new(Position::new(106, 12), Position::new(106, 12));
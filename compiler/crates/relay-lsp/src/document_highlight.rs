@@ -0,0 +1,210 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Utilities for providing the document highlight feature, which highlights
+//! the definition and the uses of the variable at the cursor, within the
+//! operation or fragment containing it.
+
+use common::DirectiveName;
+use common::Span;
+use graphql_ir::ARGUMENT_DEFINITION;
+use graphql_syntax::Argument;
+use graphql_syntax::ExecutableDefinition;
+use graphql_syntax::ExecutableDocument;
+use graphql_syntax::List;
+use graphql_syntax::Selection;
+use graphql_syntax::Value;
+use graphql_syntax::VariableDefinition;
+use intern::string_key::StringKey;
+use lsp_types::DocumentHighlight;
+use lsp_types::DocumentHighlightKind;
+use lsp_types::request::DocumentHighlightRequest;
+use lsp_types::request::Request;
+
+use crate::Feature;
+use crate::GlobalState;
+use crate::LSPRuntimeError;
+use crate::LSPRuntimeResult;
+
+/// The definition or a use of a variable, in the GraphQL source.
+#[derive(Debug, PartialEq)]
+pub(crate) struct VariableOccurrence {
+    pub(crate) name: StringKey,
+    pub(crate) span: Span,
+    pub(crate) is_definition: bool,
+}
+
+/// Resolve a [`DocumentHighlightRequest`] to the occurrences of the variable
+/// at the position: its operation variable definition, or its
+/// `@argumentDefinitions` entry, and every use of it.
+pub fn on_document_highlight(
+    state: &impl GlobalState,
+    params: <DocumentHighlightRequest as Request>::Params,
+) -> LSPRuntimeResult<<DocumentHighlightRequest as Request>::Result> {
+    let (feature, location) =
+        state.extract_feature_from_text(&params.text_document_position_params, 1)?;
+    let Feature::ExecutableDocument(document) = feature else {
+        return Err(LSPRuntimeError::ExpectedError);
+    };
+
+    let highlights = get_variable_occurrences(&document, location.span())
+        .into_iter()
+        .map(|occurrence| {
+            let lsp_location = state.transform_relay_location_in_editor_to_lsp_location(
+                location.with_span(occurrence.span),
+            )?;
+            Ok(DocumentHighlight {
+                range: lsp_location.range,
+                kind: Some(if occurrence.is_definition {
+                    DocumentHighlightKind::WRITE
+                } else {
+                    DocumentHighlightKind::READ
+                }),
+            })
+        })
+        .collect::<LSPRuntimeResult<Vec<_>>>()?;
+
+    if highlights.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(highlights))
+    }
+}
+
+/// Find the occurrences of the variable at the position, in the order of the
+/// source. Empty if the position is not on a variable.
+pub(crate) fn get_variable_occurrences(
+    document: &ExecutableDocument,
+    position_span: Span,
+) -> Vec<VariableOccurrence> {
+    let Some(definition) = document
+        .definitions
+        .iter()
+        .find(|definition| definition.location().contains(position_span))
+    else {
+        return vec![];
+    };
+
+    let mut occurrences = vec![];
+    collect_definition_occurrences(definition, &mut occurrences);
+    let Some(name) = occurrences
+        .iter()
+        .find(|occurrence| occurrence.span.contains(position_span))
+        .map(|occurrence| occurrence.name)
+    else {
+        return vec![];
+    };
+    occurrences.retain(|occurrence| occurrence.name == name);
+    occurrences.sort_by_key(|occurrence| occurrence.span.start);
+    occurrences
+}
+
+fn collect_definition_occurrences(
+    definition: &ExecutableDefinition,
+    occurrences: &mut Vec<VariableOccurrence>,
+) {
+    let (variable_definitions, directives, selections) = match definition {
+        ExecutableDefinition::Operation(operation) => (
+            &operation.variable_definitions,
+            &operation.directives,
+            &operation.selections,
+        ),
+        ExecutableDefinition::Fragment(fragment) => (
+            &fragment.variable_definitions,
+            &fragment.directives,
+            &fragment.selections,
+        ),
+    };
+    collect_variable_definition_occurrences(variable_definitions, occurrences);
+    for directive in directives {
+        if DirectiveName(directive.name.value) == *ARGUMENT_DEFINITION {
+            // The keys of `@argumentDefinitions` define the local variables
+            for argument in directive.arguments.iter().flat_map(|list| &list.items) {
+                occurrences.push(VariableOccurrence {
+                    name: argument.name.value,
+                    span: argument.name.span,
+                    is_definition: true,
+                });
+            }
+        } else {
+            collect_argument_occurrences(&directive.arguments, occurrences);
+        }
+    }
+    collect_selection_occurrences(&selections.items, occurrences);
+}
+
+fn collect_variable_definition_occurrences(
+    variable_definitions: &Option<List<VariableDefinition>>,
+    occurrences: &mut Vec<VariableOccurrence>,
+) {
+    for variable_definition in variable_definitions.iter().flat_map(|list| &list.items) {
+        occurrences.push(VariableOccurrence {
+            name: variable_definition.name.name,
+            span: variable_definition.name.span,
+            is_definition: true,
+        });
+    }
+}
+
+fn collect_selection_occurrences(
+    selections: &[Selection],
+    occurrences: &mut Vec<VariableOccurrence>,
+) {
+    for selection in selections {
+        for directive in selection.directives() {
+            collect_argument_occurrences(&directive.arguments, occurrences);
+        }
+        match selection {
+            Selection::ScalarField(field) => {
+                collect_argument_occurrences(&field.arguments, occurrences);
+            }
+            Selection::LinkedField(field) => {
+                collect_argument_occurrences(&field.arguments, occurrences);
+                collect_selection_occurrences(&field.selections.items, occurrences);
+            }
+            Selection::FragmentSpread(spread) => {
+                collect_argument_occurrences(&spread.arguments, occurrences);
+            }
+            Selection::InlineFragment(inline_fragment) => {
+                collect_selection_occurrences(&inline_fragment.selections.items, occurrences);
+            }
+        }
+    }
+}
+
+fn collect_argument_occurrences(
+    arguments: &Option<List<Argument>>,
+    occurrences: &mut Vec<VariableOccurrence>,
+) {
+    for argument in arguments.iter().flat_map(|arguments| &arguments.items) {
+        collect_value_occurrences(&argument.value, occurrences);
+    }
+}
+
+fn collect_value_occurrences(value: &Value, occurrences: &mut Vec<VariableOccurrence>) {
+    match value {
+        Value::Constant(_) => {}
+        Value::Variable(variable) => occurrences.push(VariableOccurrence {
+            name: variable.name,
+            span: variable.span,
+            is_definition: false,
+        }),
+        Value::List(list) => {
+            for item in &list.items {
+                collect_value_occurrences(item, occurrences);
+            }
+        }
+        Value::Object(object) => {
+            for argument in &object.items {
+                collect_value_occurrences(&argument.value, occurrences);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test;
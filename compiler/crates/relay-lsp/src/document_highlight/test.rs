@@ -0,0 +1,90 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use common::SourceLocationKey;
+use common::Span;
+use graphql_syntax::parse_executable;
+
+use super::get_variable_occurrences;
+
+/// Returns the text of the occurrences of the variable at the `|` in the
+/// source, with a `=` prefix for definitions.
+fn get_highlights(source: &str) -> Vec<String> {
+    let pos = source.find('|').unwrap() as u32;
+    let source = source.replace('|', "");
+    let document = parse_executable(&source, SourceLocationKey::standalone("/test")).unwrap();
+    get_variable_occurrences(&document, Span::new(pos, pos))
+        .into_iter()
+        .map(|occurrence| {
+            let text = &source[occurrence.span.start as usize..occurrence.span.end as usize];
+            if occurrence.is_definition {
+                format!("={text}")
+            } else {
+                text.to_string()
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn operation_variable() {
+    assert_eq!(
+        get_highlights(
+            r#"
+            query Test($id: ID!, $first: Int) {
+                node(id: $i|d) @include(if: $first) {
+                    ... on User {
+                        friends(first: $first, orderby: [$id]) {
+                            count
+                        }
+                    }
+                }
+                other: node(id: $id) {
+                    id
+                }
+            }
+            "#
+        ),
+        vec!["=$id", "$id", "$id", "$id"]
+    );
+}
+
+#[test]
+fn fragment_argument_definition() {
+    assert_eq!(
+        get_highlights(
+            r#"
+            fragment Test_user on User @argumentDefinitions(
+                siz|e: {type: "[Int]", defaultValue: 32}
+                cond: {type: "Boolean!"}
+            ) {
+                profilePicture(size: $size) @include(if: $cond) {
+                    uri
+                }
+                ...Other_user @arguments(size: $size)
+            }
+            "#
+        ),
+        vec!["=size", "$size", "$size"]
+    );
+}
+
+#[test]
+fn no_highlights_outside_variables() {
+    assert!(
+        get_highlights(
+            r#"
+            query Test($id: ID!) {
+                no|de(id: $id) {
+                    id
+                }
+            }
+            "#
+        )
+        .is_empty()
+    );
+}
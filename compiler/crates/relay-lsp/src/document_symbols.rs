@@ -0,0 +1,582 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Utilities for providing the document symbol and workspace symbol features
+
+use common::Location;
+use common::NamedItem;
+use common::SourceLocationKey;
+use common::Span;
+use common::TextSource;
+use docblock_shared::RELAY_RESOLVER_DIRECTIVE_NAME;
+use extract_graphql::JavaScriptSourceFeature;
+use graphql_syntax::ExecutableDefinition;
+use graphql_syntax::ExecutableDocument;
+use graphql_syntax::FieldDefinition;
+use graphql_syntax::Identifier;
+use graphql_syntax::InputValueDefinition;
+use graphql_syntax::List;
+use graphql_syntax::OperationKind;
+use graphql_syntax::SchemaDocument;
+use graphql_syntax::Selection;
+use graphql_syntax::TypeSystemDefinition;
+use graphql_syntax::parse_executable_with_error_recovery_and_parser_features;
+use intern::Lookup;
+use intern::string_key::Intern;
+use intern::string_key::StringKey;
+use lsp_types::DocumentSymbol;
+use lsp_types::DocumentSymbolResponse;
+use lsp_types::OneOf;
+use lsp_types::Range;
+use lsp_types::SymbolKind;
+use lsp_types::WorkspaceSymbol;
+use lsp_types::WorkspaceSymbolResponse;
+use lsp_types::request::DocumentSymbolRequest;
+use lsp_types::request::Request;
+use lsp_types::request::WorkspaceSymbolRequest;
+use relay_compiler::ProjectName;
+use relay_compiler::get_parser_features;
+use relay_docblock::DocblockIr;
+use relay_docblock::ResolverFieldDocblockIr;
+use relay_docblock::ResolverTypeDocblockIr;
+use schema::Schema;
+
+use crate::lsp_runtime_error::LSPRuntimeError;
+use crate::lsp_runtime_error::LSPRuntimeResult;
+use crate::server::GlobalState;
+use crate::utils::is_file_uri_in_dir;
+use crate::utils::parse_docblock_ir;
+use crate::utils::schema_source_location_key;
+
+/// The maximum number of symbols returned for a workspace symbol query.
+const MAX_WORKSPACE_SYMBOLS: usize = 100;
+
+/// Resolve a [`DocumentSymbolRequest`] to the outline of the operations,
+/// fragments and Relay Resolvers of a document.
+pub fn on_document_symbol(
+    state: &impl GlobalState,
+    params: <DocumentSymbolRequest as Request>::Params,
+) -> LSPRuntimeResult<<DocumentSymbolRequest as Request>::Result> {
+    let uri = params.text_document.uri;
+    if !is_file_uri_in_dir(&state.root_dir(), &uri) {
+        return Err(LSPRuntimeError::ExpectedError);
+    }
+
+    if let Ok(schema_source) = state.get_synced_schema_source(&uri) {
+        let text_source = schema_source.text_source();
+        let document = graphql_syntax::parse_schema_document(
+            &text_source.text,
+            schema_source_location_key(&uri),
+        )
+        .map_err(|_| LSPRuntimeError::ExpectedError)?;
+
+        return Ok(Some(DocumentSymbolResponse::Nested(
+            get_schema_document_symbols(&document, text_source),
+        )));
+    }
+
+    let javascript_sources = state.get_synced_javascript_sources(&uri)?;
+    let project_name: ProjectName = state.extract_project_name_from_uri(&uri)?.into();
+    let config = state.get_config();
+    let project_config = config.projects.get(&project_name).ok_or_else(|| {
+        LSPRuntimeError::UnexpectedError(format!(
+            "Unable to get project config for project {project_name}."
+        ))
+    })?;
+    let parser_features = get_parser_features(project_config);
+
+    let mut symbols = vec![];
+    let mut executable_definitions = vec![];
+    for (index, feature) in javascript_sources.iter().enumerate() {
+        if let JavaScriptSourceFeature::GraphQL(graphql_source) = feature {
+            let text_source = graphql_source.text_source();
+            let document = parse_executable_with_error_recovery_and_parser_features(
+                &text_source.text,
+                SourceLocationKey::embedded(uri.path().as_str(), index),
+                parser_features,
+            )
+            .item;
+            symbols.extend(get_executable_document_symbols(&document, text_source));
+            executable_definitions.extend(document.definitions);
+        }
+    }
+
+    for (index, feature) in javascript_sources.iter().enumerate() {
+        if let JavaScriptSourceFeature::Docblock(docblock_source) = feature {
+            let text_source = docblock_source.text_source();
+            if let Ok(Some(docblock_ir)) = parse_docblock_ir(
+                project_config,
+                &text_source.text,
+                SourceLocationKey::embedded(uri.path().as_str(), index),
+                &executable_definitions,
+            ) {
+                symbols.push(get_docblock_symbol(&docblock_ir, text_source));
+            }
+        }
+    }
+
+    symbols.sort_by_key(|symbol| symbol.range.start);
+
+    Ok(Some(DocumentSymbolResponse::Nested(symbols)))
+}
+
+/// Resolve a [`WorkspaceSymbolRequest`] to the fragments, operations and Relay
+/// Resolver fields of every project whose name fuzzy-matches the query.
+pub fn on_workspace_symbol(
+    state: &impl GlobalState,
+    params: <WorkspaceSymbolRequest as Request>::Params,
+) -> LSPRuntimeResult<<WorkspaceSymbolRequest as Request>::Result> {
+    let config = state.get_config();
+    let mut candidates = vec![];
+
+    for project_config in config.enabled_projects() {
+        let project_name: StringKey = project_config.name.into();
+        let Ok(program) = state.get_program(&project_name) else {
+            // The project has not been built yet
+            continue;
+        };
+
+        for fragment in program.fragments() {
+            candidates.push(WorkspaceSymbolCandidate {
+                name: fragment.name.item.0,
+                kind: SymbolKind::CLASS,
+                container_name: project_name,
+                location: fragment.name.location,
+            });
+        }
+        for operation in program.operations() {
+            candidates.push(WorkspaceSymbolCandidate {
+                name: operation.name.item.0,
+                kind: SymbolKind::FUNCTION,
+                container_name: project_name,
+                location: operation.name.location,
+            });
+        }
+        for field in program.schema.get_fields() {
+            let Some(parent_type) = field.parent_type else {
+                continue;
+            };
+            if field
+                .directives
+                .named(*RELAY_RESOLVER_DIRECTIVE_NAME)
+                .is_none()
+            {
+                continue;
+            }
+            candidates.push(WorkspaceSymbolCandidate {
+                name: format!(
+                    "{}.{}",
+                    program.schema.get_type_name(parent_type),
+                    field.name.item
+                )
+                .intern(),
+                kind: SymbolKind::PROPERTY,
+                container_name: project_name,
+                location: field.name.location,
+            });
+        }
+    }
+
+    let mut matches = candidates
+        .into_iter()
+        .filter_map(|candidate| {
+            fuzzy_match(&params.query, candidate.name.lookup()).map(|score| (score, candidate))
+        })
+        .collect::<Vec<_>>();
+    matches.sort_by(|(score_a, a), (score_b, b)| {
+        score_a
+            .cmp(score_b)
+            .then_with(|| a.name.lookup().len().cmp(&b.name.lookup().len()))
+            .then_with(|| a.name.lookup().cmp(b.name.lookup()))
+    });
+
+    let symbols = matches
+        .into_iter()
+        .filter_map(|(_, candidate)| {
+            let location = state
+                .transform_relay_location_in_editor_to_lsp_location(candidate.location)
+                .ok()?;
+            Some(WorkspaceSymbol {
+                name: candidate.name.to_string(),
+                kind: candidate.kind,
+                tags: None,
+                container_name: Some(candidate.container_name.to_string()),
+                location: OneOf::Left(location),
+                data: None,
+            })
+        })
+        .take(MAX_WORKSPACE_SYMBOLS)
+        .collect();
+
+    Ok(Some(WorkspaceSymbolResponse::Nested(symbols)))
+}
+
+struct WorkspaceSymbolCandidate {
+    name: StringKey,
+    kind: SymbolKind,
+    container_name: StringKey,
+    location: Location,
+}
+
+/// Case-insensitive subsequence match of the query in the candidate. Returns a
+/// score where lower is better: every skipped character of the candidate
+/// counts against the match, so prefixes and consecutive runs rank first.
+pub fn fuzzy_match(query: &str, candidate: &str) -> Option<usize> {
+    let mut score = 0;
+    let mut candidate_chars = candidate.chars().map(|chr| chr.to_ascii_lowercase());
+    for query_chr in query.chars().map(|chr| chr.to_ascii_lowercase()) {
+        if query_chr.is_whitespace() {
+            continue;
+        }
+        loop {
+            let candidate_chr = candidate_chars.next()?;
+            if candidate_chr == query_chr {
+                break;
+            }
+            score += 1;
+        }
+    }
+    Some(score)
+}
+
+/// Build the outline of an executable document: one symbol per operation or
+/// fragment, with its selections as children.
+pub fn get_executable_document_symbols(
+    document: &ExecutableDocument,
+    text_source: &TextSource,
+) -> Vec<DocumentSymbol> {
+    document
+        .definitions
+        .iter()
+        .map(|definition| match definition {
+            ExecutableDefinition::Operation(operation) => {
+                let operation_kind = operation
+                    .operation
+                    .as_ref()
+                    .map_or(OperationKind::Query, |(_, kind)| *kind);
+                let name = operation.name.as_ref().map_or_else(
+                    || format!("<anonymous {operation_kind}>"),
+                    |name| name.value.to_string(),
+                );
+                let selection_span = operation
+                    .name
+                    .as_ref()
+                    .map_or(operation.location.span(), |name| name.span);
+                create_symbol(
+                    name,
+                    Some(operation_kind.to_string()),
+                    SymbolKind::FUNCTION,
+                    text_source,
+                    operation.location.span(),
+                    selection_span,
+                    get_selection_symbols(&operation.selections, text_source),
+                )
+            }
+            ExecutableDefinition::Fragment(fragment) => create_symbol(
+                fragment.name.value.to_string(),
+                Some(format!("on {}", fragment.type_condition.type_.value)),
+                SymbolKind::CLASS,
+                text_source,
+                fragment.location.span(),
+                fragment.name.span,
+                get_selection_symbols(&fragment.selections, text_source),
+            ),
+        })
+        .collect()
+}
+
+fn get_selection_symbols(
+    selections: &List<Selection>,
+    text_source: &TextSource,
+) -> Vec<DocumentSymbol> {
+    selections
+        .items
+        .iter()
+        .map(|selection| match selection {
+            Selection::LinkedField(field) => create_field_symbol(
+                &field.name,
+                field.alias.as_ref().map(|alias| &alias.alias),
+                text_source,
+                field.span,
+                get_selection_symbols(&field.selections, text_source),
+            ),
+            Selection::ScalarField(field) => create_field_symbol(
+                &field.name,
+                field.alias.as_ref().map(|alias| &alias.alias),
+                text_source,
+                field.span,
+                vec![],
+            ),
+            Selection::FragmentSpread(spread) => create_symbol(
+                format!("...{}", spread.name.value),
+                None,
+                SymbolKind::CLASS,
+                text_source,
+                spread.span,
+                spread.name.span,
+                vec![],
+            ),
+            Selection::InlineFragment(fragment) => create_symbol(
+                fragment.type_condition.as_ref().map_or_else(
+                    || "...".to_string(),
+                    |type_condition| format!("... on {}", type_condition.type_.value),
+                ),
+                None,
+                SymbolKind::NAMESPACE,
+                text_source,
+                fragment.span,
+                fragment
+                    .type_condition
+                    .as_ref()
+                    .map_or(fragment.spread.span, |type_condition| type_condition.span),
+                get_selection_symbols(&fragment.selections, text_source),
+            ),
+        })
+        .collect()
+}
+
+fn create_field_symbol(
+    name: &Identifier,
+    alias: Option<&Identifier>,
+    text_source: &TextSource,
+    span: Span,
+    children: Vec<DocumentSymbol>,
+) -> DocumentSymbol {
+    match alias {
+        Some(alias) => create_symbol(
+            alias.value.to_string(),
+            Some(name.value.to_string()),
+            SymbolKind::FIELD,
+            text_source,
+            span,
+            alias.span,
+            children,
+        ),
+        None => create_symbol(
+            name.value.to_string(),
+            None,
+            SymbolKind::FIELD,
+            text_source,
+            span,
+            name.span,
+            children,
+        ),
+    }
+}
+
+/// Build the symbol of a `@RelayResolver` docblock, spanning the whole docblock.
+fn get_docblock_symbol(docblock_ir: &DocblockIr, text_source: &TextSource) -> DocumentSymbol {
+    let range = text_source.to_range();
+    let (name, kind, selection_range) = match docblock_ir {
+        DocblockIr::Field(ResolverFieldDocblockIr::TerseRelayResolver(resolver)) => (
+            format!("{}.{}", resolver.type_.item, resolver.field.name.value),
+            SymbolKind::PROPERTY,
+            text_source.to_span_range(resolver.field.name.span),
+        ),
+        DocblockIr::Type(ResolverTypeDocblockIr::StrongObjectResolver(strong_object)) => (
+            strong_object.type_name.value.to_string(),
+            SymbolKind::CLASS,
+            text_source.to_span_range(strong_object.type_name.span),
+        ),
+        DocblockIr::Type(ResolverTypeDocblockIr::WeakObjectType(weak_object)) => (
+            weak_object.type_name.value.to_string(),
+            SymbolKind::CLASS,
+            text_source.to_span_range(weak_object.type_name.span),
+        ),
+    };
+
+    #[allow(deprecated)]
+    DocumentSymbol {
+        name,
+        detail: Some("@RelayResolver".to_string()),
+        kind,
+        tags: None,
+        deprecated: None,
+        range,
+        selection_range: clamp_range(selection_range, range),
+        children: None,
+    }
+}
+
+/// Build the outline of a schema extension document: one symbol per type,
+/// with its fields or values as children.
+pub fn get_schema_document_symbols(
+    document: &SchemaDocument,
+    text_source: &TextSource,
+) -> Vec<DocumentSymbol> {
+    document
+        .definitions
+        .iter()
+        .filter_map(|definition| {
+            let (name, kind, span, children) = match definition {
+                TypeSystemDefinition::ObjectTypeDefinition(definition) => (
+                    &definition.name,
+                    SymbolKind::CLASS,
+                    definition.span,
+                    get_field_definition_symbols(&definition.fields, text_source),
+                ),
+                TypeSystemDefinition::ObjectTypeExtension(extension) => (
+                    &extension.name,
+                    SymbolKind::CLASS,
+                    extension.span,
+                    get_field_definition_symbols(&extension.fields, text_source),
+                ),
+                TypeSystemDefinition::InterfaceTypeDefinition(definition) => (
+                    &definition.name,
+                    SymbolKind::INTERFACE,
+                    definition.span,
+                    get_field_definition_symbols(&definition.fields, text_source),
+                ),
+                TypeSystemDefinition::InterfaceTypeExtension(extension) => (
+                    &extension.name,
+                    SymbolKind::INTERFACE,
+                    extension.span,
+                    get_field_definition_symbols(&extension.fields, text_source),
+                ),
+                TypeSystemDefinition::InputObjectTypeDefinition(definition) => (
+                    &definition.name,
+                    SymbolKind::STRUCT,
+                    definition.span,
+                    get_input_value_symbols(&definition.fields, text_source),
+                ),
+                TypeSystemDefinition::InputObjectTypeExtension(extension) => (
+                    &extension.name,
+                    SymbolKind::STRUCT,
+                    extension.span,
+                    get_input_value_symbols(&extension.fields, text_source),
+                ),
+                TypeSystemDefinition::EnumTypeDefinition(definition) => (
+                    &definition.name,
+                    SymbolKind::ENUM,
+                    definition.span,
+                    definition
+                        .values
+                        .iter()
+                        .flat_map(|values| &values.items)
+                        .map(|value| {
+                            create_symbol(
+                                value.name.value.to_string(),
+                                None,
+                                SymbolKind::ENUM_MEMBER,
+                                text_source,
+                                value.span,
+                                value.name.span,
+                                vec![],
+                            )
+                        })
+                        .collect(),
+                ),
+                TypeSystemDefinition::UnionTypeDefinition(definition) => {
+                    (&definition.name, SymbolKind::ENUM, definition.span, vec![])
+                }
+                TypeSystemDefinition::ScalarTypeDefinition(definition) => (
+                    &definition.name,
+                    SymbolKind::TYPE_PARAMETER,
+                    definition.span,
+                    vec![],
+                ),
+                TypeSystemDefinition::DirectiveDefinition(definition) => (
+                    &definition.name,
+                    SymbolKind::OPERATOR,
+                    definition.span,
+                    vec![],
+                ),
+                _ => return None,
+            };
+            Some(create_symbol(
+                name.value.to_string(),
+                None,
+                kind,
+                text_source,
+                span,
+                name.span,
+                children,
+            ))
+        })
+        .collect()
+}
+
+fn get_field_definition_symbols(
+    fields: &Option<List<FieldDefinition>>,
+    text_source: &TextSource,
+) -> Vec<DocumentSymbol> {
+    fields
+        .iter()
+        .flat_map(|fields| &fields.items)
+        .map(|field| {
+            create_symbol(
+                field.name.value.to_string(),
+                Some(field.type_.to_string()),
+                SymbolKind::FIELD,
+                text_source,
+                field.span,
+                field.name.span,
+                vec![],
+            )
+        })
+        .collect()
+}
+
+fn get_input_value_symbols(
+    fields: &Option<List<InputValueDefinition>>,
+    text_source: &TextSource,
+) -> Vec<DocumentSymbol> {
+    fields
+        .iter()
+        .flat_map(|fields| &fields.items)
+        .map(|field| {
+            create_symbol(
+                field.name.value.to_string(),
+                Some(field.type_.to_string()),
+                SymbolKind::FIELD,
+                text_source,
+                field.span,
+                field.name.span,
+                vec![],
+            )
+        })
+        .collect()
+}
+
+fn create_symbol(
+    name: String,
+    detail: Option<String>,
+    kind: SymbolKind,
+    text_source: &TextSource,
+    span: Span,
+    selection_span: Span,
+    children: Vec<DocumentSymbol>,
+) -> DocumentSymbol {
+    let range = text_source.to_span_range(span);
+    let selection_range = text_source.to_span_range(selection_span);
+
+    #[allow(deprecated)]
+    DocumentSymbol {
+        name,
+        detail,
+        kind,
+        tags: None,
+        deprecated: None,
+        range,
+        // Clients reject symbols whose selection range isn't contained in
+        // the full range, which can happen for recovered parse errors.
+        selection_range: clamp_range(selection_range, range),
+        children: if children.is_empty() {
+            None
+        } else {
+            Some(children)
+        },
+    }
+}
+
+fn clamp_range(range: Range, outer: Range) -> Range {
+    if range.start >= outer.start && range.end <= outer.end {
+        range
+    } else {
+        Range::new(outer.start, outer.start)
+    }
+}
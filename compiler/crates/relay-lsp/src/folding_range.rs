@@ -0,0 +1,199 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Utilities for providing the folding range feature, for the selection sets
+//! of GraphQL literals and for Relay Resolver docblocks.
+
+use common::SourceLocationKey;
+use common::Span;
+use common::TextSource;
+use docblock_syntax::DocblockAST;
+use docblock_syntax::DocblockSection;
+use docblock_syntax::parse_docblock;
+use extract_graphql::JavaScriptSourceFeature;
+use graphql_syntax::Argument;
+use graphql_syntax::Directive;
+use graphql_syntax::ExecutableDefinition;
+use graphql_syntax::ExecutableDocument;
+use graphql_syntax::List;
+use graphql_syntax::Selection;
+use graphql_syntax::parse_executable_with_error_recovery_and_parser_features;
+use lsp_types::FoldingRange;
+use lsp_types::FoldingRangeKind;
+use lsp_types::request::FoldingRangeRequest;
+use lsp_types::request::Request;
+use relay_compiler::ProjectName;
+use relay_compiler::get_parser_features;
+
+use crate::GlobalState;
+use crate::LSPRuntimeError;
+use crate::LSPRuntimeResult;
+
+/// Resolve a [`FoldingRangeRequest`] to the multi-line selection sets,
+/// variable definitions and arguments of the GraphQL literals of the
+/// document, and to its Relay Resolver docblocks.
+pub fn on_folding_range(
+    state: &impl GlobalState,
+    params: <FoldingRangeRequest as Request>::Params,
+) -> LSPRuntimeResult<<FoldingRangeRequest as Request>::Result> {
+    let uri = &params.text_document.uri;
+    let project_name = state.extract_project_name_from_uri(uri)?;
+    let config = state.get_config();
+    let project_config = config
+        .projects
+        .get(&ProjectName::from(project_name))
+        .ok_or_else(|| {
+            LSPRuntimeError::UnexpectedError(format!(
+                "Unable to get project config for project {project_name}."
+            ))
+        })?;
+
+    let mut folding_ranges = vec![];
+    for (index, source_feature) in state.get_synced_javascript_sources(uri)?.iter().enumerate() {
+        let source_location_key = SourceLocationKey::embedded(uri.as_str(), index);
+        match source_feature {
+            JavaScriptSourceFeature::GraphQL(graphql_source) => {
+                let text_source = graphql_source.text_source();
+                let document = parse_executable_with_error_recovery_and_parser_features(
+                    &text_source.text,
+                    source_location_key,
+                    get_parser_features(project_config),
+                )
+                .item;
+                folding_ranges.extend(get_graphql_folding_ranges(&document, text_source));
+            }
+            JavaScriptSourceFeature::Docblock(docblock_source) => {
+                let text_source = docblock_source.text_source();
+                let ast = parse_docblock(&text_source.text, source_location_key).ok();
+                folding_ranges.extend(get_docblock_folding_ranges(ast.as_ref(), text_source));
+            }
+        }
+    }
+
+    Ok(Some(folding_ranges))
+}
+
+/// Folding ranges of the multi-line lists of the document, in the order of
+/// the source. The closing brace or parenthesis of a list stays visible.
+pub(crate) fn get_graphql_folding_ranges(
+    document: &ExecutableDocument,
+    text_source: &TextSource,
+) -> Vec<FoldingRange> {
+    let mut spans = vec![];
+    for definition in &document.definitions {
+        match definition {
+            ExecutableDefinition::Operation(operation) => {
+                if let Some(variable_definitions) = &operation.variable_definitions {
+                    spans.push(variable_definitions.span);
+                }
+                collect_directive_spans(&operation.directives, &mut spans);
+                collect_selection_spans(&operation.selections, &mut spans);
+            }
+            ExecutableDefinition::Fragment(fragment) => {
+                if let Some(variable_definitions) = &fragment.variable_definitions {
+                    spans.push(variable_definitions.span);
+                }
+                collect_directive_spans(&fragment.directives, &mut spans);
+                collect_selection_spans(&fragment.selections, &mut spans);
+            }
+        }
+    }
+    spans.sort_by_key(|span| span.start);
+
+    spans
+        .into_iter()
+        .filter_map(|span| {
+            let range = text_source.to_span_range(span);
+            // Keep the line of the closing token visible
+            let end_line = range.end.line.checked_sub(1)?;
+            (end_line > range.start.line).then(|| FoldingRange {
+                start_line: range.start.line,
+                end_line,
+                kind: Some(FoldingRangeKind::Region),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+fn collect_selection_spans(selections: &List<Selection>, spans: &mut Vec<Span>) {
+    spans.push(selections.span);
+    for selection in &selections.items {
+        collect_directive_spans(selection.directives(), spans);
+        match selection {
+            Selection::ScalarField(field) => {
+                collect_argument_spans(&field.arguments, spans);
+            }
+            Selection::LinkedField(field) => {
+                collect_argument_spans(&field.arguments, spans);
+                collect_selection_spans(&field.selections, spans);
+            }
+            Selection::FragmentSpread(spread) => {
+                collect_argument_spans(&spread.arguments, spans);
+            }
+            Selection::InlineFragment(inline_fragment) => {
+                collect_selection_spans(&inline_fragment.selections, spans);
+            }
+        }
+    }
+}
+
+fn collect_directive_spans(directives: &[Directive], spans: &mut Vec<Span>) {
+    for directive in directives {
+        collect_argument_spans(&directive.arguments, spans);
+    }
+}
+
+fn collect_argument_spans(arguments: &Option<List<Argument>>, spans: &mut Vec<Span>) {
+    if let Some(arguments) = arguments {
+        spans.push(arguments.span);
+    }
+}
+
+/// Folding ranges of a docblock comment, and of its multi-line sections, like
+/// long descriptions. The comment is folded even if it can't be parsed.
+pub(crate) fn get_docblock_folding_ranges(
+    ast: Option<&DocblockAST>,
+    text_source: &TextSource,
+) -> Vec<FoldingRange> {
+    let range = text_source.to_range();
+    let mut folding_ranges = vec![];
+    if range.end.line > range.start.line {
+        folding_ranges.push(FoldingRange {
+            start_line: range.start.line,
+            end_line: range.end.line,
+            kind: Some(FoldingRangeKind::Comment),
+            ..Default::default()
+        });
+    }
+
+    for section in ast.iter().flat_map(|ast| &ast.sections) {
+        let span = match section {
+            DocblockSection::Field(field) => match &field.field_value {
+                Some(field_value) => Span::new(
+                    field.field_name.location.span().start,
+                    field_value.location.span().end,
+                ),
+                None => field.field_name.location.span(),
+            },
+            DocblockSection::FreeText(free_text) => free_text.location.span(),
+        };
+        let range = text_source.to_span_range(span);
+        if range.end.line > range.start.line {
+            folding_ranges.push(FoldingRange {
+                start_line: range.start.line,
+                end_line: range.end.line,
+                kind: Some(FoldingRangeKind::Comment),
+                ..Default::default()
+            });
+        }
+    }
+    folding_ranges
+}
+
+#[cfg(test)]
+mod test;
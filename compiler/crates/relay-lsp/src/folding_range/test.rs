@@ -0,0 +1,87 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use common::SourceLocationKey;
+use common::TextSource;
+use docblock_syntax::parse_docblock;
+use graphql_syntax::parse_executable_with_error_recovery;
+use lsp_types::FoldingRangeKind;
+
+use super::get_docblock_folding_ranges;
+use super::get_graphql_folding_ranges;
+
+fn get_graphql_lines(source: &str) -> Vec<(u32, u32)> {
+    let document =
+        parse_executable_with_error_recovery(source, SourceLocationKey::standalone("/test")).item;
+    get_graphql_folding_ranges(&document, &TextSource::from_whole_document(source))
+        .into_iter()
+        .map(|folding_range| (folding_range.start_line, folding_range.end_line))
+        .collect()
+}
+
+#[test]
+fn nested_selection_sets() {
+    assert_eq!(
+        get_graphql_lines(
+            r#"query Test($id: ID!) {
+  node(id: $id) {
+    id
+    ... on User {
+      name
+      friends(first: 10) { count }
+    }
+  }
+}"#
+        ),
+        vec![(0, 7), (1, 6), (3, 5)]
+    );
+}
+
+#[test]
+fn multi_line_arguments_and_variable_definitions() {
+    assert_eq!(
+        get_graphql_lines(
+            r#"fragment Test_user on User @argumentDefinitions(
+  first: {type: "Int"}
+  cond: {type: "Boolean!"}
+) {
+  friends(
+    first: $first
+  ) @include(if: $cond) {
+    count
+  }
+}"#
+        ),
+        vec![(0, 2), (3, 8), (4, 5), (6, 7)]
+    );
+}
+
+#[test]
+fn docblock() {
+    let text = r#"*
+ * @RelayResolver User.fullName: String
+ * @rootFragment UserFullNameResolver
+ *
+ * The full name of the user, which is the first name
+ * followed by the last name.
+ "#;
+    let text_source = TextSource::new(text, 10, 0);
+    let ast = parse_docblock(text, SourceLocationKey::standalone("/test")).unwrap();
+    let folding_ranges = get_docblock_folding_ranges(Some(&ast), &text_source);
+    assert_eq!(
+        folding_ranges
+            .iter()
+            .map(|folding_range| (folding_range.start_line, folding_range.end_line))
+            .collect::<Vec<_>>(),
+        vec![(10, 16), (13, 15)]
+    );
+    assert!(
+        folding_ranges
+            .iter()
+            .all(|folding_range| folding_range.kind == Some(FoldingRangeKind::Comment))
+    );
+}
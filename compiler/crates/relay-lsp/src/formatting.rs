@@ -0,0 +1,243 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Utilities for providing the document formatting feature
+
+use common::SourceLocationKey;
+use extract_graphql::JavaScriptSourceFeature;
+use graphql_syntax::ParserFeatures;
+use graphql_syntax::parse_executable_with_features;
+use graphql_syntax::parse_schema_document;
+use graphql_text_printer::prettier_print_executable_document;
+use graphql_text_printer::prettier_print_schema_document;
+use lsp_types::Range;
+use lsp_types::TextEdit;
+use lsp_types::Uri;
+use lsp_types::request::Formatting;
+use lsp_types::request::RangeFormatting;
+use lsp_types::request::Request;
+use relay_compiler::ProjectName;
+use relay_compiler::get_parser_features;
+
+use crate::lsp_runtime_error::LSPRuntimeError;
+use crate::lsp_runtime_error::LSPRuntimeResult;
+use crate::server::GlobalState;
+use crate::utils::is_file_uri_in_dir;
+
+const INDENT: &str = "  ";
+
+/// Resolve a [`Formatting`] request to the edits that format every `graphql`
+/// literal of a JavaScript document, or the whole of a schema document.
+pub fn on_formatting(
+    state: &impl GlobalState,
+    params: <Formatting as Request>::Params,
+) -> LSPRuntimeResult<<Formatting as Request>::Result> {
+    get_formatting_edits(state, &params.text_document.uri, None).map(Some)
+}
+
+/// Resolve a [`RangeFormatting`] request. Embedded literals are formatted as a
+/// whole if they intersect the requested range, schema documents are always
+/// formatted as a whole.
+pub fn on_range_formatting(
+    state: &impl GlobalState,
+    params: <RangeFormatting as Request>::Params,
+) -> LSPRuntimeResult<<RangeFormatting as Request>::Result> {
+    get_formatting_edits(state, &params.text_document.uri, Some(params.range)).map(Some)
+}
+
+fn get_formatting_edits(
+    state: &impl GlobalState,
+    uri: &Uri,
+    range: Option<Range>,
+) -> LSPRuntimeResult<Vec<TextEdit>> {
+    if !is_file_uri_in_dir(&state.root_dir(), uri) {
+        return Err(LSPRuntimeError::ExpectedError);
+    }
+
+    if let Ok(schema_source) = state.get_synced_schema_source(uri) {
+        let text_source = schema_source.text_source();
+        return Ok(format_schema_text(&text_source.text)
+            .filter(|new_text| new_text != &text_source.text)
+            .map(|new_text| TextEdit {
+                range: text_source.to_range(),
+                new_text,
+            })
+            .into_iter()
+            .collect());
+    }
+
+    let javascript_sources = state.get_synced_javascript_sources(uri)?;
+    let project_name: ProjectName = state.extract_project_name_from_uri(uri)?.into();
+    let config = state.get_config();
+    let project_config = config.projects.get(&project_name).ok_or_else(|| {
+        LSPRuntimeError::UnexpectedError(format!(
+            "Unable to get project config for project {project_name}."
+        ))
+    })?;
+    let parser_features = get_parser_features(project_config);
+
+    Ok(javascript_sources
+        .iter()
+        .filter_map(|feature| match feature {
+            JavaScriptSourceFeature::GraphQL(graphql_source) => Some(graphql_source.text_source()),
+            JavaScriptSourceFeature::Docblock(_) => None,
+        })
+        .filter_map(|text_source| {
+            let source_range = text_source.to_range();
+            if range.is_some_and(|range| !ranges_intersect(range, source_range)) {
+                return None;
+            }
+            format_embedded_text(&text_source.text, parser_features)
+                .filter(|new_text| new_text != &text_source.text)
+                .map(|new_text| TextEdit {
+                    range: source_range,
+                    new_text,
+                })
+        })
+        .collect())
+}
+
+/// Format the text of an embedded `graphql` literal. The formatted definitions
+/// are indented one level deeper than the line holding the closing backtick.
+///
+/// Returns `None` if the text doesn't parse, or contains comments that the
+/// printer would drop.
+pub fn format_embedded_text(text: &str, parser_features: ParserFeatures) -> Option<String> {
+    if contains_comment(text) {
+        return None;
+    }
+    let document =
+        parse_executable_with_features(text, SourceLocationKey::generated(), parser_features)
+            .ok()?;
+    let printed = prettier_print_executable_document(&document);
+    let indentation = get_template_indentation(text);
+
+    let mut formatted = String::from("\n");
+    for line in printed.lines() {
+        if !line.is_empty() {
+            formatted.push_str(&indentation);
+            formatted.push_str(INDENT);
+            formatted.push_str(line);
+        }
+        formatted.push('\n');
+    }
+    formatted.push_str(&indentation);
+    Some(formatted)
+}
+
+/// Format the text of a standalone schema or schema extension document.
+///
+/// A leading block of comments (e.g. a license header) is kept as is, other
+/// comments would be dropped by the printer, so we don't format documents
+/// that contain them.
+pub fn format_schema_text(text: &str) -> Option<String> {
+    let (header, body) = split_leading_comments(text);
+    if contains_comment(body) {
+        return None;
+    }
+    let document = parse_schema_document(body, SourceLocationKey::generated()).ok()?;
+    let printed = prettier_print_schema_document(&document);
+
+    let mut formatted = if header.trim().is_empty() {
+        String::new()
+    } else {
+        header.to_string()
+    };
+    formatted.push_str(printed.trim_end());
+    formatted.push('\n');
+    Some(formatted)
+}
+
+/// Indentation of the line holding the closing backtick of a template, if it's
+/// on its own line. Otherwise, one level less than the first non-empty line.
+fn get_template_indentation(text: &str) -> String {
+    let Some((_, last_line)) = text.rsplit_once('\n') else {
+        // Single line templates are expanded without any base indentation.
+        return String::new();
+    };
+    if last_line.chars().all(char::is_whitespace) {
+        return last_line.to_string();
+    }
+
+    text.lines()
+        .find(|line| !line.trim().is_empty())
+        .map(|line| {
+            let indentation = &line[..line.len() - line.trim_start().len()];
+            indentation
+                .strip_suffix(INDENT)
+                .unwrap_or(indentation)
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+/// Splits the source into the leading block of comment and blank lines, and
+/// the rest of the document.
+fn split_leading_comments(text: &str) -> (&str, &str) {
+    let mut end = 0;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            end += line.len();
+        } else {
+            break;
+        }
+    }
+    text.split_at(end)
+}
+
+/// Whether the GraphQL text contains a `#` comment outside of string literals.
+fn contains_comment(text: &str) -> bool {
+    let mut chars = text.chars().peekable();
+    while let Some(chr) = chars.next() {
+        match chr {
+            '#' => return true,
+            '"' => {
+                if text_starts_with(&mut chars, "\"\"") {
+                    // Block string, terminated by an unescaped `"""`
+                    while let Some(chr) = chars.next() {
+                        if chr == '\\' {
+                            chars.next();
+                        } else if chr == '"' && text_starts_with(&mut chars, "\"\"") {
+                            break;
+                        }
+                    }
+                } else {
+                    while let Some(chr) = chars.next() {
+                        match chr {
+                            '\\' => {
+                                chars.next();
+                            }
+                            '"' | '\n' => break,
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Consumes `expected` from the iterator if it comes next.
+fn text_starts_with(
+    chars: &mut std::iter::Peekable<impl Iterator<Item = char> + Clone>,
+    expected: &str,
+) -> bool {
+    let mut lookahead = chars.clone();
+    if expected.chars().all(|chr| lookahead.next() == Some(chr)) {
+        *chars = lookahead;
+        true
+    } else {
+        false
+    }
+}
+
+fn ranges_intersect(a: Range, b: Range) -> bool {
+    a.start <= b.end && b.start <= a.end
+}
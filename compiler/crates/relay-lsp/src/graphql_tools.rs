@@ -93,6 +93,18 @@ pub fn get_operation_only_program(
         next_program.insert_fragment(Arc::clone(fragment));
     }
 
+    insert_referenced_fragments(&mut next_program, selections_to_visit, program)?;
+
+    Some(next_program)
+}
+
+/// Adds to `next_program` the fragments of `program` that are transitively
+/// spread in `selections_to_visit` and not already part of `next_program`.
+pub(crate) fn insert_referenced_fragments<'a>(
+    next_program: &mut Program,
+    mut selections_to_visit: Vec<&'a Vec<Selection>>,
+    program: &'a Program,
+) -> Option<()> {
     let mut visited_fragments: HashSet<FragmentDefinitionName> = HashSet::default();
 
     while !selections_to_visit.is_empty() {
@@ -131,7 +143,7 @@ pub fn get_operation_only_program(
         }
     }
 
-    Some(next_program)
+    Some(())
 }
 
 /// Given the `Program` that contain operation+all its fragments this
@@ -224,8 +236,8 @@ pub(crate) fn get_query_text<
 ) -> LSPRuntimeResult<String> {
     let schema = state.get_schema(&project_name.into())?;
 
-    let project_config = state
-        .config
+    let config = state.get_config();
+    let project_config = config
         .enabled_projects()
         .find(|project_config| project_config.name == project_name)
         .ok_or_else(|| {
@@ -263,11 +275,8 @@ pub(crate) fn get_query_text<
                 project_config,
                 Arc::new(program),
                 Arc::clone(&state.perf_logger),
-                state.config.custom_transforms.as_ref(),
-                state
-                    .config
-                    .transferrable_refetchable_query_directives
-                    .clone(),
+                config.custom_transforms.as_ref(),
+                config.transferrable_refetchable_query_directives.clone(),
             )
             .map_err(LSPRuntimeError::UnexpectedError)?;
 
@@ -8,17 +8,22 @@
 //! Utilities for providing the hover feature
 
 use intern::Lookup;
+use lsp_types::Hover;
+use lsp_types::HoverContents;
 use lsp_types::LanguageString;
 use lsp_types::MarkedString;
 use lsp_types::request::HoverRequest;
 use lsp_types::request::Request;
+use relay_compiler::ProjectName;
 use resolution_path::ResolvePosition;
 use serde::Serialize;
 
 use crate::lsp_runtime_error::LSPRuntimeResult;
 use crate::server::GlobalState;
 
+mod selection_type;
 mod with_resolution_path;
+pub use selection_type::get_selection_type_hover;
 pub use with_resolution_path::get_hover;
 
 pub use self::with_resolution_path::ContentConsumerType;
@@ -79,15 +84,45 @@ pub fn on_hover(
 
     let schema_documentation = state.get_schema_documentation(project_name.lookup());
 
-    Ok(get_hover(
+    let program = state.get_program(&project_name)?;
+
+    let hover = get_hover(
         &resolution_path,
         &schema,
         project_name,
         state.get_extra_data_provider(),
         &schema_documentation,
-        &state.get_program(&project_name)?,
+        &program,
         state.get_content_consumer_type(),
-    ))
+    );
+
+    let config = state.get_config();
+    let selection_type = config
+        .projects
+        .get(&ProjectName::from(project_name))
+        .and_then(|project_config| {
+            get_selection_type_hover(&document, position_span, &program, project_config)
+        });
+
+    Ok(match (hover, selection_type) {
+        (Some(hover), Some(selection_type)) => Some(with_selection_type(hover, selection_type)),
+        (hover, _) => hover,
+    })
+}
+
+/// Appends the generated type of the hovered selection to the schema
+/// information of the hover.
+pub fn with_selection_type(hover: Hover, selection_type: MarkedString) -> Hover {
+    let mut contents = match hover.contents {
+        HoverContents::Scalar(contents) => vec![contents],
+        HoverContents::Array(contents) => contents,
+        HoverContents::Markup(markup) => vec![MarkedString::String(markup.value)],
+    };
+    contents.push(selection_type);
+    Hover {
+        contents: HoverContents::Array(contents),
+        ..hover
+    }
 }
 
 #[derive(Serialize)]
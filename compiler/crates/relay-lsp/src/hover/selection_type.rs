@@ -0,0 +1,209 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Utilities for showing the type `relay-typegen` generates for the selection
+//! under the cursor
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use common::NoopPerfLogger;
+use common::Span;
+use common::WithLocation;
+use graphql_ir::ExecutableDefinition;
+use graphql_ir::FragmentDefinition;
+use graphql_ir::FragmentDefinitionName;
+use graphql_ir::FragmentSignature;
+use graphql_ir::FragmentSignatures;
+use graphql_ir::Program;
+use graphql_ir::Selection;
+use graphql_syntax::ExecutableDocument;
+use intern::string_key::Intern;
+use intern::string_key::StringKey;
+use lsp_types::LanguageString;
+use lsp_types::MarkedString;
+use relay_compiler::config::ProjectConfig;
+use relay_transforms::apply_typegen_transforms;
+use relay_typegen::FragmentLocations;
+use relay_typegen::TypegenLanguage;
+use relay_typegen::generate_fragment_selections_type;
+use schema::SDLSchema;
+use schema::Schema;
+use schema::Type;
+
+use crate::graphql_tools::insert_referenced_fragments;
+use crate::server::build_ir_for_lsp_with_signatures;
+use crate::utils::collect_fragment_spread_names;
+use crate::utils::contains;
+
+/// Name of the fragment the hovered selection is wrapped in, so that the
+/// selection gets the same type as it would in the fragment that contains it.
+const SELECTION_FRAGMENT_NAME: &str = "RelayLSPHoveredSelection";
+
+/// Returns the type that `relay-typegen` would emit for the field or fragment
+/// spread at `position_span`, as it is seen by the parent selection. This takes
+/// directives such as `@required`, `@catch` or `@alias` and the masking of
+/// fragment spreads into account.
+pub fn get_selection_type_hover(
+    document: &ExecutableDocument,
+    position_span: Span,
+    program: &Program,
+    project_config: &ProjectConfig,
+) -> Option<MarkedString> {
+    let language = match project_config.typegen_config.language {
+        TypegenLanguage::TypeScript => "typescript",
+        TypegenLanguage::Flow => "flow",
+        TypegenLanguage::JavaScript => return None,
+    };
+
+    // Only the fragments spread in the document need a signature to build it.
+    let mut spread_names = vec![];
+    for definition in &document.definitions {
+        collect_fragment_spread_names(definition.selections(), &mut spread_names);
+    }
+    let spread_names: HashSet<StringKey> =
+        spread_names.into_iter().map(|name| name.value).collect();
+    let mut signatures = FragmentSignatures::default();
+    for name in spread_names {
+        if let Some(fragment) = program.fragment(FragmentDefinitionName(name)) {
+            signatures.insert(
+                fragment.name.item,
+                FragmentSignature {
+                    name: fragment.name,
+                    variable_definitions: fragment.variable_definitions.clone(),
+                    type_condition: fragment.type_condition,
+                    directives: fragment.directives.clone(),
+                },
+            );
+        }
+    }
+    let definitions =
+        build_ir_for_lsp_with_signatures(&program.schema, &document.definitions, &signatures)
+            .ok()?;
+
+    let fragment = definitions.iter().find_map(|definition| {
+        selection_fragment_for_definition(definition, position_span, &program.schema)
+    })?;
+
+    // Fragments of the document take precedence over the ones of the program,
+    // which may not have been rebuilt since the document was last edited.
+    let mut next_program = Program::new(Arc::clone(&program.schema));
+    let mut selections_to_visit = vec![&fragment.selections];
+    for definition in &definitions {
+        if let ExecutableDefinition::Fragment(fragment) = definition {
+            selections_to_visit.push(&fragment.selections);
+            next_program.insert_fragment(Arc::new(fragment.clone()));
+        }
+    }
+    next_program.insert_fragment(Arc::new(fragment.clone()));
+    insert_referenced_fragments(&mut next_program, selections_to_visit, program)?;
+
+    let typegen_program = apply_typegen_transforms(
+        project_config,
+        Arc::new(next_program),
+        Default::default(),
+        Arc::new(NoopPerfLogger),
+        None,
+        vec![],
+    )
+    .ok()?;
+
+    let typegen_fragment = typegen_program.fragment(fragment.name.item)?;
+    let fragment_locations = FragmentLocations::new(typegen_program.fragments());
+    Some(MarkedString::LanguageString(LanguageString {
+        language: language.to_string(),
+        value: generate_fragment_selections_type(
+            typegen_fragment,
+            &typegen_program.schema,
+            project_config,
+            &fragment_locations,
+        ),
+    }))
+}
+
+/// Wraps the selection at `position_span` of this definition in a fragment on
+/// the type of its parent.
+fn selection_fragment_for_definition(
+    definition: &ExecutableDefinition,
+    position_span: Span,
+    schema: &SDLSchema,
+) -> Option<FragmentDefinition> {
+    let (type_, variable_definitions, used_global_variables, selections, location) =
+        match definition {
+            ExecutableDefinition::Operation(operation) => (
+                operation.type_,
+                vec![],
+                operation.variable_definitions.clone(),
+                &operation.selections,
+                operation.name.location,
+            ),
+            ExecutableDefinition::Fragment(fragment) => (
+                fragment.type_condition,
+                fragment.variable_definitions.clone(),
+                fragment.used_global_variables.clone(),
+                &fragment.selections,
+                fragment.name.location,
+            ),
+        };
+    let (selection, parent_type) = find_selection(selections, type_, position_span, schema)?;
+
+    Some(FragmentDefinition {
+        name: WithLocation::new(
+            location,
+            FragmentDefinitionName(SELECTION_FRAGMENT_NAME.intern()),
+        ),
+        variable_definitions,
+        used_global_variables,
+        type_condition: parent_type,
+        directives: vec![],
+        selections: vec![selection.clone()],
+    })
+}
+
+/// Finds the field or fragment spread whose name is at `position_span`,
+/// together with the type it is selected on.
+fn find_selection<'a>(
+    selections: &'a [Selection],
+    parent_type: Type,
+    position_span: Span,
+    schema: &SDLSchema,
+) -> Option<(&'a Selection, Type)> {
+    selections.iter().find_map(|selection| match selection {
+        Selection::ScalarField(field) => {
+            (contains(field.definition.location.span(), position_span)
+                || field
+                    .alias
+                    .is_some_and(|alias| contains(alias.location.span(), position_span)))
+            .then_some((selection, parent_type))
+        }
+        Selection::LinkedField(field) => {
+            if contains(field.definition.location.span(), position_span)
+                || field
+                    .alias
+                    .is_some_and(|alias| contains(alias.location.span(), position_span))
+            {
+                Some((selection, parent_type))
+            } else {
+                let field_type = schema.field(field.definition.item).type_.inner();
+                find_selection(&field.selections, field_type, position_span, schema)
+            }
+        }
+        Selection::FragmentSpread(spread) => {
+            contains(spread.fragment.location.span(), position_span)
+                .then_some((selection, parent_type))
+        }
+        Selection::InlineFragment(inline_fragment) => find_selection(
+            &inline_fragment.selections,
+            inline_fragment.type_condition.unwrap_or(parent_type),
+            position_span,
+            schema,
+        ),
+        Selection::Condition(condition) => {
+            find_selection(&condition.selections, parent_type, position_span, schema)
+        }
+    })
+}
@@ -5,14 +5,28 @@
  * LICENSE file in the root directory of this source tree.
  */
 
+use std::sync::LazyLock;
+
+use common::ArgumentName;
+use common::DirectiveName;
 use common::Location;
+use common::NamedItem;
 use common::Span;
 use graphql_ir::Argument;
+use graphql_ir::ConstantValue;
+use graphql_ir::Directive;
+use graphql_ir::Field;
+use graphql_ir::FragmentDefinition;
 use graphql_ir::FragmentDefinitionName;
 use graphql_ir::FragmentSpread;
 use graphql_ir::InlineFragment;
+use graphql_ir::OperationDefinition;
 use graphql_ir::Program;
+use graphql_ir::Value;
 use graphql_ir::Visitor;
+use graphql_text_printer::PrinterOptions;
+use graphql_text_printer::print_value;
+use intern::string_key::Intern;
 use intern::string_key::StringKey;
 use lsp_types::InlayHint;
 use lsp_types::InlayHintLabel;
@@ -20,6 +34,9 @@ use lsp_types::InlayHintTooltip;
 use lsp_types::MarkupContent;
 use lsp_types::request::InlayHintRequest;
 use lsp_types::request::Request;
+use relay_transforms::ACTION_ARGUMENT;
+use relay_transforms::REQUIRED_DIRECTIVE_NAME;
+use relay_transforms::RequiredAction;
 use schema::SDLSchema;
 use schema::Schema;
 
@@ -29,6 +46,9 @@ use crate::server::GlobalState;
 use crate::server::build_ir_for_lsp;
 use crate::utils::is_file_uri_in_dir;
 
+static THROW_ON_FIELD_ERROR_DIRECTIVE: LazyLock<DirectiveName> =
+    LazyLock::new(|| DirectiveName("throwOnFieldError".intern()));
+
 pub fn on_inlay_hint_request(
     state: &impl GlobalState,
     params: <InlayHintRequest as Request>::Params,
@@ -65,22 +85,32 @@ pub fn on_inlay_hint_request(
 
 // Simplified version of the InlayHint struct that uses Relay location. Assumes
 // the following:
-// 1. The hint will be placed at the start of the location
-// 2. Padding right should be added
-// 3. Tooltips are rendered as markdown
+// 1. The hint will be placed at the start of the location with padding right,
+//    or at its end with padding left
+// 2. Tooltips are rendered as markdown
 struct Hint {
     location: Location,
+    placement: HintPlacement,
     label: String,
     tooltip: Option<String>,
 }
 
+enum HintPlacement {
+    Start,
+    End,
+}
+
 impl Hint {
     // Resolve Relay location to LSP location and create an InlayHint
     fn into_inlay_hint(self, state: &impl GlobalState) -> LSPRuntimeResult<InlayHint> {
         let lsp_location =
             state.transform_relay_location_in_editor_to_lsp_location(self.location)?;
+        let (position, padding_left, padding_right) = match self.placement {
+            HintPlacement::Start => (lsp_location.range.start, None, Some(true)),
+            HintPlacement::End => (lsp_location.range.end, Some(true), None),
+        };
         Ok(InlayHint {
-            position: lsp_location.range.start,
+            position,
             label: InlayHintLabel::String(self.label),
             kind: None,
             text_edits: None,
@@ -90,17 +120,27 @@ impl Hint {
                     value: tooltip,
                 })
             }),
-            padding_left: None,
-            padding_right: Some(true),
+            padding_left,
+            padding_right,
             data: None,
         })
     }
 }
 
+/// The selection that becomes `null` when one of the `@required` fields it
+/// contains is `null`. A `@required(action: THROW)` field has no location: the
+/// `null` stops there because the field throws, so there is nothing to show.
+struct RequiredBubbleTarget {
+    location: Option<Location>,
+    required_fields: Vec<StringKey>,
+}
+
 struct InlayHintVisitor<'a> {
     program: &'a Program,
     schema: &'a SDLSchema,
     inlay_hints: Vec<Hint>,
+    required_bubble_targets: Vec<RequiredBubbleTarget>,
+    throw_on_field_error: bool,
 }
 
 impl<'a> InlayHintVisitor<'a> {
@@ -109,12 +149,15 @@ impl<'a> InlayHintVisitor<'a> {
             program,
             schema,
             inlay_hints: vec![],
+            required_bubble_targets: vec![],
+            throw_on_field_error: false,
         }
     }
 
     fn add_alias_hint(&mut self, alias: StringKey, location: Location) {
         self.inlay_hints.push(Hint {
                 location,
+                placement: HintPlacement::Start,
                 label: format!("{alias}:"),
                 tooltip: Some("Fragment alias from the attached `@alias` directive. [Read More](https://relay.dev/docs/guides/alias-directive/).".to_string()),
             });
@@ -126,6 +169,7 @@ impl<'a> InlayHintVisitor<'a> {
                 let arg_type = self.schema.get_type_string(&arg_def.type_);
                 self.inlay_hints.push(Hint {
                     location: arg.value.location,
+                    placement: HintPlacement::Start,
                     label: arg_type,
                     tooltip: None,
                 });
@@ -133,6 +177,76 @@ impl<'a> InlayHintVisitor<'a> {
         }
     }
 
+    fn add_semantic_non_null_hint(&mut self, field_def: &schema::Field, location: Location) {
+        if !self.throw_on_field_error {
+            return;
+        }
+        let semantic_type = field_def.semantic_type();
+        if semantic_type != field_def.type_ {
+            let semantic_type = self.schema.get_type_string(&semantic_type);
+            self.inlay_hints.push(Hint {
+                location,
+                placement: HintPlacement::Start,
+                label: semantic_type.clone(),
+                tooltip: Some(format!(
+                    "`{}` is `@semanticNonNull`: with `@throwOnFieldError`, a field error throws instead of returning `null`, so it is typed as `{}`.",
+                    field_def.name.item, semantic_type
+                )),
+            });
+        }
+    }
+
+    fn push_required_bubble_target(&mut self, location: Option<Location>) {
+        self.required_bubble_targets.push(RequiredBubbleTarget {
+            location,
+            required_fields: vec![],
+        });
+    }
+
+    fn pop_required_bubble_target(&mut self) {
+        if let Some(target) = self.required_bubble_targets.pop()
+            && let Some(location) = target.location
+            && !target.required_fields.is_empty()
+        {
+            let fields = target
+                .required_fields
+                .iter()
+                .map(|field| field.to_string())
+                .collect::<Vec<_>>();
+            self.inlay_hints.push(Hint {
+                location,
+                placement: HintPlacement::Start,
+                label: format!("null if {} is null", fields.join(" or ")),
+                tooltip: Some(format!(
+                    "`@required` on {} makes this selection `null` when the field is `null`. [Read More](https://relay.dev/docs/guides/required-directive/).",
+                    fields
+                        .iter()
+                        .map(|field| format!("`{field}`"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )),
+            });
+        }
+    }
+
+    /// Records where the nullability of a `@required` field goes. Fields with
+    /// the `THROW` action throw instead, while the other actions make the
+    /// closest enclosing selection that is not `@required` itself `null`.
+    fn add_required_field(&mut self, action: RequiredAction, name: StringKey, location: Location) {
+        if action.is_throw_action() {
+            self.inlay_hints.push(Hint {
+                location,
+                placement: HintPlacement::Start,
+                label: "throws if null".to_string(),
+                tooltip: Some(format!(
+                    "`@required(action: THROW)` on `{name}` throws an error when the field is `null`, so its parents are not affected. [Read More](https://relay.dev/docs/guides/required-directive/).",
+                )),
+            });
+        } else if let Some(target) = self.required_bubble_targets.last_mut() {
+            target.required_fields.push(name);
+        }
+    }
+
     fn add_fragment_argument_hints(
         &mut self,
         fragment_name: FragmentDefinitionName,
@@ -148,6 +262,7 @@ impl<'a> InlayHintVisitor<'a> {
                     let arg_type = self.schema.get_type_string(&variable_def.type_);
                     self.inlay_hints.push(Hint {
                         location: arg.value.location,
+                        placement: HintPlacement::Start,
                         label: arg_type,
                         tooltip: None,
                     });
@@ -155,6 +270,53 @@ impl<'a> InlayHintVisitor<'a> {
             }
         }
     }
+
+    /// Shows the default values from `@argumentDefinitions` that the fragment
+    /// receives for the arguments the spread does not pass.
+    fn add_fragment_default_argument_hints(
+        &mut self,
+        fragment_name: FragmentDefinitionName,
+        arguments: &[Argument],
+        location: Location,
+    ) {
+        if let Some(fragment) = self.program.fragment(fragment_name) {
+            for variable_def in &fragment.variable_definitions {
+                if arguments
+                    .named(ArgumentName(variable_def.name.item.0))
+                    .is_some()
+                {
+                    continue;
+                }
+                if let Some(default_value) = &variable_def.default_value {
+                    let value = print_value(
+                        self.schema,
+                        &Value::Constant(default_value.item.clone()),
+                        PrinterOptions::default(),
+                    );
+                    self.inlay_hints.push(Hint {
+                        location,
+                        placement: HintPlacement::End,
+                        label: format!("{}: {}", variable_def.name.item, value),
+                        tooltip: Some(format!(
+                            "Default value of `${}` from the `@argumentDefinitions` of `{}`.",
+                            variable_def.name.item, fragment_name
+                        )),
+                    });
+                }
+            }
+        }
+    }
+}
+
+fn required_action(directives: &[Directive]) -> Option<RequiredAction> {
+    let action = directives
+        .named(*REQUIRED_DIRECTIVE_NAME)?
+        .arguments
+        .named(*ACTION_ARGUMENT)?;
+    match action.value.item.get_constant()? {
+        ConstantValue::Enum(action) => Some(RequiredAction::from(*action)),
+        _ => None,
+    }
 }
 
 impl Visitor for InlayHintVisitor<'_> {
@@ -164,16 +326,74 @@ impl Visitor for InlayHintVisitor<'_> {
 
     const VISIT_DIRECTIVES: bool = false;
 
+    fn visit_fragment(&mut self, fragment: &FragmentDefinition) {
+        self.throw_on_field_error = fragment
+            .directives
+            .named(*THROW_ON_FIELD_ERROR_DIRECTIVE)
+            .is_some();
+        self.push_required_bubble_target(Some(fragment.name.location));
+        self.default_visit_fragment(fragment);
+        self.pop_required_bubble_target();
+    }
+
+    fn visit_operation(&mut self, operation: &OperationDefinition) {
+        self.throw_on_field_error = operation
+            .directives
+            .named(*THROW_ON_FIELD_ERROR_DIRECTIVE)
+            .is_some();
+        self.push_required_bubble_target(Some(operation.name.location));
+        self.default_visit_operation(operation);
+        self.pop_required_bubble_target();
+    }
+
     fn visit_scalar_field(&mut self, field: &graphql_ir::ScalarField) {
         let field_def = self.schema.field(field.definition.item);
-        self.add_field_argument_hints(field_def, &field.arguments)
+        self.add_field_argument_hints(field_def, &field.arguments);
+        self.add_semantic_non_null_hint(field_def, field.alias_or_name_location());
+
+        if let Some(action) = required_action(&field.directives) {
+            self.add_required_field(
+                action,
+                field.alias_or_name(self.schema),
+                field.alias_or_name_location(),
+            );
+        }
     }
 
     fn visit_linked_field(&mut self, field: &graphql_ir::LinkedField) {
         let field_def = self.schema.field(field.definition.item);
         self.add_field_argument_hints(field_def, &field.arguments);
+        self.add_semantic_non_null_hint(field_def, field.alias_or_name_location());
 
-        self.default_visit_linked_field(field);
+        match required_action(&field.directives) {
+            Some(action) if action.is_throw_action() => {
+                self.add_required_field(
+                    action,
+                    field.alias_or_name(self.schema),
+                    field.alias_or_name_location(),
+                );
+                // The `@required` children of a field that throws make it
+                // throw too, instead of bubbling further up.
+                self.push_required_bubble_target(None);
+                self.default_visit_linked_field(field);
+                self.pop_required_bubble_target();
+            }
+            Some(action) => {
+                self.add_required_field(
+                    action,
+                    field.alias_or_name(self.schema),
+                    field.alias_or_name_location(),
+                );
+                // The `@required` children of a `@required` field bubble
+                // further up, together with the field itself.
+                self.default_visit_linked_field(field);
+            }
+            None => {
+                self.push_required_bubble_target(Some(field.alias_or_name_location()));
+                self.default_visit_linked_field(field);
+                self.pop_required_bubble_target();
+            }
+        }
     }
 
     fn visit_fragment_spread(&mut self, spread: &FragmentSpread) {
@@ -191,13 +411,27 @@ impl Visitor for InlayHintVisitor<'_> {
         }
 
         self.add_fragment_argument_hints(spread.fragment.item, &spread.arguments);
+        self.add_fragment_default_argument_hints(
+            spread.fragment.item,
+            &spread.arguments,
+            spread.fragment.location,
+        );
     }
 
     fn visit_inline_fragment(&mut self, fragment: &InlineFragment) {
         if let Ok(Some(alias)) = fragment.alias(self.schema) {
             self.add_alias_hint(alias.item, fragment.spread_location);
-        }
 
-        self.default_visit_inline_fragment(fragment)
+            // An aliased inline fragment is `null` when one of its `@required`
+            // fields is, instead of its parent.
+            self.push_required_bubble_target(Some(fragment.spread_location));
+            self.default_visit_inline_fragment(fragment);
+            self.pop_required_bubble_target();
+        } else {
+            self.default_visit_inline_fragment(fragment)
+        }
     }
 }
+
+#[cfg(test)]
+mod test;
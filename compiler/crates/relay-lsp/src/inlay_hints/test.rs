@@ -0,0 +1,185 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::sync::Arc;
+
+use common::SourceLocationKey;
+use graphql_ir::Program;
+use graphql_ir::Visitor;
+use graphql_ir::build;
+use graphql_syntax::parse_executable;
+use relay_test_schema::get_test_schema;
+
+use super::HintPlacement;
+use super::InlayHintVisitor;
+
+/// Returns the label of each hint, together with the text of the location it
+/// is placed before (`label|text`) or after (`text|label`).
+fn get_hints(source: &str) -> Vec<String> {
+    let schema = get_test_schema();
+    let document = parse_executable(source, SourceLocationKey::standalone("/test")).unwrap();
+    let definitions = build(&schema, &document.definitions).unwrap();
+    let program = Program::from_definitions(Arc::clone(&schema), definitions.clone());
+    let mut visitor = InlayHintVisitor::new(&program, &schema);
+    for definition in &definitions {
+        visitor.visit_executable_definition(definition);
+    }
+    visitor
+        .inlay_hints
+        .into_iter()
+        .map(|hint| {
+            let span = hint.location.span();
+            let text = &source[span.start as usize..span.end as usize];
+            match hint.placement {
+                HintPlacement::Start => format!("{}|{}", hint.label, text),
+                HintPlacement::End => format!("{}|{}", text, hint.label),
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn required_bubbles_to_nullable_parent() {
+    assert_eq!(
+        get_hints(
+            r#"
+            fragment Test_user on User {
+                name
+                profilePicture {
+                    uri @required(action: LOG)
+                    height @required(action: NONE)
+                }
+            }
+            "#
+        ),
+        vec!["null if uri or height is null|profilePicture"]
+    );
+}
+
+#[test]
+fn required_bubbles_through_required_parents() {
+    assert_eq!(
+        get_hints(
+            r#"
+            query TestQuery {
+                me @required(action: LOG) {
+                    pic: profilePicture @required(action: LOG) {
+                        uri @required(action: LOG)
+                    }
+                }
+            }
+            "#
+        ),
+        vec!["null if me or pic or uri is null|TestQuery"]
+    );
+}
+
+#[test]
+fn required_throw_does_not_bubble() {
+    assert_eq!(
+        get_hints(
+            r#"
+            fragment Test_user on User {
+                name @required(action: THROW)
+            }
+            "#
+        ),
+        vec!["throws if null|name"]
+    );
+}
+
+#[test]
+fn required_stops_at_throwing_parent() {
+    assert_eq!(
+        get_hints(
+            r#"
+            fragment Test_user on User {
+                profilePicture @required(action: THROW) {
+                    uri @required(action: LOG)
+                    height @required(action: THROW)
+                }
+            }
+            "#
+        ),
+        vec!["throws if null|profilePicture", "throws if null|height",]
+    );
+}
+
+#[test]
+fn required_bubbles_to_aliased_inline_fragment() {
+    assert_eq!(
+        get_hints(
+            r#"
+            fragment Test_user on User {
+                ... @alias(as: "names") {
+                    name @required(action: LOG)
+                }
+            }
+            "#
+        ),
+        vec!["names:|...", "null if name is null|..."]
+    );
+}
+
+#[test]
+fn semantic_non_null_with_throw_on_field_error() {
+    assert_eq!(
+        get_hints(
+            r#"
+            query TestQuery @throwOnFieldError {
+                opera {
+                    composer {
+                        name
+                    }
+                    cast {
+                        character
+                    }
+                }
+            }
+            query OtherQuery {
+                opera {
+                    composer {
+                        name
+                    }
+                }
+            }
+            "#
+        ),
+        vec!["User!|composer", "[Portrayal!]!|cast", "String!|character"]
+    );
+}
+
+#[test]
+fn fragment_argument_defaults() {
+    assert_eq!(
+        get_hints(
+            r#"
+            fragment Parent_user on User {
+                ...Child_user @arguments(cond: true)
+            }
+            fragment Child_user on User @argumentDefinitions(
+                size: {type: "[Int]", defaultValue: [32]}
+                cond: {type: "Boolean!", defaultValue: false}
+                first: {type: "Int"}
+            ) {
+                profilePicture(size: $size) @include(if: $cond) {
+                    uri
+                }
+                friends(first: $first) {
+                    count
+                }
+            }
+            "#
+        ),
+        vec![
+            "Boolean!|true",
+            "Child_user|size: [32]",
+            "[Int]|$size",
+            "Int|$first"
+        ]
+    );
+}
@@ -8,35 +8,48 @@
 #![deny(clippy::all)]
 #![allow(clippy::mutable_key_type)] // lsp_types::Uri
 
+pub mod call_hierarchy;
 mod client;
 pub mod code_action;
+pub mod code_lens;
 pub mod completion;
 pub mod daemon;
 pub mod diagnostic_reporter;
 mod docblock_resolution_info;
+pub mod document_highlight;
+pub mod document_symbols;
 mod explore_schema_for_type;
 pub mod find_field_usages;
+pub mod folding_range;
+pub mod formatting;
 pub mod goto_definition;
 mod graphql_tools;
 pub mod hover;
 mod inlay_hints;
+pub mod linked_editing_range;
 pub mod location;
 mod lsp_extra_data_provider;
 pub mod lsp_process_error;
 pub mod lsp_runtime_error;
 pub mod node_resolution_info;
 pub mod print_operation;
+pub mod pull_diagnostics;
 pub mod references;
 pub mod rename;
 mod resolved_types_at_location;
 mod search_schema_items;
+pub mod selection_range;
+pub mod semantic_tokens;
 pub mod server;
 pub mod shutdown;
+pub mod signature_help;
 mod status_reporter;
 pub mod status_updater;
 pub mod text_documents;
+pub mod type_hierarchy;
 pub mod type_information;
 pub mod utils;
+pub mod workspace;
 use std::path::Path;
 use std::sync::Arc;
 
@@ -62,10 +75,12 @@ use relay_compiler::config::Config;
 use relay_docblock::DocblockIr;
 use schema_documentation::SchemaDocumentation;
 use schema_documentation::SchemaDocumentationLoader;
+pub use server::ConfigLoader;
 pub use server::GlobalState;
 pub use server::LSPNotificationDispatch;
 pub use server::LSPRequestDispatch;
 pub use server::LSPState;
+pub use server::LanguageServerOptions;
 pub use server::Schemas;
 pub use utils::position_to_offset;
 
@@ -96,12 +111,37 @@ pub async fn start_language_server<
     extra_data_provider: Box<dyn LSPExtraDataProvider + Send + Sync>,
     schema_documentation_loader: Option<Box<dyn SchemaDocumentationLoader<TSchemaDocumentation>>>,
 ) -> LSPProcessResult<()>
+where
+    TPerfLogger: PerfLogger + 'static,
+{
+    start_language_server_with_options(
+        config,
+        perf_logger,
+        extra_data_provider,
+        schema_documentation_loader,
+        LanguageServerOptions::default(),
+    )
+    .await
+}
+
+/// Same as [`start_language_server`], with the optional behaviors of the
+/// server configured by `options`.
+pub async fn start_language_server_with_options<
+    TPerfLogger,
+    TSchemaDocumentation: SchemaDocumentation + 'static,
+>(
+    config: Config,
+    perf_logger: Arc<TPerfLogger>,
+    extra_data_provider: Box<dyn LSPExtraDataProvider + Send + Sync>,
+    schema_documentation_loader: Option<Box<dyn SchemaDocumentationLoader<TSchemaDocumentation>>>,
+    options: LanguageServerOptions,
+) -> LSPProcessResult<()>
 where
     TPerfLogger: PerfLogger + 'static,
 {
     let (connection, io_handles) = Connection::stdio();
     debug!("Initialized stdio transport layer");
-    server::initialize(&connection)?;
+    let initialize_params = server::initialize(&connection)?;
     debug!("JSON-RPC handshake completed");
     server::run(
         connection,
@@ -109,6 +149,8 @@ where
         perf_logger,
         extra_data_provider,
         schema_documentation_loader,
+        initialize_params.capabilities,
+        options,
     )
     .await?;
     io_handles.join()?;
@@ -0,0 +1,114 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Utilities for providing the linked editing range feature, which edits the
+//! name of a fragment defined in a file together with its spreads in the
+//! GraphQL literals of the same file.
+
+use common::Location;
+use graphql_syntax::ExecutableDefinition;
+use lsp_types::LinkedEditingRanges;
+use lsp_types::request::LinkedEditingRange;
+use lsp_types::request::Request;
+
+use crate::Feature;
+use crate::GlobalState;
+use crate::LSPRuntimeError;
+use crate::LSPRuntimeResult;
+use crate::utils::collect_fragment_spread_names;
+use crate::utils::contains;
+
+/// Names of fragments are GraphQL names.
+const FRAGMENT_NAME_PATTERN: &str = "[_A-Za-z][_0-9A-Za-z]*";
+
+pub fn on_linked_editing_range(
+    state: &impl GlobalState,
+    params: <LinkedEditingRange as Request>::Params,
+) -> LSPRuntimeResult<<LinkedEditingRange as Request>::Result> {
+    let (feature, location) =
+        state.extract_feature_from_text(&params.text_document_position_params, 1)?;
+    let Feature::ExecutableDocument(_) = feature else {
+        return Err(LSPRuntimeError::ExpectedError);
+    };
+    let definitions = state
+        .resolve_executable_definitions(&params.text_document_position_params.text_document.uri)?;
+
+    let locations = get_fragment_name_locations(&definitions, location);
+    if locations.is_empty() {
+        return Ok(None);
+    }
+    let ranges = locations
+        .into_iter()
+        .map(|location| {
+            state
+                .transform_relay_location_in_editor_to_lsp_location(location)
+                .map(|lsp_location| lsp_location.range)
+        })
+        .collect::<LSPRuntimeResult<Vec<_>>>()?;
+
+    Ok(Some(LinkedEditingRanges {
+        ranges,
+        word_pattern: Some(FRAGMENT_NAME_PATTERN.to_string()),
+    }))
+}
+
+/// Find the name of the fragment definition and the names of its spreads in
+/// the definitions of a file, when the position is on one of them. Empty if
+/// the position is not on a fragment name, or if the fragment is not defined
+/// in the file, as renaming the spreads alone would break them.
+pub(crate) fn get_fragment_name_locations(
+    definitions: &[ExecutableDefinition],
+    position: Location,
+) -> Vec<Location> {
+    let mut occurrences = vec![];
+    for definition in definitions {
+        let source_location = definition.location().source_location();
+        let mut spans = vec![];
+        let selections = match definition {
+            ExecutableDefinition::Operation(operation) => &operation.selections,
+            ExecutableDefinition::Fragment(fragment) => {
+                spans.push((fragment.name.value, fragment.name.span, true));
+                &fragment.selections
+            }
+        };
+        let mut spread_names = vec![];
+        collect_fragment_spread_names(&selections.items, &mut spread_names);
+        spans.extend(
+            spread_names
+                .into_iter()
+                .map(|name| (name.value, name.span, false)),
+        );
+        occurrences.extend(spans.into_iter().map(|(name, span, is_definition)| {
+            (name, Location::new(source_location, span), is_definition)
+        }));
+    }
+
+    let Some(name) = occurrences
+        .iter()
+        .find(|(_, location, _)| {
+            location.source_location() == position.source_location()
+                && contains(location.span(), position.span())
+        })
+        .map(|(name, _, _)| *name)
+    else {
+        return vec![];
+    };
+    occurrences.retain(|(occurrence_name, _, _)| *occurrence_name == name);
+    if !occurrences
+        .iter()
+        .any(|(_, _, is_definition)| *is_definition)
+    {
+        return vec![];
+    }
+    occurrences
+        .into_iter()
+        .map(|(_, location, _)| location)
+        .collect()
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,78 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use common::Location;
+use common::SourceLocationKey;
+use common::Span;
+use graphql_syntax::parse_executable;
+
+use super::get_fragment_name_locations;
+
+/// Returns the linked names for the `|` in one of the sources, as the index of
+/// their source and their offset in it.
+fn get_linked_names(sources: &[&str]) -> Vec<(u16, u32)> {
+    let mut position = None;
+    let mut definitions = vec![];
+    for (index, source) in sources.iter().enumerate() {
+        let source_location = SourceLocationKey::embedded("/test.js", index);
+        if let Some(offset) = source.find('|') {
+            let offset = offset as u32;
+            position = Some(Location::new(source_location, Span::new(offset, offset)));
+        }
+        let source = source.replace('|', "");
+        definitions.extend(
+            parse_executable(&source, source_location)
+                .unwrap()
+                .definitions,
+        );
+    }
+    get_fragment_name_locations(&definitions, position.unwrap())
+        .into_iter()
+        .map(|location| match location.source_location() {
+            SourceLocationKey::Embedded { index, .. } => (index, location.span().start),
+            _ => panic!("Expected an embedded location"),
+        })
+        .collect()
+}
+
+#[test]
+fn fragment_definition_name() {
+    assert_eq!(
+        get_linked_names(&[
+            "fragment Test_us|er on User { name }",
+            "query TestQuery { me { ...Test_user ... on User { ...Test_user } } }",
+        ]),
+        vec![(0, 9), (1, 26), (1, 53)]
+    );
+}
+
+#[test]
+fn fragment_spread_name_at_the_end() {
+    assert_eq!(
+        get_linked_names(&[
+            "fragment Test_user on User { name }",
+            "query TestQuery { me { ...Test_user| } }",
+        ]),
+        vec![(0, 9), (1, 26)]
+    );
+}
+
+#[test]
+fn fragment_defined_in_another_file() {
+    assert_eq!(
+        get_linked_names(&["query TestQuery { me { ...Other_u|ser } }"]),
+        vec![]
+    );
+}
+
+#[test]
+fn not_a_fragment_name() {
+    assert_eq!(
+        get_linked_names(&["fragment Test_user on User { na|me }"]),
+        vec![]
+    );
+}
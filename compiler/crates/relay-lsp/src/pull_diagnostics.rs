@@ -0,0 +1,160 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Utilities for the pull model of diagnostics, where clients request the
+//! diagnostics of documents instead of being sent all of them after each
+//! build.
+
+use std::collections::HashMap;
+
+use lsp_types::ClientCapabilities;
+use lsp_types::DiagnosticOptions;
+use lsp_types::DiagnosticServerCapabilities;
+use lsp_types::DocumentDiagnosticReport;
+use lsp_types::FullDocumentDiagnosticReport;
+use lsp_types::PreviousResultId;
+use lsp_types::RelatedFullDocumentDiagnosticReport;
+use lsp_types::RelatedUnchangedDocumentDiagnosticReport;
+use lsp_types::UnchangedDocumentDiagnosticReport;
+use lsp_types::WorkDoneProgressOptions;
+use lsp_types::WorkspaceDiagnosticReport;
+use lsp_types::WorkspaceDocumentDiagnosticReport;
+use lsp_types::WorkspaceFullDocumentDiagnosticReport;
+use lsp_types::request::DocumentDiagnosticRequest;
+use lsp_types::request::Request;
+use lsp_types::request::WorkspaceDiagnosticRequest;
+
+use crate::diagnostic_reporter::DocumentDiagnostics;
+use crate::lsp_runtime_error::LSPRuntimeResult;
+use crate::server::GlobalState;
+
+/// Whether the client supports `textDocument/diagnostic` requests, in which
+/// case diagnostics are not published to it.
+pub fn supports_pull_diagnostics(client_capabilities: &ClientCapabilities) -> bool {
+    client_capabilities
+        .text_document
+        .as_ref()
+        .is_some_and(|text_document| text_document.diagnostic.is_some())
+}
+
+/// Whether the client supports `workspace/diagnostic/refresh` requests.
+pub fn supports_diagnostics_refresh(client_capabilities: &ClientCapabilities) -> bool {
+    client_capabilities
+        .workspace
+        .as_ref()
+        .and_then(|workspace| workspace.diagnostic.as_ref())
+        .and_then(|diagnostic| diagnostic.refresh_support)
+        .unwrap_or(false)
+}
+
+pub(crate) fn pull_diagnostics_capability() -> DiagnosticServerCapabilities {
+    DiagnosticServerCapabilities::Options(DiagnosticOptions {
+        identifier: Some("relay".to_string()),
+        // Changing a fragment can cause errors in the documents that spread it
+        inter_file_dependencies: true,
+        workspace_diagnostics: true,
+        work_done_progress_options: WorkDoneProgressOptions {
+            work_done_progress: None,
+        },
+    })
+}
+
+/// Resolve a [`DocumentDiagnosticRequest`] to the diagnostics of the document,
+/// unless they are the ones of the previous result.
+pub fn on_document_diagnostic(
+    state: &impl GlobalState,
+    params: <DocumentDiagnosticRequest as Request>::Params,
+) -> LSPRuntimeResult<<DocumentDiagnosticRequest as Request>::Result> {
+    let document_diagnostics = state.get_document_diagnostics(&params.text_document.uri);
+    Ok(
+        get_document_diagnostic_report(document_diagnostics, params.previous_result_id.as_deref())
+            .into(),
+    )
+}
+
+/// Resolve a [`WorkspaceDiagnosticRequest`] to the diagnostics of the
+/// documents that changed since the previous results.
+pub fn on_workspace_diagnostic(
+    state: &impl GlobalState,
+    params: <WorkspaceDiagnosticRequest as Request>::Params,
+) -> LSPRuntimeResult<<WorkspaceDiagnosticRequest as Request>::Result> {
+    let items = get_workspace_diagnostic_reports(
+        state.get_workspace_diagnostics(),
+        &params.previous_result_ids,
+    );
+    Ok(WorkspaceDiagnosticReport { items }.into())
+}
+
+pub(crate) fn get_document_diagnostic_report(
+    document_diagnostics: DocumentDiagnostics,
+    previous_result_id: Option<&str>,
+) -> DocumentDiagnosticReport {
+    if previous_result_id == Some(document_diagnostics.result_id.as_str()) {
+        RelatedUnchangedDocumentDiagnosticReport {
+            related_documents: None,
+            unchanged_document_diagnostic_report: UnchangedDocumentDiagnosticReport {
+                result_id: document_diagnostics.result_id,
+            },
+        }
+        .into()
+    } else {
+        RelatedFullDocumentDiagnosticReport {
+            related_documents: None,
+            full_document_diagnostic_report: FullDocumentDiagnosticReport {
+                result_id: Some(document_diagnostics.result_id),
+                items: document_diagnostics.diagnostics,
+            },
+        }
+        .into()
+    }
+}
+
+/// Reports of the documents whose diagnostics changed since the previous
+/// results. Documents that no longer have diagnostics are reported with
+/// empty diagnostics; unchanged documents are left out.
+pub(crate) fn get_workspace_diagnostic_reports(
+    workspace_diagnostics: Vec<DocumentDiagnostics>,
+    previous_result_ids: &[PreviousResultId],
+) -> Vec<WorkspaceDocumentDiagnosticReport> {
+    let mut previous_result_ids = previous_result_ids
+        .iter()
+        .map(|previous| (&previous.uri, previous.value.as_str()))
+        .collect::<HashMap<_, _>>();
+
+    let mut reports = vec![];
+    for document_diagnostics in workspace_diagnostics {
+        let previous_result_id = previous_result_ids.remove(&document_diagnostics.uri);
+        if previous_result_id != Some(document_diagnostics.result_id.as_str()) {
+            reports.push(document_diagnostics);
+        }
+    }
+    for (uri, previous_result_id) in previous_result_ids {
+        let document_diagnostics = DocumentDiagnostics::new(uri.clone(), vec![]);
+        if previous_result_id != document_diagnostics.result_id {
+            reports.push(document_diagnostics);
+        }
+    }
+    reports.sort_by(|a, b| a.uri.as_str().cmp(b.uri.as_str()));
+    reports.into_iter().map(create_full_report).collect()
+}
+
+fn create_full_report(
+    document_diagnostics: DocumentDiagnostics,
+) -> WorkspaceDocumentDiagnosticReport {
+    WorkspaceFullDocumentDiagnosticReport {
+        uri: document_diagnostics.uri,
+        version: None,
+        full_document_diagnostic_report: FullDocumentDiagnosticReport {
+            result_id: Some(document_diagnostics.result_id),
+            items: document_diagnostics.diagnostics,
+        },
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,135 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::str::FromStr;
+
+use lsp_types::Diagnostic;
+use lsp_types::DocumentDiagnosticReport;
+use lsp_types::PreviousResultId;
+use lsp_types::Range;
+use lsp_types::Uri;
+use lsp_types::WorkspaceDocumentDiagnosticReport;
+
+use super::get_document_diagnostic_report;
+use super::get_workspace_diagnostic_reports;
+use crate::diagnostic_reporter::DocumentDiagnostics;
+
+fn uri(path: &str) -> Uri {
+    Uri::from_str(&format!("file:///{path}")).unwrap()
+}
+
+fn document_diagnostics(path: &str, messages: &[&str]) -> DocumentDiagnostics {
+    DocumentDiagnostics::new(
+        uri(path),
+        messages
+            .iter()
+            .map(|message| Diagnostic::new_simple(Range::default(), message.to_string()))
+            .collect(),
+    )
+}
+
+/// Returns the uri and messages of each full report.
+fn describe_reports(reports: &[WorkspaceDocumentDiagnosticReport]) -> Vec<(String, Vec<String>)> {
+    reports
+        .iter()
+        .map(|report| match report {
+            WorkspaceDocumentDiagnosticReport::Full(report) => (
+                report.uri.to_string(),
+                report
+                    .full_document_diagnostic_report
+                    .items
+                    .iter()
+                    .map(|diagnostic| diagnostic.message.clone())
+                    .collect(),
+            ),
+            WorkspaceDocumentDiagnosticReport::Unchanged(_) => {
+                panic!("Unchanged documents are not reported")
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn result_id_changes_with_diagnostics() {
+    assert_eq!(
+        document_diagnostics("a.js", &["error"]).result_id,
+        document_diagnostics("a.js", &["error"]).result_id
+    );
+    assert_ne!(
+        document_diagnostics("a.js", &["error"]).result_id,
+        document_diagnostics("a.js", &["other error"]).result_id
+    );
+}
+
+#[test]
+fn document_report() {
+    let diagnostics = document_diagnostics("a.js", &["error"]);
+    let result_id = diagnostics.result_id.clone();
+
+    let DocumentDiagnosticReport::Full(report) =
+        get_document_diagnostic_report(diagnostics.clone(), None)
+    else {
+        panic!("Expected a full report");
+    };
+    assert_eq!(
+        report.full_document_diagnostic_report.result_id,
+        Some(result_id.clone())
+    );
+    assert_eq!(report.full_document_diagnostic_report.items.len(), 1);
+
+    let DocumentDiagnosticReport::Unchanged(report) =
+        get_document_diagnostic_report(diagnostics, Some(&result_id))
+    else {
+        panic!("Expected an unchanged report");
+    };
+    assert_eq!(
+        report.unchanged_document_diagnostic_report.result_id,
+        result_id
+    );
+}
+
+#[test]
+fn workspace_reports_of_changed_documents() {
+    let unchanged = document_diagnostics("unchanged.js", &["error"]);
+    let previous_result_ids = vec![
+        PreviousResultId {
+            uri: uri("unchanged.js"),
+            value: unchanged.result_id.clone(),
+        },
+        PreviousResultId {
+            uri: uri("changed.js"),
+            value: document_diagnostics("changed.js", &["old error"]).result_id,
+        },
+        PreviousResultId {
+            uri: uri("fixed.js"),
+            value: document_diagnostics("fixed.js", &["error"]).result_id,
+        },
+        PreviousResultId {
+            uri: uri("clean.js"),
+            value: document_diagnostics("clean.js", &[]).result_id,
+        },
+    ];
+    let reports = get_workspace_diagnostic_reports(
+        vec![
+            unchanged,
+            document_diagnostics("changed.js", &["new error"]),
+            document_diagnostics("new.js", &["error"]),
+        ],
+        &previous_result_ids,
+    );
+    assert_eq!(
+        describe_reports(&reports),
+        vec![
+            (
+                "file:///changed.js".to_string(),
+                vec!["new error".to_string()]
+            ),
+            ("file:///fixed.js".to_string(), vec![]),
+            ("file:///new.js".to_string(), vec!["error".to_string()]),
+        ]
+    );
+}
@@ -13,6 +13,7 @@ use common::Location as IRLocation;
 use graphql_ir::FragmentDefinitionName;
 use graphql_ir::FragmentSpread;
 use graphql_ir::Program;
+use graphql_ir::Selection;
 use graphql_ir::Visitor;
 use intern::string_key::StringKey;
 use lsp_types::Location as LSPLocation;
@@ -106,13 +107,16 @@ fn get_references_response(
 }
 
 #[derive(Debug, Clone)]
-struct ReferenceFinder {
+pub(crate) struct ReferenceFinder {
     references: Vec<IRLocation>,
     name: StringKey,
 }
 
 impl ReferenceFinder {
-    fn get_references_to_fragment(program: &Program, name: StringKey) -> Vec<IRLocation> {
+    pub(crate) fn get_references_to_fragment(
+        program: &Program,
+        name: StringKey,
+    ) -> Vec<IRLocation> {
         let mut reference_finder = ReferenceFinder {
             references: vec![],
             name,
@@ -120,6 +124,18 @@ impl ReferenceFinder {
         reference_finder.visit_program(program);
         reference_finder.references
     }
+
+    pub(crate) fn get_references_to_fragment_in_selections(
+        selections: &[Selection],
+        name: StringKey,
+    ) -> Vec<IRLocation> {
+        let mut reference_finder = ReferenceFinder {
+            references: vec![],
+            name,
+        };
+        reference_finder.visit_selections(selections);
+        reference_finder.references
+    }
 }
 
 impl Visitor for ReferenceFinder {
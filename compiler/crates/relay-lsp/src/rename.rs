@@ -14,6 +14,7 @@ use std::sync::LazyLock;
 use common::DirectiveName;
 use common::Location as IRLocation;
 use common::NamedItem;
+use common::SourceLocationKey;
 use common::Span;
 use graphql_ir::FragmentDefinition;
 use graphql_ir::FragmentSpread;
@@ -22,8 +23,10 @@ use graphql_ir::Program;
 use graphql_ir::Visitor;
 use graphql_syntax::ExecutableDefinition;
 use graphql_syntax::OperationDefinition;
+use intern::Lookup;
 use intern::string_key::Intern;
 use intern::string_key::StringKey;
+use itertools::Itertools;
 use lsp_types::PrepareRenameResponse;
 use lsp_types::TextEdit;
 use lsp_types::Uri;
@@ -33,27 +36,41 @@ use lsp_types::request::Rename;
 use lsp_types::request::Request;
 use rayon::prelude::IntoParallelRefIterator;
 use rayon::prelude::ParallelIterator;
+use relay_docblock::DocblockIr;
+use relay_docblock::ResolverFieldDocblockIr;
+use relay_transforms::relay_resolvers::ResolverSchemaGenType;
+use relay_transforms::relay_resolvers::get_resolver_info;
 use resolution_path::ArgumentParent;
 use resolution_path::ArgumentPath;
 use resolution_path::DirectiveParent;
 use resolution_path::DirectivePath;
+use resolution_path::FieldDefinitionParent;
+use resolution_path::FieldDefinitionPath;
 use resolution_path::FragmentDefinitionPath;
 use resolution_path::FragmentSpreadPath;
 use resolution_path::IdentParent;
 use resolution_path::IdentPath;
+use resolution_path::LinkedFieldPath;
 use resolution_path::OperationDefinitionPath;
 use resolution_path::ResolutionPath;
 use resolution_path::ResolvePosition;
+use resolution_path::ScalarFieldPath;
+use resolution_path::SelectionParent;
 use resolution_path::VariableDefinitionListParent;
 use resolution_path::VariableDefinitionListPath;
 use resolution_path::VariableDefinitionPath;
 use resolution_path::VariableIdentifierParent;
 use resolution_path::VariableIdentifierPath;
+use schema::InterfaceID;
+use schema::SDLSchema;
+use schema::Schema;
+use schema::Type;
 
 use crate::Feature;
 use crate::GlobalState;
 use crate::LSPRuntimeError;
 use crate::LSPRuntimeResult;
+use crate::find_field_usages::find_field_locations;
 use crate::location::transform_relay_location_on_disk_to_lsp_location;
 
 static ARGUMENTS_DIRECTIVE: LazyLock<StringKey> = LazyLock::new(|| "arguments".intern());
@@ -71,8 +88,14 @@ pub fn on_rename(
     let program = &state.get_program(&state.extract_project_name_from_uri(uri)?)?;
     let root_dir = &state.root_dir();
 
-    let rename_request = create_rename_request(feature, location)?;
-    let locations_to_rename = get_locations_for_rename(rename_request, program)?;
+    let rename_request = create_rename_request(feature, location, &program.schema)?;
+    let resolver_exports = get_resolver_exports_for_rename(&rename_request, program);
+    let mut locations_to_rename = get_locations_for_rename(rename_request, program)?;
+    locations_to_rename.extend(resolver_exports.into_iter().filter_map(|resolver_export| {
+        let source =
+            std::fs::read_to_string(root_dir.join(resolver_export.import_path.lookup())).ok()?;
+        resolver_export.find_declaration(&source)
+    }));
     let changes = map_locations_to_text_edits(locations_to_rename, params.new_name, root_dir);
 
     Ok(Some(WorkspaceEdit {
@@ -86,12 +109,13 @@ pub fn on_prepare_rename(
     state: &impl GlobalState,
     params: <PrepareRenameRequest as Request>::Params,
 ) -> LSPRuntimeResult<<PrepareRenameRequest as Request>::Result> {
-    let root_dir = &state.root_dir();
     let (feature, location) = state.extract_feature_from_text(&params, 1)?;
+    let schema =
+        state.get_schema(&state.extract_project_name_from_uri(&params.text_document.uri)?)?;
 
-    let rename_request = create_rename_request(feature, location)?;
+    let rename_request = create_rename_request(feature, location, &schema)?;
     let lsp_location =
-        transform_relay_location_on_disk_to_lsp_location(root_dir, rename_request.location)?;
+        state.transform_relay_location_in_editor_to_lsp_location(rename_request.location)?;
 
     Ok(Some(PrepareRenameResponse::Range(lsp_location.range)))
 }
@@ -110,6 +134,12 @@ enum RenameKind {
         fragment_name: StringKey,
         argument_name: StringKey,
     },
+    /// A client schema extension or Relay Resolver field, renamed on the type
+    /// declaring it and on the types implementing that type.
+    Field {
+        type_name: StringKey,
+        field_name: StringKey,
+    },
 }
 
 #[derive(Debug)]
@@ -129,6 +159,7 @@ impl RenameRequest {
 pub fn create_rename_request(
     feature: Feature,
     location: IRLocation,
+    schema: &SDLSchema,
 ) -> LSPRuntimeResult<RenameRequest> {
     match feature {
         Feature::ExecutableDocument(document) => {
@@ -286,11 +317,150 @@ pub fn create_rename_request(
                         location,
                     ))
                 }
+                ResolutionPath::Ident(IdentPath {
+                    inner: field_name,
+                    parent:
+                        IdentParent::ScalarFieldName(ScalarFieldPath {
+                            inner: _,
+                            parent: selection_path,
+                        }),
+                }) => create_field_rename_request(
+                    schema,
+                    selection_path.parent,
+                    field_name.value,
+                    location.with_span(field_name.span),
+                ),
+                ResolutionPath::Ident(IdentPath {
+                    inner: field_name,
+                    parent:
+                        IdentParent::LinkedFieldName(LinkedFieldPath {
+                            inner: _,
+                            parent: selection_path,
+                        }),
+                }) => create_field_rename_request(
+                    schema,
+                    selection_path.parent,
+                    field_name.value,
+                    location.with_span(field_name.span),
+                ),
                 _ => Err(LSPRuntimeError::ExpectedError),
             }
         }
-        Feature::DocblockIr(_) => Err(LSPRuntimeError::ExpectedError),
-        Feature::SchemaDocument(_) => Err(LSPRuntimeError::ExpectedError),
+        Feature::DocblockIr(DocblockIr::Field(ResolverFieldDocblockIr::TerseRelayResolver(
+            resolver_ir,
+        ))) => {
+            // Both the `@relayField` and the legacy `@RelayResolver` tags are
+            // parsed to a terse resolver. The verbose legacy syntax (`@onType`,
+            // `@fieldName`) is a docblock error, so it never gets here.
+            let field_name = resolver_ir.field.name;
+            if !field_name.span.contains(location.span()) {
+                return Err(LSPRuntimeError::ExpectedError);
+            }
+            let type_ = schema
+                .get_type(resolver_ir.type_.item)
+                .ok_or(LSPRuntimeError::ExpectedError)?;
+
+            get_field_rename_request(
+                schema,
+                type_,
+                field_name.value,
+                location.with_span(field_name.span),
+            )
+        }
+        Feature::DocblockIr(DocblockIr::Type(_)) => Err(LSPRuntimeError::ExpectedError),
+        Feature::SchemaDocument(document) => match document.resolve((), location.span()) {
+            ResolutionPath::Ident(IdentPath {
+                inner: field_name,
+                parent: IdentParent::FieldDefinitionName(FieldDefinitionPath { inner: _, parent }),
+            }) => {
+                let type_name = match parent {
+                    FieldDefinitionParent::ObjectTypeDefinition(type_definition) => {
+                        type_definition.inner.name.value
+                    }
+                    FieldDefinitionParent::ObjectTypeExtension(type_extension) => {
+                        type_extension.inner.name.value
+                    }
+                    FieldDefinitionParent::InterfaceTypeDefinition(type_definition) => {
+                        type_definition.inner.name.value
+                    }
+                    FieldDefinitionParent::InterfaceTypeExtension(type_extension) => {
+                        type_extension.inner.name.value
+                    }
+                };
+                let type_ = schema
+                    .get_type(type_name)
+                    .ok_or(LSPRuntimeError::ExpectedError)?;
+
+                get_field_rename_request(
+                    schema,
+                    type_,
+                    field_name.value,
+                    location.with_span(field_name.span),
+                )
+            }
+            _ => Err(LSPRuntimeError::ExpectedError),
+        },
+    }
+}
+
+fn create_field_rename_request(
+    schema: &SDLSchema,
+    selection_parent: SelectionParent<'_>,
+    field_name: StringKey,
+    location: IRLocation,
+) -> LSPRuntimeResult<RenameRequest> {
+    let type_ = selection_parent
+        .find_parent_type(schema)
+        .ok_or(LSPRuntimeError::ExpectedError)?;
+
+    get_field_rename_request(schema, type_, field_name, location)
+}
+
+/// Only the fields defined by the client can be renamed. An interface field
+/// is renamed on the interface, so that its implementations stay valid.
+fn get_field_rename_request(
+    schema: &SDLSchema,
+    type_: Type,
+    field_name: StringKey,
+    location: IRLocation,
+) -> LSPRuntimeResult<RenameRequest> {
+    let field_id = schema
+        .named_field(type_, field_name)
+        .ok_or(LSPRuntimeError::ExpectedError)?;
+    if !schema.field(field_id).is_extension {
+        return Err(LSPRuntimeError::UnexpectedError(format!(
+            "Only client schema extension and Relay Resolver fields can be renamed, `{}.{field_name}` is defined by the server schema.",
+            schema.get_type_name(type_),
+        )));
+    }
+
+    let mut declaring_type = type_;
+    while let Some(interface_id) =
+        get_interfaces(schema, declaring_type)
+            .into_iter()
+            .find(|interface_id| {
+                schema
+                    .named_field(Type::Interface(*interface_id), field_name)
+                    .is_some()
+            })
+    {
+        declaring_type = Type::Interface(interface_id);
+    }
+
+    Ok(RenameRequest::new(
+        RenameKind::Field {
+            type_name: schema.get_type_name(declaring_type),
+            field_name,
+        },
+        location,
+    ))
+}
+
+fn get_interfaces(schema: &SDLSchema, type_: Type) -> Vec<InterfaceID> {
+    match type_ {
+        Type::Object(object_id) => schema.object(object_id).interfaces.clone(),
+        Type::Interface(interface_id) => schema.interface(interface_id).interfaces.clone(),
+        _ => vec![],
     }
 }
 
@@ -315,9 +485,157 @@ pub fn get_locations_for_rename(
         RenameKind::FragmentDefinitionOrSpread { fragment_name } => {
             Ok(FragmentFinder::get_fragment_usages(program, fragment_name))
         }
+        RenameKind::Field {
+            type_name,
+            field_name,
+        } => get_field_locations(program, type_name, field_name),
+    }
+}
+
+/// The definitions of the field on the type and its implementations, which
+/// are the same for a Relay Resolver defined on an interface, and every
+/// selection of it.
+fn get_field_locations(
+    program: &Program,
+    type_name: StringKey,
+    field_name: StringKey,
+) -> LSPRuntimeResult<Vec<IRLocation>> {
+    let schema = &program.schema;
+    let type_ = schema
+        .get_type(type_name)
+        .ok_or(LSPRuntimeError::ExpectedError)?;
+
+    let definition_locations = get_type_and_implementations(schema, type_)
+        .into_iter()
+        .filter_map(|type_| {
+            schema
+                .named_field(type_, field_name)
+                .map(|field_id| schema.field(field_id).name.location)
+        });
+    let usage_locations = find_field_locations(program, field_name, type_name)
+        .ok_or(LSPRuntimeError::ExpectedError)?;
+
+    Ok(definition_locations
+        .chain(usage_locations)
+        .filter(|location| !location.source_location().is_generated())
+        .unique()
+        .collect())
+}
+
+fn get_type_and_implementations(schema: &SDLSchema, type_: Type) -> Vec<Type> {
+    let mut types = vec![type_];
+    let mut index = 0;
+    while let Some(type_) = types.get(index) {
+        if let Type::Interface(interface_id) = type_ {
+            let interface = schema.interface(*interface_id);
+            let implementations = interface
+                .implementing_interfaces
+                .iter()
+                .map(|interface_id| Type::Interface(*interface_id))
+                .chain(
+                    interface
+                        .implementing_objects
+                        .iter()
+                        .map(|object_id| Type::Object(*object_id)),
+                )
+                .filter(|implementation| !types.contains(implementation))
+                .collect::<Vec<_>>();
+            types.extend(implementations);
+        }
+        index += 1;
+    }
+
+    types
+}
+
+/// The module of a Relay Resolver, and the name of the export artifacts import
+/// the resolver by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ResolverExport {
+    pub import_path: StringKey,
+    pub import_name: StringKey,
+}
+
+impl ResolverExport {
+    /// The location of the name of the exported declaration in the source of
+    /// the resolver module, e.g. `export function fieldName(...)`.
+    pub fn find_declaration(&self, source: &str) -> Option<IRLocation> {
+        let export_name = self.import_name.lookup();
+        let is_identifier_char = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+
+        source
+            .match_indices(export_name)
+            .map(|(start, _)| (start, start + export_name.len()))
+            .find(|(start, end)| {
+                let text_before_name = &source[..*start];
+                !text_before_name.ends_with(is_identifier_char)
+                    && !source[*end..].starts_with(is_identifier_char)
+                    && is_export_declaration(text_before_name)
+            })
+            .map(|(start, end)| {
+                IRLocation::new(
+                    SourceLocationKey::standalone(self.import_path.lookup()),
+                    Span::from_usize(start, end),
+                )
+            })
+    }
+}
+
+fn is_export_declaration(text_before_name: &str) -> bool {
+    let mut keywords = text_before_name.split_whitespace().rev();
+    let is_function = match keywords.next() {
+        Some("function" | "function*") => true,
+        Some("const" | "let" | "var") => false,
+        _ => return false,
+    };
+
+    match keywords.next() {
+        Some("export") => true,
+        Some("async") => is_function && keywords.next() == Some("export"),
+        _ => false,
     }
 }
 
+/// Artifacts import the resolver of a terse Relay Resolver field by the name
+/// of the field, so renaming the field also renames the export of its
+/// resolvers. The artifacts themselves are updated by the next build.
+pub fn get_resolver_exports_for_rename(
+    rename_request: &RenameRequest,
+    program: &Program,
+) -> Vec<ResolverExport> {
+    let RenameKind::Field {
+        type_name,
+        field_name,
+    } = rename_request.kind
+    else {
+        return vec![];
+    };
+    let schema = &program.schema;
+    let Some(type_) = schema.get_type(type_name) else {
+        return vec![];
+    };
+
+    get_type_and_implementations(schema, type_)
+        .into_iter()
+        .filter_map(|type_| {
+            let field = schema.field(schema.named_field(type_, field_name)?);
+            let resolver_info = get_resolver_info(schema, field, field.name.location)?.ok()?;
+            match resolver_info.resolver_type {
+                ResolverSchemaGenType::ResolverModule
+                    if resolver_info.import_name == Some(field_name) =>
+                {
+                    Some(ResolverExport {
+                        import_path: resolver_info.import_path,
+                        import_name: field_name,
+                    })
+                }
+                _ => None,
+            }
+        })
+        .unique()
+        .collect()
+}
+
 fn is_variable_defined_in_variable_definitions(
     fragment_definition: &graphql_syntax::FragmentDefinition,
     variable_name: StringKey,
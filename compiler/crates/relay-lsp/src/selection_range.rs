@@ -0,0 +1,281 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Utilities for providing the selection range feature, which expands the
+//! selection along the syntax tree of the GraphQL literal.
+
+use common::Span;
+use graphql_syntax::Argument;
+use graphql_syntax::ConstantValue;
+use graphql_syntax::Directive;
+use graphql_syntax::ExecutableDefinition;
+use graphql_syntax::ExecutableDocument;
+use graphql_syntax::List;
+use graphql_syntax::Selection;
+use graphql_syntax::Value;
+use graphql_syntax::VariableDefinition;
+use lsp_types::SelectionRange;
+use lsp_types::TextDocumentPositionParams;
+use lsp_types::request::Request;
+use lsp_types::request::SelectionRangeRequest;
+
+use crate::Feature;
+use crate::GlobalState;
+use crate::LSPRuntimeError;
+use crate::LSPRuntimeResult;
+
+/// Resolve a [`SelectionRangeRequest`] to the nodes of the GraphQL literal
+/// containing each position, from the innermost one to the whole literal.
+pub fn on_selection_range(
+    state: &impl GlobalState,
+    params: <SelectionRangeRequest as Request>::Params,
+) -> LSPRuntimeResult<<SelectionRangeRequest as Request>::Result> {
+    let selection_ranges = params
+        .positions
+        .into_iter()
+        .map(|position| {
+            let (feature, location) = state.extract_feature_from_text(
+                &TextDocumentPositionParams {
+                    text_document: params.text_document.clone(),
+                    position,
+                },
+                1,
+            )?;
+            let Feature::ExecutableDocument(document) = feature else {
+                return Err(LSPRuntimeError::ExpectedError);
+            };
+
+            let mut selection_range: Option<SelectionRange> = None;
+            for span in get_selection_spans(&document, location.span()) {
+                let range = state
+                    .transform_relay_location_in_editor_to_lsp_location(location.with_span(span))?
+                    .range;
+                if selection_range
+                    .as_ref()
+                    .is_some_and(|parent| parent.range == range)
+                {
+                    continue;
+                }
+                selection_range = Some(SelectionRange {
+                    range,
+                    parent: selection_range.map(Box::new),
+                });
+            }
+            selection_range.ok_or(LSPRuntimeError::ExpectedError)
+        })
+        .collect::<LSPRuntimeResult<Vec<_>>>()?;
+
+    Ok(Some(selection_ranges))
+}
+
+/// The spans of the nodes containing the position, from the whole document
+/// to the innermost node.
+pub(crate) fn get_selection_spans(document: &ExecutableDocument, position: Span) -> Vec<Span> {
+    let mut collector = SelectionSpanCollector {
+        position,
+        spans: vec![],
+    };
+    if collector.add(document.span) {
+        collector.collect_definitions(&document.definitions);
+    }
+    collector.spans
+}
+
+struct SelectionSpanCollector {
+    position: Span,
+    spans: Vec<Span>,
+}
+
+impl SelectionSpanCollector {
+    /// Adds the span if it contains the position. The end of a span counts
+    /// as part of it, as that is where the cursor is after typing a name.
+    fn add(&mut self, span: Span) -> bool {
+        let contains_position = span.start <= self.position.start && self.position.end <= span.end;
+        if contains_position && self.spans.last() != Some(&span) {
+            self.spans.push(span);
+        }
+        contains_position
+    }
+
+    // Each of the `collect_*` methods returns whether the node contains the
+    // position, so that only the first of two adjacent siblings is descended
+    // into.
+
+    fn collect_definitions(&mut self, definitions: &[ExecutableDefinition]) {
+        let Some(definition) = definitions
+            .iter()
+            .find(|definition| self.add(definition.location().span()))
+        else {
+            return;
+        };
+        match definition {
+            ExecutableDefinition::Operation(operation) => {
+                let _ = operation
+                    .name
+                    .as_ref()
+                    .is_some_and(|name| self.add(name.span))
+                    || self.collect_variable_definitions(&operation.variable_definitions)
+                    || self.collect_directives(&operation.directives)
+                    || self.collect_selection_list(&operation.selections);
+            }
+            ExecutableDefinition::Fragment(fragment) => {
+                let _ = self.add(fragment.name.span)
+                    || self.collect_variable_definitions(&fragment.variable_definitions)
+                    || (self.add(fragment.type_condition.span)
+                        && self.add(fragment.type_condition.type_.span))
+                    || self.collect_directives(&fragment.directives)
+                    || self.collect_selection_list(&fragment.selections);
+            }
+        }
+    }
+
+    fn collect_variable_definitions(
+        &mut self,
+        variable_definitions: &Option<List<VariableDefinition>>,
+    ) -> bool {
+        let Some(variable_definitions) = variable_definitions else {
+            return false;
+        };
+        if !self.add(variable_definitions.span) {
+            return false;
+        }
+        if let Some(variable_definition) = variable_definitions
+            .items
+            .iter()
+            .find(|variable_definition| self.add(variable_definition.span))
+        {
+            let _ = self.add(variable_definition.name.span)
+                || self.add(variable_definition.type_.span())
+                || variable_definition
+                    .default_value
+                    .as_ref()
+                    .is_some_and(|default_value| {
+                        self.add(default_value.span)
+                            && self.collect_constant_value(&default_value.value)
+                    })
+                || self.collect_directives(&variable_definition.directives);
+        }
+        true
+    }
+
+    fn collect_selection_list(&mut self, selections: &List<Selection>) -> bool {
+        if !self.add(selections.span) {
+            return false;
+        }
+        let Some(selection) = selections
+            .items
+            .iter()
+            .find(|selection| self.add(selection.span()))
+        else {
+            return true;
+        };
+        let _ = match selection {
+            Selection::ScalarField(field) => {
+                field
+                    .alias
+                    .as_ref()
+                    .is_some_and(|alias| self.add(alias.span))
+                    || self.add(field.name.span)
+                    || self.collect_arguments(&field.arguments)
+                    || self.collect_directives(&field.directives)
+            }
+            Selection::LinkedField(field) => {
+                field
+                    .alias
+                    .as_ref()
+                    .is_some_and(|alias| self.add(alias.span))
+                    || self.add(field.name.span)
+                    || self.collect_arguments(&field.arguments)
+                    || self.collect_directives(&field.directives)
+                    || self.collect_selection_list(&field.selections)
+            }
+            Selection::FragmentSpread(spread) => {
+                self.add(spread.name.span)
+                    || self.collect_arguments(&spread.arguments)
+                    || self.collect_directives(&spread.directives)
+            }
+            Selection::InlineFragment(inline_fragment) => {
+                inline_fragment
+                    .type_condition
+                    .as_ref()
+                    .is_some_and(|type_condition| {
+                        self.add(type_condition.span) && self.add(type_condition.type_.span)
+                    })
+                    || self.collect_directives(&inline_fragment.directives)
+                    || self.collect_selection_list(&inline_fragment.selections)
+            }
+        };
+        true
+    }
+
+    fn collect_directives(&mut self, directives: &[Directive]) -> bool {
+        let Some(directive) = directives.iter().find(|directive| self.add(directive.span)) else {
+            return false;
+        };
+        let _ = self.add(directive.name.span) || self.collect_arguments(&directive.arguments);
+        true
+    }
+
+    fn collect_arguments(&mut self, arguments: &Option<List<Argument>>) -> bool {
+        let Some(arguments) = arguments else {
+            return false;
+        };
+        self.add(arguments.span) && self.collect_argument_list(&arguments.items)
+    }
+
+    /// Returns true, as the list was checked to contain the position.
+    fn collect_argument_list(&mut self, arguments: &[Argument]) -> bool {
+        if let Some(argument) = arguments.iter().find(|argument| self.add(argument.span)) {
+            let _ = self.add(argument.name.span) || self.collect_value(&argument.value);
+        }
+        true
+    }
+
+    fn collect_value(&mut self, value: &Value) -> bool {
+        if !self.add(value.span()) {
+            return false;
+        }
+        match value {
+            Value::Constant(constant_value) => {
+                self.collect_constant_value(constant_value);
+            }
+            Value::Variable(_) => {}
+            Value::List(list) => {
+                list.items.iter().any(|item| self.collect_value(item));
+            }
+            Value::Object(object) => {
+                self.collect_argument_list(&object.items);
+            }
+        }
+        true
+    }
+
+    fn collect_constant_value(&mut self, value: &ConstantValue) -> bool {
+        if !self.add(value.span()) {
+            return false;
+        }
+        match value {
+            ConstantValue::List(list) => {
+                list.items
+                    .iter()
+                    .any(|item| self.collect_constant_value(item));
+            }
+            ConstantValue::Object(object) => {
+                if let Some(argument) = object.items.iter().find(|argument| self.add(argument.span))
+                {
+                    let _ = self.add(argument.name.span)
+                        || self.collect_constant_value(&argument.value);
+                }
+            }
+            _ => {}
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod test;
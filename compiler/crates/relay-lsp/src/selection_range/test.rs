@@ -0,0 +1,110 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use common::SourceLocationKey;
+use common::Span;
+use graphql_syntax::parse_executable;
+
+use super::get_selection_spans;
+
+/// Returns the text of the nodes containing the `|` in the source, from the
+/// innermost one to the definition, with whitespace collapsed. The outermost
+/// node is always the whole document.
+fn get_selections(source: &str) -> Vec<String> {
+    let pos = source.find('|').unwrap() as u32;
+    let source = source.replace('|', "");
+    let document = parse_executable(&source, SourceLocationKey::standalone("/test")).unwrap();
+    let spans = get_selection_spans(&document, Span::new(pos, pos));
+    assert_eq!(spans.first(), Some(&document.span));
+    spans
+        .into_iter()
+        .skip(1)
+        .rev()
+        .map(|span| {
+            source[span.start as usize..span.end as usize]
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect()
+}
+
+#[test]
+fn field_in_fragment() {
+    assert_eq!(
+        get_selections(
+            r#"
+            fragment Test_user on User {
+                id
+                friends(first: 10) {
+                    count
+                    edges { cur|sor }
+                }
+            }
+            "#
+        ),
+        vec![
+            "cursor",
+            "{ cursor }",
+            "edges { cursor }",
+            "{ count edges { cursor } }",
+            "friends(first: 10) { count edges { cursor } }",
+            "{ id friends(first: 10) { count edges { cursor } } }",
+            "fragment Test_user on User { id friends(first: 10) { count edges { cursor } } }",
+        ]
+    );
+}
+
+#[test]
+fn argument_value() {
+    assert_eq!(
+        get_selections(
+            r#"
+            query Test($ids: [ID!]) {
+                nodes(ids: [$ids, "4|2"]) {
+                    id
+                }
+            }
+            "#
+        ),
+        vec![
+            r#""42""#,
+            r#"[$ids, "42"]"#,
+            r#"ids: [$ids, "42"]"#,
+            r#"(ids: [$ids, "42"])"#,
+            r#"nodes(ids: [$ids, "42"]) { id }"#,
+            r#"{ nodes(ids: [$ids, "42"]) { id } }"#,
+            r#"query Test($ids: [ID!]) { nodes(ids: [$ids, "42"]) { id } }"#,
+        ]
+    );
+}
+
+#[test]
+fn type_condition() {
+    assert_eq!(
+        get_selections(
+            r#"
+            query Test {
+                node(id: "1") {
+                    ... on Us|er @include(if: true) {
+                        name
+                    }
+                }
+            }
+            "#
+        ),
+        vec![
+            "User",
+            "on User",
+            "... on User @include(if: true) { name }",
+            r#"{ ... on User @include(if: true) { name } }"#,
+            r#"node(id: "1") { ... on User @include(if: true) { name } }"#,
+            r#"{ node(id: "1") { ... on User @include(if: true) { name } } }"#,
+            r#"query Test { node(id: "1") { ... on User @include(if: true) { name } } }"#,
+        ]
+    );
+}
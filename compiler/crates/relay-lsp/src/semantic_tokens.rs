@@ -0,0 +1,479 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Utilities for providing the semantic tokens feature
+
+use common::NamedItem;
+use common::SourceLocationKey;
+use common::Span;
+use docblock_shared::RELAY_RESOLVER_DIRECTIVE_NAME;
+use extract_graphql::JavaScriptSourceFeature;
+use graphql_syntax::Argument;
+use graphql_syntax::ConstantValue;
+use graphql_syntax::Directive;
+use graphql_syntax::ExecutableDefinition;
+use graphql_syntax::ExecutableDocument;
+use graphql_syntax::Identifier;
+use graphql_syntax::List;
+use graphql_syntax::Selection;
+use graphql_syntax::TypeAnnotation;
+use graphql_syntax::Value;
+use graphql_syntax::VariableDefinition;
+use graphql_syntax::parse_executable_with_error_recovery_and_parser_features;
+use lsp_types::Range;
+use lsp_types::SemanticToken;
+use lsp_types::SemanticTokenModifier;
+use lsp_types::SemanticTokenType;
+use lsp_types::SemanticTokens;
+use lsp_types::SemanticTokensFullOptions;
+use lsp_types::SemanticTokensLegend;
+use lsp_types::SemanticTokensOptions;
+use lsp_types::SemanticTokensRangeResult;
+use lsp_types::SemanticTokensResult;
+use lsp_types::SemanticTokensServerCapabilities;
+use lsp_types::Uri;
+use lsp_types::WorkDoneProgressOptions;
+use lsp_types::request::Request;
+use lsp_types::request::SemanticTokensFullRequest;
+use lsp_types::request::SemanticTokensRangeRequest;
+use relay_compiler::ProjectName;
+use relay_compiler::get_parser_features;
+use resolution_path::IdentParent;
+use resolution_path::IdentPath;
+use resolution_path::LinkedFieldPath;
+use resolution_path::ResolutionPath;
+use resolution_path::ResolvePosition;
+use resolution_path::ScalarFieldPath;
+use schema::SDLSchema;
+use schema::Schema;
+
+use crate::lsp_runtime_error::LSPRuntimeError;
+use crate::lsp_runtime_error::LSPRuntimeResult;
+use crate::server::GlobalState;
+use crate::utils::is_file_uri_in_dir;
+
+/// The token types reported by the server, in the order of the legend.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RelayTokenType {
+    Operation,
+    Fragment,
+    Type,
+    Field,
+    Argument,
+    Variable,
+    Directive,
+    EnumValue,
+}
+
+impl RelayTokenType {
+    const ALL: [RelayTokenType; 8] = [
+        RelayTokenType::Operation,
+        RelayTokenType::Fragment,
+        RelayTokenType::Type,
+        RelayTokenType::Field,
+        RelayTokenType::Argument,
+        RelayTokenType::Variable,
+        RelayTokenType::Directive,
+        RelayTokenType::EnumValue,
+    ];
+
+    fn semantic_token_type(self) -> SemanticTokenType {
+        match self {
+            RelayTokenType::Operation => SemanticTokenType::FUNCTION,
+            RelayTokenType::Fragment => SemanticTokenType::CLASS,
+            RelayTokenType::Type => SemanticTokenType::TYPE,
+            RelayTokenType::Field => SemanticTokenType::PROPERTY,
+            RelayTokenType::Argument => SemanticTokenType::PARAMETER,
+            RelayTokenType::Variable => SemanticTokenType::VARIABLE,
+            RelayTokenType::Directive => SemanticTokenType::DECORATOR,
+            RelayTokenType::EnumValue => SemanticTokenType::ENUM_MEMBER,
+        }
+    }
+}
+
+/// Bitset of the token modifiers reported by the server. Each flag is the bit
+/// of the modifier at the same position in the legend.
+pub mod token_modifiers {
+    pub const DECLARATION: u32 = 1 << 0;
+    pub const DEPRECATED: u32 = 1 << 1;
+    pub const CLIENT_EXTENSION: u32 = 1 << 2;
+    pub const RESOLVER: u32 = 1 << 3;
+}
+
+const TOKEN_MODIFIERS: [SemanticTokenModifier; 4] = [
+    SemanticTokenModifier::DECLARATION,
+    SemanticTokenModifier::DEPRECATED,
+    SemanticTokenModifier::new("clientExtension"),
+    SemanticTokenModifier::new("resolver"),
+];
+
+/// The semantic tokens capability advertised during initialization.
+pub fn semantic_tokens_capability() -> SemanticTokensServerCapabilities {
+    SemanticTokensServerCapabilities::SemanticTokensOptions(SemanticTokensOptions {
+        work_done_progress_options: WorkDoneProgressOptions {
+            work_done_progress: None,
+        },
+        legend: SemanticTokensLegend {
+            token_types: RelayTokenType::ALL
+                .iter()
+                .map(|token_type| token_type.semantic_token_type())
+                .collect(),
+            token_modifiers: TOKEN_MODIFIERS.to_vec(),
+        },
+        range: Some(true),
+        full: Some(SemanticTokensFullOptions::Bool(true)),
+    })
+}
+
+/// A classified identifier of a GraphQL document.
+#[derive(Debug)]
+pub struct RelaySemanticToken {
+    pub span: Span,
+    pub token_type: RelayTokenType,
+    pub modifiers: u32,
+}
+
+/// Resolve a [`SemanticTokensFullRequest`] to the tokens of every `graphql`
+/// literal of a JavaScript document.
+pub fn on_semantic_tokens_full(
+    state: &impl GlobalState,
+    params: <SemanticTokensFullRequest as Request>::Params,
+) -> LSPRuntimeResult<<SemanticTokensFullRequest as Request>::Result> {
+    let data = get_semantic_tokens(state, &params.text_document.uri, None)?;
+    Ok(Some(SemanticTokensResult::Tokens(SemanticTokens {
+        result_id: None,
+        data,
+    })))
+}
+
+/// Resolve a [`SemanticTokensRangeRequest`] to the tokens of the `graphql`
+/// literals within the requested range.
+pub fn on_semantic_tokens_range(
+    state: &impl GlobalState,
+    params: <SemanticTokensRangeRequest as Request>::Params,
+) -> LSPRuntimeResult<<SemanticTokensRangeRequest as Request>::Result> {
+    let data = get_semantic_tokens(state, &params.text_document.uri, Some(params.range))?;
+    Ok(Some(SemanticTokensRangeResult::Tokens(SemanticTokens {
+        result_id: None,
+        data,
+    })))
+}
+
+fn get_semantic_tokens(
+    state: &impl GlobalState,
+    uri: &Uri,
+    range: Option<Range>,
+) -> LSPRuntimeResult<Vec<SemanticToken>> {
+    if !is_file_uri_in_dir(&state.root_dir(), uri) {
+        return Err(LSPRuntimeError::ExpectedError);
+    }
+
+    let javascript_sources = state.get_synced_javascript_sources(uri)?;
+    let project_name = state.extract_project_name_from_uri(uri)?;
+    let config = state.get_config();
+    let project_config = config
+        .projects
+        .get(&ProjectName::from(project_name))
+        .ok_or_else(|| {
+            LSPRuntimeError::UnexpectedError(format!(
+                "Unable to get project config for project {project_name}."
+            ))
+        })?;
+    let parser_features = get_parser_features(project_config);
+    // Tokens are still reported without schema information while the project
+    // is building.
+    let schema = state.get_schema(&project_name).ok();
+
+    let mut tokens = vec![];
+    for (index, feature) in javascript_sources.iter().enumerate() {
+        let JavaScriptSourceFeature::GraphQL(graphql_source) = feature else {
+            continue;
+        };
+        let text_source = graphql_source.text_source();
+        let document = parse_executable_with_error_recovery_and_parser_features(
+            &text_source.text,
+            SourceLocationKey::embedded(uri.as_str(), index),
+            parser_features,
+        )
+        .item;
+
+        for token in get_document_semantic_tokens(&document, schema.as_deref()) {
+            let token_range = text_source.to_span_range(token.span);
+            if range
+                .is_some_and(|range| token_range.end < range.start || token_range.start > range.end)
+            {
+                continue;
+            }
+            tokens.push((token_range, token));
+        }
+    }
+
+    Ok(encode_semantic_tokens(tokens))
+}
+
+/// Classify the identifiers of an executable document. Fields are annotated
+/// with schema information if a schema is provided.
+pub fn get_document_semantic_tokens(
+    document: &ExecutableDocument,
+    schema: Option<&SDLSchema>,
+) -> Vec<RelaySemanticToken> {
+    let mut collector = SemanticTokenCollector {
+        document,
+        schema,
+        tokens: vec![],
+    };
+    for definition in &document.definitions {
+        collector.collect_definition(definition);
+    }
+    collector.tokens
+}
+
+/// Encode tokens in the relative format of the protocol: each token's position
+/// is relative to the previous one.
+fn encode_semantic_tokens(mut tokens: Vec<(Range, RelaySemanticToken)>) -> Vec<SemanticToken> {
+    tokens.sort_by_key(|(range, _)| range.start);
+
+    let mut previous_line = 0;
+    let mut previous_start = 0;
+    tokens
+        .into_iter()
+        .map(|(range, token)| {
+            let delta_line = range.start.line - previous_line;
+            let delta_start = if delta_line == 0 {
+                range.start.character - previous_start
+            } else {
+                range.start.character
+            };
+            previous_line = range.start.line;
+            previous_start = range.start.character;
+            SemanticToken {
+                delta_line,
+                delta_start,
+                length: token.span.end - token.span.start,
+                token_type: RelayTokenType::ALL
+                    .iter()
+                    .position(|token_type| *token_type == token.token_type)
+                    .unwrap() as u32,
+                token_modifiers_bitset: token.modifiers,
+            }
+        })
+        .collect()
+}
+
+struct SemanticTokenCollector<'a> {
+    document: &'a ExecutableDocument,
+    schema: Option<&'a SDLSchema>,
+    tokens: Vec<RelaySemanticToken>,
+}
+
+impl SemanticTokenCollector<'_> {
+    fn push(&mut self, span: Span, token_type: RelayTokenType, modifiers: u32) {
+        // Error recovery can produce empty identifiers
+        if span.end > span.start {
+            self.tokens.push(RelaySemanticToken {
+                span,
+                token_type,
+                modifiers,
+            });
+        }
+    }
+
+    fn collect_definition(&mut self, definition: &ExecutableDefinition) {
+        match definition {
+            ExecutableDefinition::Operation(operation) => {
+                if let Some(name) = &operation.name {
+                    self.push(
+                        name.span,
+                        RelayTokenType::Operation,
+                        token_modifiers::DECLARATION,
+                    );
+                }
+                self.collect_variable_definitions(&operation.variable_definitions);
+                self.collect_directives(&operation.directives);
+                self.collect_selections(&operation.selections);
+            }
+            ExecutableDefinition::Fragment(fragment) => {
+                self.push(
+                    fragment.name.span,
+                    RelayTokenType::Fragment,
+                    token_modifiers::DECLARATION,
+                );
+                self.collect_variable_definitions(&fragment.variable_definitions);
+                self.push(fragment.type_condition.type_.span, RelayTokenType::Type, 0);
+                self.collect_directives(&fragment.directives);
+                self.collect_selections(&fragment.selections);
+            }
+        }
+    }
+
+    fn collect_variable_definitions(&mut self, definitions: &Option<List<VariableDefinition>>) {
+        for definition in definitions.iter().flat_map(|list| &list.items) {
+            self.push(
+                definition.name.span,
+                RelayTokenType::Variable,
+                token_modifiers::DECLARATION,
+            );
+            self.collect_type_annotation(&definition.type_);
+            if let Some(default_value) = &definition.default_value {
+                self.collect_constant_value(&default_value.value);
+            }
+            self.collect_directives(&definition.directives);
+        }
+    }
+
+    fn collect_type_annotation(&mut self, type_annotation: &TypeAnnotation) {
+        match type_annotation {
+            TypeAnnotation::Named(named) => self.push(named.name.span, RelayTokenType::Type, 0),
+            TypeAnnotation::List(list) => self.collect_type_annotation(&list.type_),
+            TypeAnnotation::NonNull(non_null) => self.collect_type_annotation(&non_null.type_),
+        }
+    }
+
+    fn collect_selections(&mut self, selections: &List<Selection>) {
+        for selection in &selections.items {
+            match selection {
+                Selection::LinkedField(field) => {
+                    self.collect_field(field.alias.as_ref().map(|alias| &alias.alias), &field.name);
+                    self.collect_arguments(&field.arguments);
+                    self.collect_directives(&field.directives);
+                    self.collect_selections(&field.selections);
+                }
+                Selection::ScalarField(field) => {
+                    self.collect_field(field.alias.as_ref().map(|alias| &alias.alias), &field.name);
+                    self.collect_arguments(&field.arguments);
+                    self.collect_directives(&field.directives);
+                }
+                Selection::FragmentSpread(spread) => {
+                    self.push(spread.name.span, RelayTokenType::Fragment, 0);
+                    self.collect_arguments(&spread.arguments);
+                    self.collect_directives(&spread.directives);
+                }
+                Selection::InlineFragment(fragment) => {
+                    if let Some(type_condition) = &fragment.type_condition {
+                        self.push(type_condition.type_.span, RelayTokenType::Type, 0);
+                    }
+                    self.collect_directives(&fragment.directives);
+                    self.collect_selections(&fragment.selections);
+                }
+            }
+        }
+    }
+
+    fn collect_field(&mut self, alias: Option<&Identifier>, name: &Identifier) {
+        let modifiers = self.get_field_modifiers(name);
+        if let Some(alias) = alias {
+            self.push(alias.span, RelayTokenType::Field, modifiers);
+        }
+        self.push(name.span, RelayTokenType::Field, modifiers);
+    }
+
+    /// Find the schema field of a field selection through its resolution path.
+    fn get_field_modifiers(&self, name: &Identifier) -> u32 {
+        let Some(schema) = self.schema else {
+            return 0;
+        };
+        let parent_type = match self.document.resolve((), name.span) {
+            ResolutionPath::Ident(IdentPath {
+                inner: _,
+                parent:
+                    IdentParent::ScalarFieldName(ScalarFieldPath {
+                        inner: _,
+                        parent: selection_path,
+                    }),
+            }) => selection_path.parent.find_parent_type(schema),
+            ResolutionPath::Ident(IdentPath {
+                inner: _,
+                parent:
+                    IdentParent::LinkedFieldName(LinkedFieldPath {
+                        inner: _,
+                        parent: selection_path,
+                    }),
+            }) => selection_path.parent.find_parent_type(schema),
+            _ => return 0,
+        };
+        let Some(field) = parent_type
+            .and_then(|parent_type| schema.named_field(parent_type, name.value))
+            .map(|field_id| schema.field(field_id))
+        else {
+            return 0;
+        };
+
+        let mut modifiers = 0;
+        if field.deprecated().is_some() {
+            modifiers |= token_modifiers::DEPRECATED;
+        }
+        if field.is_extension {
+            modifiers |= token_modifiers::CLIENT_EXTENSION;
+        }
+        if field
+            .directives
+            .named(*RELAY_RESOLVER_DIRECTIVE_NAME)
+            .is_some()
+        {
+            modifiers |= token_modifiers::RESOLVER;
+        }
+        modifiers
+    }
+
+    fn collect_directives(&mut self, directives: &[Directive]) {
+        for directive in directives {
+            self.push(
+                Span::new(directive.at.span.start, directive.name.span.end),
+                RelayTokenType::Directive,
+                0,
+            );
+            self.collect_arguments(&directive.arguments);
+        }
+    }
+
+    fn collect_arguments(&mut self, arguments: &Option<List<Argument>>) {
+        for argument in arguments.iter().flat_map(|list| &list.items) {
+            self.push(argument.name.span, RelayTokenType::Argument, 0);
+            self.collect_value(&argument.value);
+        }
+    }
+
+    fn collect_value(&mut self, value: &Value) {
+        match value {
+            Value::Constant(constant) => self.collect_constant_value(constant),
+            Value::Variable(variable) => self.push(variable.span, RelayTokenType::Variable, 0),
+            Value::List(list) => {
+                for item in &list.items {
+                    self.collect_value(item);
+                }
+            }
+            Value::Object(object) => {
+                for field in &object.items {
+                    self.push(field.name.span, RelayTokenType::Argument, 0);
+                    self.collect_value(&field.value);
+                }
+            }
+        }
+    }
+
+    fn collect_constant_value(&mut self, value: &ConstantValue) {
+        match value {
+            ConstantValue::Enum(node) => self.push(node.token.span, RelayTokenType::EnumValue, 0),
+            ConstantValue::List(list) => {
+                for item in &list.items {
+                    self.collect_constant_value(item);
+                }
+            }
+            ConstantValue::Object(object) => {
+                for field in &object.items {
+                    self.push(field.name.span, RelayTokenType::Argument, 0);
+                    self.collect_constant_value(&field.value);
+                }
+            }
+            ConstantValue::Int(_)
+            | ConstantValue::Float(_)
+            | ConstantValue::String(_)
+            | ConstantValue::Boolean(_)
+            | ConstantValue::Null(_) => {}
+        }
+    }
+}
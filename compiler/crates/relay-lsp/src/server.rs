@@ -18,6 +18,7 @@ use std::sync::Arc;
 use common::PerfLogEvent;
 use common::PerfLogger;
 use crossbeam::channel::Receiver;
+use crossbeam::channel::Sender;
 use crossbeam::select;
 use heartbeat::HeartbeatRequest;
 use heartbeat::on_heartbeat;
@@ -28,36 +29,70 @@ use lsp_server::Connection;
 use lsp_server::ErrorCode;
 use lsp_server::Message;
 use lsp_server::Notification;
+use lsp_server::Request as ServerRequest;
+use lsp_server::RequestId;
 use lsp_server::Response as ServerResponse;
 use lsp_server::ResponseError;
+pub use lsp_state::ConfigLoader;
 pub use lsp_state::GlobalState;
 pub use lsp_state::LSPState;
 pub use lsp_state::Schemas;
 pub use lsp_state::build_ir_for_lsp;
+pub use lsp_state::build_ir_for_lsp_with_signatures;
+use lsp_types::CallHierarchyServerCapability;
+use lsp_types::ClientCapabilities;
 use lsp_types::CodeActionOptions;
 use lsp_types::CodeActionProviderCapability;
+use lsp_types::CodeLensOptions;
 use lsp_types::CompletionOptions;
+use lsp_types::InitializeParams;
+use lsp_types::RegistrationParams;
 use lsp_types::RenameOptions;
 use lsp_types::ServerCapabilities;
 use lsp_types::TextDocumentSyncCapability;
 use lsp_types::TextDocumentSyncKind;
 use lsp_types::WorkDoneProgressOptions;
 use lsp_types::notification::Cancel;
+use lsp_types::notification::DidChangeConfiguration;
 use lsp_types::notification::DidChangeTextDocument;
+use lsp_types::notification::DidChangeWatchedFiles;
 use lsp_types::notification::DidCloseTextDocument;
 use lsp_types::notification::DidOpenTextDocument;
 use lsp_types::notification::DidSaveTextDocument;
 use lsp_types::notification::Exit;
+use lsp_types::request::CallHierarchyIncomingCalls;
+use lsp_types::request::CallHierarchyOutgoingCalls;
+use lsp_types::request::CallHierarchyPrepare;
 use lsp_types::request::CodeActionRequest;
+use lsp_types::request::CodeLensRequest;
+use lsp_types::request::CodeLensResolve;
 use lsp_types::request::Completion;
+use lsp_types::request::DocumentDiagnosticRequest;
+use lsp_types::request::DocumentHighlightRequest;
+use lsp_types::request::DocumentSymbolRequest;
+use lsp_types::request::FoldingRangeRequest;
+use lsp_types::request::Formatting;
 use lsp_types::request::GotoDefinition;
 use lsp_types::request::HoverRequest;
 use lsp_types::request::InlayHintRequest;
+use lsp_types::request::LinkedEditingRange;
 use lsp_types::request::PrepareRenameRequest;
+use lsp_types::request::RangeFormatting;
 use lsp_types::request::References;
+use lsp_types::request::RegisterCapability;
 use lsp_types::request::Rename;
+use lsp_types::request::Request;
 use lsp_types::request::ResolveCompletionItem;
+use lsp_types::request::SelectionRangeRequest;
+use lsp_types::request::SemanticTokensFullRequest;
+use lsp_types::request::SemanticTokensRangeRequest;
 use lsp_types::request::Shutdown;
+use lsp_types::request::SignatureHelpRequest;
+use lsp_types::request::TypeHierarchyPrepare;
+use lsp_types::request::TypeHierarchySubtypes;
+use lsp_types::request::TypeHierarchySupertypes;
+use lsp_types::request::WorkspaceDiagnosticRequest;
+use lsp_types::request::WorkspaceSymbolRequest;
 use relay_compiler::NoopArtifactWriter;
 use relay_compiler::config::Config;
 use schema_documentation::SchemaDocumentation;
@@ -65,15 +100,26 @@ use schema_documentation::SchemaDocumentationLoader;
 
 use self::task_queue::TaskProcessor;
 pub use crate::LSPExtraDataProvider;
+use crate::call_hierarchy::on_call_hierarchy_incoming_calls;
+use crate::call_hierarchy::on_call_hierarchy_outgoing_calls;
+use crate::call_hierarchy::on_prepare_call_hierarchy;
 use crate::code_action::on_code_action;
+use crate::code_lens::on_code_lens;
+use crate::code_lens::on_code_lens_resolve;
 use crate::completion::on_completion;
 use crate::completion::on_resolve_completion_item;
 use crate::daemon;
 use crate::daemon::DeamonRequestMessage;
+use crate::document_highlight::on_document_highlight;
+use crate::document_symbols::on_document_symbol;
+use crate::document_symbols::on_workspace_symbol;
 use crate::explore_schema_for_type::ExploreSchemaForType;
 use crate::explore_schema_for_type::on_explore_schema_for_type;
 use crate::find_field_usages::FindFieldUsages;
 use crate::find_field_usages::on_find_field_usages;
+use crate::folding_range::on_folding_range;
+use crate::formatting::on_formatting;
+use crate::formatting::on_range_formatting;
 use crate::goto_definition::GetSourceLocationOfTypeDefinition;
 use crate::goto_definition::on_get_source_location_of_type_definition;
 use crate::goto_definition::on_goto_definition;
@@ -81,10 +127,15 @@ use crate::graphql_tools::GraphQLExecuteQuery;
 use crate::graphql_tools::on_graphql_execute_query;
 use crate::hover::on_hover;
 use crate::inlay_hints::on_inlay_hint_request;
+use crate::linked_editing_range::on_linked_editing_range;
 use crate::lsp_process_error::LSPProcessResult;
 use crate::lsp_runtime_error::LSPRuntimeError;
 use crate::print_operation::PrintOperation;
 use crate::print_operation::on_print_operation;
+use crate::pull_diagnostics::on_document_diagnostic;
+use crate::pull_diagnostics::on_workspace_diagnostic;
+use crate::pull_diagnostics::pull_diagnostics_capability;
+use crate::pull_diagnostics::supports_pull_diagnostics;
 use crate::references::on_references;
 use crate::rename::on_prepare_rename;
 use crate::rename::on_rename;
@@ -92,22 +143,38 @@ use crate::resolved_types_at_location::ResolvedTypesAtLocation;
 use crate::resolved_types_at_location::on_get_resolved_types_at_location;
 use crate::search_schema_items::SearchSchemaItems;
 use crate::search_schema_items::on_search_schema_items;
+use crate::selection_range::on_selection_range;
+use crate::semantic_tokens::on_semantic_tokens_full;
+use crate::semantic_tokens::on_semantic_tokens_range;
+use crate::semantic_tokens::semantic_tokens_capability;
 use crate::server::lsp_state::handle_lsp_state_tasks;
 use crate::server::lsp_state_resources::LSPStateResources;
 use crate::server::task_queue::TaskQueue;
 use crate::shutdown::on_exit;
 use crate::shutdown::on_shutdown;
+use crate::signature_help::on_signature_help;
+use crate::signature_help::signature_help_capability;
 use crate::status_reporter::LSPStatusReporter;
 use crate::text_documents::on_cancel;
 use crate::text_documents::on_did_change_text_document;
 use crate::text_documents::on_did_close_text_document;
 use crate::text_documents::on_did_open_text_document;
 use crate::text_documents::on_did_save_text_document;
+use crate::type_hierarchy::on_prepare_type_hierarchy;
+use crate::type_hierarchy::on_type_hierarchy_subtypes;
+use crate::type_hierarchy::on_type_hierarchy_supertypes;
 use crate::type_information::get_type_information;
+use crate::workspace::on_did_change_configuration;
+use crate::workspace::on_did_change_watched_files;
+use crate::workspace::supports_watched_files_registration;
+use crate::workspace::watched_files_registration;
 
 /// Initializes an LSP connection, handling the `initialize` message and `initialized` notification
-/// handshake.
-pub fn initialize(connection: &Connection) -> LSPProcessResult<()> {
+/// handshake. Returns the parameters of the `initialize` request.
+pub fn initialize(connection: &Connection) -> LSPProcessResult<InitializeParams> {
+    let (initialize_id, initialize_params) = connection.initialize_start()?;
+    let initialize_params: InitializeParams = serde_json::from_value(initialize_params)?;
+
     // We don't currently negotiate character encoding in the Relay LSP.
     // This means we fall back to the LSP default of UTF-16, but we make no effort to
     // ensure that the LSP positions we emit are actually representing the source text as UTF-16.
@@ -136,16 +203,45 @@ pub fn initialize(connection: &Connection) -> LSPProcessResult<()> {
         definition_provider: Some(lsp_types::OneOf::Left(true)),
         references_provider: Some(lsp_types::OneOf::Left(true)),
         code_action_provider: Some(CodeActionProviderCapability::Options(CodeActionOptions {
-            code_action_kinds: Some(vec![lsp_types::CodeActionKind::QUICKFIX]),
+            code_action_kinds: Some(vec![
+                lsp_types::CodeActionKind::QUICKFIX,
+                lsp_types::CodeActionKind::REFACTOR_EXTRACT,
+                lsp_types::CodeActionKind::REFACTOR_INLINE,
+            ]),
             ..Default::default()
         })),
         inlay_hint_provider: Some(lsp_types::OneOf::Left(true)),
+        document_formatting_provider: Some(lsp_types::OneOf::Left(true)),
+        document_range_formatting_provider: Some(lsp_types::OneOf::Left(true)),
+        document_symbol_provider: Some(lsp_types::OneOf::Left(true)),
+        document_highlight_provider: Some(lsp_types::OneOf::Left(true)),
+        selection_range_provider: Some(lsp_types::SelectionRangeProviderCapability::Simple(true)),
+        folding_range_provider: Some(lsp_types::FoldingRangeProviderCapability::Simple(true)),
+        linked_editing_range_provider: Some(
+            lsp_types::LinkedEditingRangeServerCapabilities::Simple(true),
+        ),
+        workspace_symbol_provider: Some(lsp_types::OneOf::Left(true)),
+        semantic_tokens_provider: Some(semantic_tokens_capability()),
+        signature_help_provider: Some(signature_help_capability()),
+        code_lens_provider: Some(CodeLensOptions {
+            resolve_provider: Some(true),
+        }),
+        call_hierarchy_provider: Some(CallHierarchyServerCapability::Simple(true)),
+        // Clients that can't pull diagnostics keep being sent them
+        diagnostic_provider: supports_pull_diagnostics(&initialize_params.capabilities)
+            .then(pull_diagnostics_capability),
         ..Default::default()
     };
 
-    let server_capabilities = serde_json::to_value(server_capabilities)?;
-    connection.initialize(server_capabilities)?;
-    Ok(())
+    let mut server_capabilities = serde_json::to_value(server_capabilities)?;
+    // `ServerCapabilities` of lsp_types has no field for the type hierarchy
+    server_capabilities["typeHierarchyProvider"] = serde_json::Value::Bool(true);
+
+    connection.initialize_finish(
+        initialize_id,
+        serde_json::json!({ "capabilities": server_capabilities }),
+    )?;
+    Ok(initialize_params)
 }
 
 #[derive(Debug)]
@@ -155,16 +251,27 @@ pub enum Task {
     DeamonRequest(daemon::DeamonRequest),
 }
 
+/// Options of the language server that embedders can leave to their defaults.
+#[derive(Default)]
+pub struct LanguageServerOptions {
+    /// Loads the Relay config again when the config file or the settings of
+    /// the client change. Without it, the server keeps the config it was
+    /// started with.
+    pub config_loader: Option<ConfigLoader>,
+}
+
 /// Run the main server loop
 pub async fn run<
     TPerfLogger: PerfLogger + 'static,
     TSchemaDocumentation: SchemaDocumentation + 'static,
 >(
     connection: Connection,
-    mut config: Config,
+    config: Config,
     perf_logger: Arc<TPerfLogger>,
     extra_data_provider: Box<dyn LSPExtraDataProvider + Send + Sync>,
     schema_documentation_loader: Option<Box<dyn SchemaDocumentationLoader<TSchemaDocumentation>>>,
+    client_capabilities: ClientCapabilities,
+    options: LanguageServerOptions,
 ) -> LSPProcessResult<()> {
     debug!(
         "Running language server with config root {:?}",
@@ -175,23 +282,41 @@ pub async fn run<
     let mut task_queue = TaskQueue::new(Arc::new(task_processor));
     let task_scheduler = task_queue.get_scheduler();
 
-    config.artifact_writer = Box::new(NoopArtifactWriter);
-    config.status_reporter = Box::new(LSPStatusReporter::new(
-        config.root_dir.clone(),
-        connection.sender.clone(),
-    ));
+    let config = with_lsp_extensions(config, connection.sender.clone());
+    let config_loader = options.config_loader.map(|config_loader| -> ConfigLoader {
+        let sender = connection.sender.clone();
+        Box::new(move || Ok(with_lsp_extensions(config_loader()?, sender.clone())))
+    });
 
     let lsp_state = Arc::new(LSPState::new(
         Arc::new(config),
+        config_loader,
         connection.sender.clone(),
         Arc::clone(&task_scheduler),
         Arc::clone(&perf_logger),
         extra_data_provider,
         schema_documentation_loader,
+        &client_capabilities,
     ));
 
     LSPStateResources::new(Arc::clone(&lsp_state)).watch();
 
+    if supports_watched_files_registration(&client_capabilities) {
+        let request = ServerRequest::new(
+            RequestId::from("relay-register-watched-files".to_string()),
+            RegisterCapability::METHOD.to_string(),
+            RegistrationParams {
+                registrations: vec![watched_files_registration(
+                    &lsp_state.get_config().config_path,
+                )],
+            },
+        );
+        connection
+            .sender
+            .send(Message::Request(request))
+            .unwrap_or(());
+    }
+
     let daemon_task_scheduler = Arc::clone(&task_scheduler);
     tokio::spawn(daemon::start_server(Arc::new(move |request| {
         daemon_task_scheduler.schedule(Task::DeamonRequest(request));
@@ -212,6 +337,13 @@ pub async fn run<
     panic!("Client exited without proper shutdown sequence.")
 }
 
+/// The LSP doesn't write artifacts, and reports its status to the client.
+fn with_lsp_extensions(mut config: Config, sender: Sender<Message>) -> Config {
+    config.artifact_writer = Box::new(NoopArtifactWriter);
+    config.status_reporter = Box::new(LSPStatusReporter::new(config.root_dir.clone(), sender));
+    config
+}
+
 fn next_task(
     lsp_receiver: &Receiver<Message>,
     task_queue_receiver: &Receiver<Task>,
@@ -222,10 +354,12 @@ fn next_task(
     }
 }
 
-static NOTIFCATIONS_MUTATING_LSP_STATE: [&str; 3] = [
+static NOTIFCATIONS_MUTATING_LSP_STATE: [&str; 5] = [
     "textDocument/didOpen",
     "textDocument/didChange",
     "textDocument/didClose",
+    "workspace/didChangeWatchedFiles",
+    "workspace/didChangeConfiguration",
 ];
 
 struct LSPTaskProcessor;
@@ -335,6 +469,27 @@ fn dispatch_request(request: lsp_server::Request, lsp_state: &impl GlobalState)
             .on_request_sync::<InlayHintRequest>(on_inlay_hint_request)?
             .on_request_sync::<Rename>(on_rename)?
             .on_request_sync::<PrepareRenameRequest>(on_prepare_rename)?
+            .on_request_sync::<Formatting>(on_formatting)?
+            .on_request_sync::<RangeFormatting>(on_range_formatting)?
+            .on_request_sync::<DocumentSymbolRequest>(on_document_symbol)?
+            .on_request_sync::<WorkspaceSymbolRequest>(on_workspace_symbol)?
+            .on_request_sync::<SemanticTokensFullRequest>(on_semantic_tokens_full)?
+            .on_request_sync::<SemanticTokensRangeRequest>(on_semantic_tokens_range)?
+            .on_request_sync::<SignatureHelpRequest>(on_signature_help)?
+            .on_request_sync::<CodeLensRequest>(on_code_lens)?
+            .on_request_sync::<CodeLensResolve>(on_code_lens_resolve)?
+            .on_request_sync::<CallHierarchyPrepare>(on_prepare_call_hierarchy)?
+            .on_request_sync::<CallHierarchyIncomingCalls>(on_call_hierarchy_incoming_calls)?
+            .on_request_sync::<CallHierarchyOutgoingCalls>(on_call_hierarchy_outgoing_calls)?
+            .on_request_sync::<DocumentDiagnosticRequest>(on_document_diagnostic)?
+            .on_request_sync::<WorkspaceDiagnosticRequest>(on_workspace_diagnostic)?
+            .on_request_sync::<TypeHierarchyPrepare>(on_prepare_type_hierarchy)?
+            .on_request_sync::<TypeHierarchySupertypes>(on_type_hierarchy_supertypes)?
+            .on_request_sync::<TypeHierarchySubtypes>(on_type_hierarchy_subtypes)?
+            .on_request_sync::<DocumentHighlightRequest>(on_document_highlight)?
+            .on_request_sync::<SelectionRangeRequest>(on_selection_range)?
+            .on_request_sync::<FoldingRangeRequest>(on_folding_range)?
+            .on_request_sync::<LinkedEditingRange>(on_linked_editing_range)?
             .request();
 
         // If we have gotten here, we have not handled the request
@@ -435,6 +590,8 @@ fn dispatch_notification(
         .on_notification_sync::<DidCloseTextDocument>(on_did_close_text_document)?
         .on_notification_sync::<DidChangeTextDocument>(on_did_change_text_document)?
         .on_notification_sync::<DidSaveTextDocument>(on_did_save_text_document)?
+        .on_notification_sync::<DidChangeWatchedFiles>(on_did_change_watched_files)?
+        .on_notification_sync::<DidChangeConfiguration>(on_did_change_configuration)?
         .on_notification_sync::<Cancel>(on_cancel)?
         .on_notification_sync::<Exit>(on_exit)?
         .notification();
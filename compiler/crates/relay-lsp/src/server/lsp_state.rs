@@ -7,6 +7,9 @@
 
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::RwLock;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 
 use common::DiagnosticsResult;
 use common::Location;
@@ -16,15 +19,18 @@ use common::Span;
 use crossbeam::channel::SendError;
 use crossbeam::channel::Sender;
 use dashmap::DashMap;
+use dashmap::DashSet;
 use dashmap::mapref::entry::Entry;
 use docblock_syntax::parse_docblock;
 use extract_graphql::JavaScriptSourceFeature;
 use fnv::FnvBuildHasher;
 use graphql_ir::BuilderOptions;
+use graphql_ir::FragmentSignatures;
 use graphql_ir::FragmentVariablesSemantic;
 use graphql_ir::OperationDefinitionName;
 use graphql_ir::Program;
 use graphql_ir::RelayMode;
+use graphql_ir::build_ir_with_external_signatures;
 use graphql_ir::build_ir_with_extra_features;
 use graphql_syntax::ExecutableDefinition;
 use graphql_syntax::ExecutableDocument;
@@ -35,7 +41,9 @@ use intern::string_key::Intern;
 use intern::string_key::StringKey;
 use log::debug;
 use lsp_server::Message;
+use lsp_types::ClientCapabilities;
 use lsp_types::Diagnostic;
+use lsp_types::Position;
 use lsp_types::Range;
 use lsp_types::TextDocumentPositionParams;
 use lsp_types::Uri;
@@ -62,21 +70,30 @@ use crate::FeatureResolutionInfo;
 use crate::LSPExtraDataProvider;
 use crate::LSPRuntimeError;
 use crate::diagnostic_reporter::DiagnosticReporter;
+use crate::diagnostic_reporter::DocumentDiagnostics;
 use crate::docblock_resolution_info::create_docblock_resolution_info;
 use crate::graphql_tools::get_operation_only_program;
 use crate::graphql_tools::get_query_text;
 use crate::location::transform_relay_location_to_lsp_location_with_cache;
 use crate::lsp_runtime_error::LSPRuntimeResult;
 use crate::node_resolution_info::create_node_resolution_info;
+use crate::pull_diagnostics::supports_diagnostics_refresh;
+use crate::pull_diagnostics::supports_pull_diagnostics;
 use crate::utils::extract_executable_definitions_from_text_document;
 use crate::utils::extract_feature_from_text;
+use crate::utils::get_end_position;
 use crate::utils::get_file_group_from_uri;
 use crate::utils::get_project_name_from_file_group;
+use crate::utils::schema_source_location_key;
 
 pub type Schemas = Arc<DashMap<StringKey, Arc<SDLSchema>, FnvBuildHasher>>;
 pub type SourcePrograms = Arc<DashMap<StringKey, Program, FnvBuildHasher>>;
 pub type ProjectStatusMap = Arc<DashMap<StringKey, ProjectStatus, FnvBuildHasher>>;
 
+/// Loads the Relay config again, when the config file changes, so that the
+/// projects can be built with it without restarting the server.
+pub type ConfigLoader = Box<dyn Fn() -> relay_compiler::errors::Result<Config> + Send + Sync>;
+
 #[derive(Eq, PartialEq)]
 pub enum ProjectStatus {
     /// The project was just activated, but not yet built.
@@ -128,6 +145,30 @@ pub trait GlobalState {
 
     fn get_diagnostic_for_range(&self, uri: &Uri, range: Range) -> Option<Diagnostic>;
 
+    /// Return the current diagnostics of a document, for clients that pull
+    /// diagnostics.
+    fn get_document_diagnostics(&self, uri: &Uri) -> DocumentDiagnostics;
+
+    /// Return the current diagnostics of all documents that have any, for
+    /// clients that pull diagnostics.
+    fn get_workspace_diagnostics(&self) -> Vec<DocumentDiagnostics>;
+
+    fn get_config(&self) -> Arc<Config>;
+
+    /// Return the `graphql` literals and docblocks extracted from an open
+    /// JavaScript document.
+    fn get_synced_javascript_sources(
+        &self,
+        uri: &Uri,
+    ) -> LSPRuntimeResult<Vec<JavaScriptSourceFeature>>;
+
+    /// Return the position of the end of an open JavaScript document, where
+    /// new `graphql` literals can be appended.
+    fn get_synced_javascript_source_end(&self, uri: &Uri) -> LSPRuntimeResult<Position>;
+
+    /// Return the text of an open schema or schema extension document.
+    fn get_synced_schema_source(&self, uri: &Uri) -> LSPRuntimeResult<GraphQLSource>;
+
     /// For Relay - project_name is an human-readable identifier of a set of configurations,
     /// source files, schema extensions, etc, that are compiled together using a single GraphQL
     /// Schema. project_name typically the same as the schema name: facebook, intern, etc.
@@ -153,6 +194,14 @@ pub trait GlobalState {
 
     fn document_closed(&self, uri: &Uri) -> LSPRuntimeResult<()>;
 
+    /// Load the Relay config again and, if it changed, build the active
+    /// projects whose settings changed with it.
+    fn reload_config(&self) -> LSPRuntimeResult<()>;
+
+    /// Build the active projects of the changed schema, schema extension and
+    /// generated files again.
+    fn watched_files_changed(&self, uris: &[Uri]) -> LSPRuntimeResult<()>;
+
     /// To distinguish content, that we show to consumers
     /// we may need to know who's our current consumer.
     /// This is mostly for hover handler (where we render markup)
@@ -176,29 +225,39 @@ pub struct LSPState<
     TPerfLogger: PerfLogger + 'static,
     TSchemaDocumentation: SchemaDocumentation + 'static,
 > {
-    pub(crate) config: Arc<Config>,
+    config: RwLock<Arc<Config>>,
+    config_loader: Option<ConfigLoader>,
     pub(crate) sender: Sender<Message>,
     task_scheduler: Arc<TaskScheduler<super::Task>>,
     root_dir: PathBuf,
     extra_data_provider: Box<dyn LSPExtraDataProvider>,
-    file_categorizer: FileCategorizer,
+    file_categorizer: RwLock<FileCategorizer>,
     pub(crate) schemas: Schemas,
     schema_documentation_loader: Option<Box<dyn SchemaDocumentationLoader<TSchemaDocumentation>>>,
     pub(crate) source_programs: SourcePrograms,
     synced_javascript_sources: DashMap<Uri, Vec<JavaScriptSourceFeature>>,
+    synced_javascript_source_ends: DashMap<Uri, Position>,
     synced_schema_sources: DashMap<Uri, GraphQLSource>,
     pub(crate) perf_logger: Arc<TPerfLogger>,
     pub(crate) diagnostic_reporter: Arc<DiagnosticReporter>,
     pub(crate) notify_lsp_state_resources: Arc<Notify>,
     pub(crate) project_status: ProjectStatusMap,
+    /// Set when the config was reloaded, until the resources are initialized
+    /// again with it.
+    pub(crate) config_reloaded: AtomicBool,
+    /// Active projects with a changed schema or generated file, that need to
+    /// be built again even if the file source didn't report the change.
+    pub(crate) stale_projects: DashSet<StringKey, FnvBuildHasher>,
 }
 
 impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentation>
     LSPState<TPerfLogger, TSchemaDocumentation>
 {
     /// Private constructor
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         config: Arc<Config>,
+        config_loader: Option<ConfigLoader>,
         sender: Sender<Message>,
         task_scheduler: Arc<TaskScheduler<super::Task>>,
         perf_logger: Arc<TPerfLogger>,
@@ -206,22 +265,27 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
         schema_documentation_loader: Option<
             Box<dyn SchemaDocumentationLoader<TSchemaDocumentation>>,
         >,
+        client_capabilities: &ClientCapabilities,
     ) -> Self {
         debug!("Creating lsp_state...");
         let file_categorizer = FileCategorizer::from_config(&config);
         let root_dir = &config.root_dir.clone();
-        let diagnostic_reporter = Arc::new(DiagnosticReporter::new(
-            config.root_dir.clone(),
-            Some(sender.clone()),
-        ));
+        let mut diagnostic_reporter =
+            DiagnosticReporter::new(config.root_dir.clone(), Some(sender.clone()));
+        if supports_pull_diagnostics(client_capabilities) {
+            diagnostic_reporter
+                .enable_pull_diagnostics(supports_diagnostics_refresh(client_capabilities));
+        }
+        let diagnostic_reporter = Arc::new(diagnostic_reporter);
 
         let lsp_state = Self {
-            config,
+            config: RwLock::new(config),
+            config_loader,
             sender,
             task_scheduler,
             diagnostic_reporter,
             extra_data_provider,
-            file_categorizer,
+            file_categorizer: RwLock::new(file_categorizer),
             notify_lsp_state_resources: Arc::new(Notify::new()),
             perf_logger,
             project_status: Arc::new(DashMap::with_hasher(FnvBuildHasher::default())),
@@ -230,7 +294,10 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
             schema_documentation_loader,
             source_programs: Arc::new(DashMap::with_hasher(FnvBuildHasher::default())),
             synced_javascript_sources: Default::default(),
+            synced_javascript_source_ends: Default::default(),
             synced_schema_sources: Default::default(),
+            config_reloaded: AtomicBool::new(false),
+            stale_projects: DashSet::with_hasher(FnvBuildHasher::default()),
         };
 
         // Preload schema documentation - this will warm-up schema documentation cache in the LSP Extra Data providers
@@ -241,6 +308,15 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
         lsp_state
     }
 
+    fn get_file_group(&self, uri: &Uri) -> LSPRuntimeResult<FileGroup> {
+        get_file_group_from_uri(
+            &self.file_categorizer.read().unwrap(),
+            uri,
+            &self.root_dir,
+            &self.get_config(),
+        )
+    }
+
     fn insert_synced_js_sources(&self, uri: &Uri, sources: Vec<JavaScriptSourceFeature>) {
         self.synced_javascript_sources.insert(uri.clone(), sources);
     }
@@ -254,8 +330,8 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
             ))
         })?;
         let project_name = self.extract_project_name_from_uri(uri)?;
-        let project_config = self
-            .config
+        let config = self.get_config();
+        let project_config = config
             .projects
             .get(&ProjectName::from(project_name))
             .unwrap();
@@ -357,8 +433,8 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
             ))
         })?;
         let project_name = self.extract_project_name_from_uri(uri)?;
-        let project_config = self
-            .config
+        let config = self.get_config();
+        let project_config = config
             .projects
             .get(&ProjectName::from(project_name))
             .unwrap();
@@ -367,7 +443,7 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
             return Ok(());
         }
 
-        let source_location_key = SourceLocationKey::standalone(uri.as_str());
+        let source_location_key = schema_source_location_key(uri);
 
         let mut diagnostics = vec![];
         let text_source = schema_source.text_source();
@@ -392,7 +468,7 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
     }
 
     fn preload_documentation(&self) {
-        for project_config in self.config.enabled_projects() {
+        for project_config in self.get_config().enabled_projects() {
             self.get_schema_documentation(&project_config.name.to_string());
         }
     }
@@ -412,6 +488,7 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
 
     fn remove_synced_js_sources(&self, uri: &Uri) {
         self.synced_javascript_sources.remove(uri);
+        self.synced_javascript_source_ends.remove(uri);
         self.diagnostic_reporter
             .clear_quick_diagnostics_for_uri(uri);
     }
@@ -542,7 +619,8 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
         let project_name: ProjectName = self
             .extract_project_name_from_uri(&position.text_document.uri)?
             .into();
-        let project_config = self.config.projects.get(&project_name).unwrap();
+        let config = self.get_config();
+        let project_config = config.projects.get(&project_name).unwrap();
 
         extract_feature_from_text(
             project_config,
@@ -567,8 +645,7 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
     }
 
     fn extract_project_name_from_uri(&self, uri: &Uri) -> LSPRuntimeResult<StringKey> {
-        let file_group =
-            get_file_group_from_uri(&self.file_categorizer, uri, &self.root_dir, &self.config)?;
+        let file_group = self.get_file_group(uri)?;
 
         get_project_name_from_file_group(&file_group).map_err(|msg| {
             LSPRuntimeError::UnexpectedError(format!(
@@ -589,7 +666,8 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
         let project_name: ProjectName = self
             .extract_project_name_from_uri(text_document_uri)?
             .into();
-        let project_config = self.config.projects.get(&project_name).unwrap();
+        let config = self.get_config();
+        let project_config = config.projects.get(&project_name).unwrap();
 
         extract_executable_definitions_from_text_document(
             text_document_uri,
@@ -603,6 +681,42 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
             .get_diagnostics_for_range(uri, range)
     }
 
+    fn get_document_diagnostics(&self, uri: &Uri) -> DocumentDiagnostics {
+        self.diagnostic_reporter.get_document_diagnostics(uri)
+    }
+
+    fn get_workspace_diagnostics(&self) -> Vec<DocumentDiagnostics> {
+        self.diagnostic_reporter.get_workspace_diagnostics()
+    }
+
+    fn get_config(&self) -> Arc<Config> {
+        Arc::clone(&self.config.read().unwrap())
+    }
+
+    fn get_synced_javascript_sources(
+        &self,
+        uri: &Uri,
+    ) -> LSPRuntimeResult<Vec<JavaScriptSourceFeature>> {
+        self.synced_javascript_sources
+            .get(uri)
+            .map(|sources| sources.value().clone())
+            .ok_or(LSPRuntimeError::ExpectedError)
+    }
+
+    fn get_synced_javascript_source_end(&self, uri: &Uri) -> LSPRuntimeResult<Position> {
+        self.synced_javascript_source_ends
+            .get(uri)
+            .map(|end| *end.value())
+            .ok_or(LSPRuntimeError::ExpectedError)
+    }
+
+    fn get_synced_schema_source(&self, uri: &Uri) -> LSPRuntimeResult<GraphQLSource> {
+        self.synced_schema_sources
+            .get(uri)
+            .map(|source| source.value().clone())
+            .ok_or(LSPRuntimeError::ExpectedError)
+    }
+
     fn get_full_query_text(
         &self,
         query_text: String,
@@ -616,8 +730,8 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
         operation_name: OperationDefinitionName,
         project_name: &StringKey,
     ) -> LSPRuntimeResult<String> {
-        let project_config = self
-            .config
+        let config = self.get_config();
+        let project_config = config
             .enabled_projects()
             .find(|project_config| project_config.name == (*project_name).into())
             .ok_or_else(|| {
@@ -641,10 +755,8 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
             Default::default(),
             Arc::clone(&self.perf_logger),
             None,
-            self.config.custom_transforms.as_ref(),
-            self.config
-                .transferrable_refetchable_query_directives
-                .clone(),
+            config.custom_transforms.as_ref(),
+            config.transferrable_refetchable_query_directives.clone(),
         )
         .map_err(|_| LSPRuntimeError::ExpectedError)?;
 
@@ -663,8 +775,7 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
     }
 
     fn document_opened(&self, uri: &Uri, text: &str) -> LSPRuntimeResult<()> {
-        let file_group =
-            get_file_group_from_uri(&self.file_categorizer, uri, &self.root_dir, &self.config)?;
+        let file_group = self.get_file_group(uri)?;
         let project_name = get_project_name_from_file_group(&file_group).map_err(|msg| {
             LSPRuntimeError::UnexpectedError(format!(
                 "Could not determine project name for \"{}\": {msg}",
@@ -692,6 +803,8 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
 
                 if !embedded_sources.is_empty() {
                     self.initialize_lsp_state_resources(project_name);
+                    self.synced_javascript_source_ends
+                        .insert(uri.clone(), get_end_position(text));
                     self.process_synced_js_sources(uri, embedded_sources);
                 }
 
@@ -702,8 +815,7 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
     }
 
     fn document_changed(&self, uri: &Uri, text: &str) -> LSPRuntimeResult<()> {
-        let file_group =
-            get_file_group_from_uri(&self.file_categorizer, uri, &self.root_dir, &self.config)?;
+        let file_group = self.get_file_group(uri)?;
 
         match file_group {
             FileGroup::Schema { project_set: _ } | FileGroup::Extension { project_set: _ } => {
@@ -721,6 +833,8 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
                 if embedded_sources.is_empty() {
                     self.remove_synced_js_sources(uri);
                 } else {
+                    self.synced_javascript_source_ends
+                        .insert(uri.clone(), get_end_position(text));
                     self.process_synced_js_sources(uri, embedded_sources);
                 }
 
@@ -736,6 +850,104 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
         Ok(())
     }
 
+    fn reload_config(&self) -> LSPRuntimeResult<()> {
+        // Without a loader, the config can only be changed by a restart
+        let config_loader = self
+            .config_loader
+            .as_ref()
+            .ok_or(LSPRuntimeError::ExpectedError)?;
+        let config = config_loader().map_err(|error| {
+            LSPRuntimeError::UnexpectedError(format!("Unable to reload the Relay config: {error}"))
+        })?;
+        if config.root_dir != self.root_dir {
+            return Err(LSPRuntimeError::UnexpectedError(format!(
+                "The root directory of the Relay config changed to {:?}, restart the server to use it.",
+                config.root_dir
+            )));
+        }
+        let previous_config = self.get_config();
+        if config.saved_state_version == previous_config.saved_state_version {
+            debug!("The Relay config did not change");
+            return Ok(());
+        }
+        debug!("Reloaded the Relay config");
+
+        let file_categorizer = FileCategorizer::from_config(&config);
+        *self.config.write().unwrap() = Arc::new(config);
+        *self.file_categorizer.write().unwrap() = file_categorizer;
+        let config = self.get_config();
+
+        // Drop the resources of the projects that were removed or disabled
+        let is_enabled = |project_name: &StringKey| {
+            config
+                .enabled_projects()
+                .any(|project_config| project_config.name == (*project_name).into())
+        };
+        self.project_status.retain(|project_name, project_status| {
+            if is_enabled(project_name) {
+                return true;
+            }
+            if let ProjectStatus::Activated { when_completed } = project_status {
+                when_completed.set(()).ok();
+            }
+            false
+        });
+        self.schemas
+            .retain(|project_name, _| is_enabled(project_name));
+        self.source_programs
+            .retain(|project_name, _| is_enabled(project_name));
+        self.stale_projects
+            .retain(|project_name| is_enabled(project_name));
+
+        // Projects that were added are built once they are activated
+        let sources_changed = config.sources != previous_config.sources
+            || config.excludes != previous_config.excludes
+            || config.generated_sources != previous_config.generated_sources;
+        for project_status in self.project_status.iter() {
+            let project_name = ProjectName::from(*project_status.key());
+            if sources_changed
+                || project_config_changed(&previous_config, &config, project_name)
+                || config.projects[&project_name]
+                    .base
+                    .is_some_and(|base| project_config_changed(&previous_config, &config, base))
+            {
+                self.stale_projects.insert(project_name.into());
+            }
+        }
+        self.config_reloaded.store(true, Ordering::SeqCst);
+        self.notify_lsp_state_resources.notify_one();
+
+        Ok(())
+    }
+
+    fn watched_files_changed(&self, uris: &[Uri]) -> LSPRuntimeResult<()> {
+        let mut has_stale_projects = false;
+        for uri in uris {
+            let project_names: Vec<ProjectName> = match self.get_file_group(uri) {
+                Ok(
+                    FileGroup::Schema { project_set }
+                    | FileGroup::Extension { project_set }
+                    | FileGroup::CompactSchema { project_set },
+                ) => project_set.into_iter().collect(),
+                Ok(FileGroup::Generated { project_name }) => vec![project_name],
+                _ => continue,
+            };
+            for project_name in project_names {
+                let project_name: StringKey = project_name.into();
+                // Inactive projects are built once they are activated
+                if self.project_status.contains_key(&project_name) {
+                    self.stale_projects.insert(project_name);
+                    has_stale_projects = true;
+                }
+            }
+        }
+        if has_stale_projects {
+            self.notify_lsp_state_resources.notify_one();
+        }
+
+        Ok(())
+    }
+
     fn get_content_consumer_type(&self) -> ContentConsumerType {
         ContentConsumerType::Relay
     }
@@ -757,18 +969,45 @@ pub fn build_ir_for_lsp(
     schema: &SDLSchema,
     definitions: &[ExecutableDefinition],
 ) -> DiagnosticsResult<Vec<graphql_ir::ExecutableDefinition>> {
-    build_ir_with_extra_features(
-        schema,
-        definitions,
-        &BuilderOptions {
-            allow_undefined_fragment_spreads: true,
-            allow_non_overlapping_abstract_spreads: false,
-            fragment_variables_semantic: FragmentVariablesSemantic::PassedValue,
-            relay_mode: Some(RelayMode),
-            default_anonymous_operation_name: None,
-            allow_custom_scalar_literals: true, // for compatibility
-        },
-    )
+    build_ir_with_extra_features(schema, definitions, &lsp_builder_options())
+}
+
+/// Build the definitions with the signatures of fragments defined in other
+/// documents, so that the arguments of their spreads are built too.
+pub fn build_ir_for_lsp_with_signatures(
+    schema: &SDLSchema,
+    definitions: &[ExecutableDefinition],
+    signatures: &FragmentSignatures,
+) -> DiagnosticsResult<Vec<graphql_ir::ExecutableDefinition>> {
+    build_ir_with_external_signatures(schema, definitions, signatures, &lsp_builder_options())
+}
+
+/// Whether the settings of a project differ between the configs, or it was
+/// added. They are compared by their debug representation: a spurious difference
+/// only costs a rebuild.
+fn project_config_changed(
+    previous_config: &Config,
+    config: &Config,
+    project_name: ProjectName,
+) -> bool {
+    match (
+        previous_config.projects.get(&project_name),
+        config.projects.get(&project_name),
+    ) {
+        (Some(previous), Some(current)) => format!("{previous:?}") != format!("{current:?}"),
+        _ => true,
+    }
+}
+
+fn lsp_builder_options() -> BuilderOptions {
+    BuilderOptions {
+        allow_undefined_fragment_spreads: true,
+        allow_non_overlapping_abstract_spreads: false,
+        fragment_variables_semantic: FragmentVariablesSemantic::PassedValue,
+        relay_mode: Some(RelayMode),
+        default_anonymous_operation_name: None,
+        allow_custom_scalar_literals: true, // for compatibility
+    }
 }
 
 #[derive(Debug)]
@@ -5,8 +5,15 @@
  * LICENSE file in the root directory of this source tree.
  */
 
+use std::fs;
+use std::future;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process;
 use std::sync::Arc;
 use std::sync::RwLock;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
 
 use common::PerfLogEvent;
 use common::PerfLogger;
@@ -16,6 +23,7 @@ use graphql_ir::FragmentDefinitionNameSet;
 use graphql_watchman::WatchmanFileSourceSubscriptionNextChange;
 use log::debug;
 use log::error;
+use log::warn;
 use rayon::iter::ParallelIterator;
 use relay_compiler::ArtifactSourceKey;
 use relay_compiler::BuildProjectFailure;
@@ -35,6 +43,7 @@ use relay_compiler::build_raw_program;
 use relay_compiler::build_schema;
 use relay_compiler::compiler_state::CompilerState;
 use relay_compiler::config::Config;
+use relay_compiler::config::FileSourceKind;
 use relay_compiler::config::ProjectConfig;
 use relay_compiler::errors::BuildProjectError;
 use relay_compiler::errors::Error;
@@ -45,9 +54,12 @@ use schema_diff::check::SchemaChangeSafety;
 use schema_documentation::SchemaDocumentation;
 use tokio::task;
 use tokio::task::JoinHandle;
+use tokio::time;
+use tokio::time::Instant;
 
 use super::lsp_state::ProjectStatus;
 use super::lsp_state::Task;
+use crate::GlobalState;
 use crate::LSPState;
 use crate::status_updater::set_ready_status;
 use crate::status_updater::update_in_progress_status;
@@ -82,8 +94,17 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
     async fn internal_watch(&self) {
         // avoid dead loop when watchman has an error
         let mut error_count = 0;
+        // The saved state is only restored when the server starts, later
+        // iterations query the file source again.
+        let mut should_restore_saved_state = true;
+        let mut state_saver = StateSaver::default();
         'outer: loop {
             debug!("Initializing resources for LSP server");
+            // The state of the previous iteration is not saved anymore.
+            state_saver.cancel();
+            self.lsp_state
+                .config_reloaded
+                .store(false, Ordering::SeqCst);
 
             update_in_progress_status(
                 "Relay: watchman...",
@@ -96,8 +117,12 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
                 .create_event("lsp_state_initialize_resources");
             let timer = setup_event.start("lsp_state_initialize_resources_time");
 
-            let file_source = match FileSource::connect(&self.lsp_state.config, &setup_event).await
-            {
+            let config = self.lsp_state.get_config();
+            let is_restoring_saved_state =
+                should_restore_saved_state && restore_saved_state(&config);
+            should_restore_saved_state = false;
+
+            let file_source = match FileSource::connect(&config, &setup_event).await {
                 Ok(f) => f,
                 Err(error) => {
                     self.log_errors("watch_build_error", &error);
@@ -113,6 +138,12 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
                 .await
             {
                 Ok(f) => f,
+                Err(error) if is_restoring_saved_state => {
+                    warn!(
+                        "Unable to restore the saved state, falling back to a full build: {error}"
+                    );
+                    continue;
+                }
                 Err(error) => {
                     self.log_errors("watch_build_error", &error);
                     error_count += 1;
@@ -122,6 +153,14 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
                     continue;
                 }
             };
+            if is_restoring_saved_state
+                && compiler_state.saved_state_version != config.saved_state_version
+            {
+                warn!(
+                    "The saved state was created with another config, falling back to a full build"
+                );
+                continue;
+            }
 
             let pending_file_source_changes =
                 Arc::clone(&compiler_state.pending_file_source_changes);
@@ -147,8 +186,9 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
                 .clear_regular_diagnostics();
 
             // Run initial build, before entering the watch changes loop
-            if let Err(error) = self.build_projects(&mut compiler_state, &setup_event) {
-                self.publish_errors(&error, "lsp_state_error");
+            match self.build_projects(&mut compiler_state, &setup_event) {
+                Ok(()) => state_saver.state_changed(&config),
+                Err(error) => self.publish_errors(&error, "lsp_state_error"),
             }
             set_ready_status(&self.lsp_state.sender);
 
@@ -161,8 +201,16 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
             // Here we will wait for changes from watchman
             'inner: loop {
                 // Wait for a notify from watchman updates, or when a Relay file
-                // from an unactivated project is opened in VSCode
-                self.lsp_state.notify_lsp_state_resources.notified().await;
+                // from an unactivated project is opened in VSCode. The state is
+                // saved in between, once the builds settled.
+                let save_deadline = state_saver.deadline;
+                tokio::select! {
+                    _ = self.lsp_state.notify_lsp_state_resources.notified() => {}
+                    _ = sleep_until_deadline(save_deadline) => {
+                        state_saver.save(&compiler_state, &self.lsp_state.get_config());
+                        continue 'inner;
+                    }
+                }
 
                 // Source control update started, we can ignore all pending changes, and wait for it to complete,
                 // we may change the status bar to `Source Control Update...`
@@ -182,14 +230,23 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
                     continue 'outer;
                 }
 
+                // The config changed, the file source needs to be queried again with it
+                if self.lsp_state.config_reloaded.load(Ordering::SeqCst) {
+                    debug!("The Relay config was reloaded!");
+                    subscription_handle.abort();
+                    continue 'outer;
+                }
+
                 let log_event = self
                     .lsp_state
                     .perf_logger
                     .create_event("lsp_state_watchman_event");
                 let log_time = log_event.start("lsp_state_watchman_event_time");
 
-                if let Err(error) = self.incremental_build(&mut compiler_state, &log_event) {
-                    self.publish_errors(&error, "lsp_state_user_error");
+                match self.incremental_build(&mut compiler_state, &log_event) {
+                    Ok(true) => state_saver.state_changed(&self.lsp_state.get_config()),
+                    Ok(false) => {}
+                    Err(error) => self.publish_errors(&error, "lsp_state_user_error"),
                 }
                 set_ready_status(&self.lsp_state.sender);
 
@@ -199,19 +256,21 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
         }
     }
 
+    /// Returns whether the projects were rebuilt.
     fn incremental_build(
         &self,
         compiler_state: &mut CompilerState,
         log_event: &impl PerfLogEvent,
-    ) -> Result<(), Error> {
+    ) -> Result<bool, Error> {
         let has_new_changes = compiler_state.merge_file_source_changes(
-            &self.lsp_state.config,
+            &self.lsp_state.get_config(),
             self.lsp_state.perf_logger.as_ref(),
             false,
         )?;
 
-        // Rebuild if there are pending files or if a new project is activated
+        // Rebuild if there are pending files, stale projects, or if a new project is activated
         if has_new_changes
+            || !self.lsp_state.stale_projects.is_empty()
             || self
                 .lsp_state
                 .project_status
@@ -231,9 +290,10 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
             );
 
             self.build_projects(compiler_state, log_event)?;
+            return Ok(true);
         }
 
-        Ok(())
+        Ok(false)
     }
 
     fn build_projects(
@@ -241,11 +301,12 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
         compiler_state: &mut CompilerState,
         log_event: &impl PerfLogEvent,
     ) -> Result<(), Error> {
+        let config = self.lsp_state.get_config();
         let graphql_asts = log_event.time("parse_sources_time", || {
             GraphQLAsts::from_graphql_sources_map(
                 &compiler_state.graphql_sources,
-                &compiler_state.get_dirty_artifact_sources(&self.lsp_state.config),
-                &self.lsp_state.config,
+                &compiler_state.get_dirty_artifact_sources(&config),
+                &config,
             )
         })?;
 
@@ -255,9 +316,7 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
         }
 
         let timer = log_event.start("build_lsp_projects");
-        let build_results: Vec<_> = self
-            .lsp_state
-            .config
+        let build_results: Vec<_> = config
             .par_enabled_projects()
             .filter(|project_config| {
                 // Filter inactive projects
@@ -268,6 +327,14 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
                 {
                     return false;
                 }
+                if self
+                    .lsp_state
+                    .stale_projects
+                    .remove(&project_config.name.into())
+                    .is_some()
+                {
+                    return true;
+                }
                 // When the source programs is empty, we need to compile all source programs once
                 if !self
                     .lsp_state
@@ -284,12 +351,7 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
                 compiler_state.project_has_pending_changes(project_config.name)
             })
             .map(|project_config| {
-                self.build_project(
-                    &self.lsp_state.config,
-                    project_config,
-                    compiler_state,
-                    &graphql_asts,
-                )
+                self.build_project(&config, project_config, compiler_state, &graphql_asts)
             })
             .collect();
         log_event.stop(timer);
@@ -357,6 +419,7 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
         self.lsp_state.schedule_task(Task::SyncedDocuments);
 
         self.build_programs(
+            config,
             project_config,
             project_asts,
             base_fragment_names,
@@ -424,6 +487,7 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
     #[allow(clippy::too_many_arguments)]
     fn build_programs(
         &self,
+        config: &Config,
         project_config: &ProjectConfig,
         project_asts: ProjectAsts,
         base_fragment_names: FragmentDefinitionNameSet,
@@ -483,7 +547,7 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
                 }
             }
         };
-        if !self.lsp_state.config.has_schema_change_incremental_build {
+        if !config.has_schema_change_incremental_build {
             // Killswitch here to bail out of schema based incremental builds
             build_mode = if let BuildMode::IncrementalWithSchemaChanges(_) = build_mode {
                 BuildMode::Full
@@ -509,7 +573,7 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
             },
         );
 
-        let (base_program, _) =
+        let (base_program, _, fragment_spread_locations) =
             build_raw_program(project_config, project_asts, schema, log_event, build_mode)?;
 
         if compiler_state.should_cancel_current_build() {
@@ -543,11 +607,14 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
             }
         }
 
-        // Call validation rules that go beyond type checking.
-        validate_program(
-            &self.lsp_state.config,
+        // Call validation rules that go beyond type checking. Non-blocking
+        // diagnostics, such as the ones of lint rules, are reported together
+        // with the ones of the reader validations.
+        let validation_diagnostics = validate_program(
+            config,
             project_config,
             &base_program,
+            &fragment_spread_locations,
             log_event,
         )
         .map_err(|diagnostics| {
@@ -563,11 +630,8 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
             Arc::new(base_fragment_names),
             Arc::clone(&self.lsp_state.perf_logger),
             log_event,
-            self.lsp_state.config.custom_transforms.as_ref(),
-            self.lsp_state
-                .config
-                .transferrable_refetchable_query_directives
-                .clone(),
+            config.custom_transforms.as_ref(),
+            config.transferrable_refetchable_query_directives.clone(),
         )
         .map_err(|diagnostics| {
             BuildProjectFailure::Error(BuildProjectError::ValidationErrors {
@@ -577,7 +641,7 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
         })?;
 
         match validate_reader_program(
-            &self.lsp_state.config,
+            config,
             project_config,
             &transformed_programs.reader,
             log_event,
@@ -585,7 +649,10 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
             // Non-blocking validation errors
             Ok(diagnostics) => Err(BuildProjectFailure::Error(
                 BuildProjectError::ValidationErrors {
-                    errors: diagnostics,
+                    errors: validation_diagnostics
+                        .into_iter()
+                        .chain(diagnostics)
+                        .collect(),
                     project_name: project_config.name,
                 },
             )),
@@ -665,3 +732,188 @@ impl<TPerfLogger: PerfLogger + 'static, TSchemaDocumentation: SchemaDocumentatio
         self.log_errors(log_event_name, error)
     }
 }
+
+/// Sets up the file source to restore the compiler state from the saved state
+/// of the language server, if there is one. The file source then only queries
+/// the files that changed since the clock of the saved state.
+fn restore_saved_state(config: &Config) -> bool {
+    let Some(saved_state_file) = &config.lsp_saved_state_file else {
+        return false;
+    };
+    // Only the watchman file source can query the changes since the clock of
+    // the saved state.
+    if !matches!(config.file_source_config, FileSourceKind::Watchman) || !saved_state_file.exists()
+    {
+        return false;
+    }
+    debug!("Restoring the saved state from {saved_state_file:?}");
+    *config.load_saved_state_file.lock().unwrap() = Some(saved_state_file.clone());
+    true
+}
+
+/// The builds of the language server follow each other while files are
+/// edited, so the state is only saved once no build happened for this long.
+const SAVE_STATE_DEBOUNCE: Duration = Duration::from_secs(30);
+
+/// Saves the compiler state for the next start of the language server, after
+/// the successful builds that changed it. The state is written to the file on
+/// a blocking task, off the loop keeping the resources up-to-date.
+#[derive(Default)]
+struct StateSaver {
+    deadline: Option<Instant>,
+    pending_write: Option<JoinHandle<()>>,
+}
+
+impl StateSaver {
+    fn state_changed(&mut self, config: &Config) {
+        if config.lsp_saved_state_file.is_some() {
+            self.deadline = Some(Instant::now() + SAVE_STATE_DEBOUNCE);
+        }
+    }
+
+    fn cancel(&mut self) {
+        self.deadline = None;
+    }
+
+    fn save(&mut self, compiler_state: &CompilerState, config: &Config) {
+        let Some(saved_state_file) = &config.lsp_saved_state_file else {
+            self.deadline = None;
+            return;
+        };
+        // One write at a time, so that an older state never replaces a newer one.
+        if let Some(pending_write) = &self.pending_write
+            && !pending_write.is_finished()
+        {
+            self.deadline = Some(Instant::now() + SAVE_STATE_DEBOUNCE);
+            return;
+        }
+        self.deadline = None;
+
+        match compiler_state.serialize_to_bytes() {
+            Ok(serialized) => {
+                let saved_state_file = saved_state_file.clone();
+                self.pending_write = Some(task::spawn_blocking(move || {
+                    write_saved_state(&serialized, &saved_state_file)
+                }));
+            }
+            Err(error) => warn!("Unable to save the state to {saved_state_file:?}: {error}"),
+        }
+    }
+}
+
+async fn sleep_until_deadline(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => time::sleep_until(deadline).await,
+        None => future::pending().await,
+    }
+}
+
+/// The state is written to a file of this process next to the saved state
+/// file first, so that a server starting at the same time never reads a
+/// partially written file.
+fn write_saved_state(serialized: &[u8], saved_state_file: &Path) {
+    let mut temporary_file = saved_state_file.as_os_str().to_owned();
+    temporary_file.push(format!(".{}.tmp", process::id()));
+    let temporary_file = PathBuf::from(temporary_file);
+
+    let result = CompilerState::write_serialized_to_file(serialized, &temporary_file)
+        .map_err(|error| error.to_string())
+        .and_then(|_| {
+            fs::rename(&temporary_file, saved_state_file).map_err(|error| error.to_string())
+        });
+    if let Err(error) = result {
+        fs::remove_file(&temporary_file).ok();
+        warn!("Unable to save the state to {saved_state_file:?}: {error}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_config(saved_state_file: &Path) -> Config {
+        let mut config = Config::from_string_for_test(
+            r#"
+                {
+                    "sources": {
+                        "src": "test"
+                    },
+                    "projects": {
+                        "test": {
+                            "schema": "graphql/schema.graphql",
+                            "language": "flow"
+                        }
+                    }
+                }
+            "#,
+        )
+        .unwrap();
+        config.file_source_config = FileSourceKind::Watchman;
+        config.lsp_saved_state_file = Some(saved_state_file.to_path_buf());
+        config
+    }
+
+    #[tokio::test]
+    async fn test_saved_state_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let saved_state_file = dir.path().join("lsp_saved_state");
+        let config = create_test_config(&saved_state_file);
+        let compiler_state = CompilerState {
+            saved_state_version: config.saved_state_version.clone(),
+            ..Default::default()
+        };
+
+        let mut state_saver = StateSaver::default();
+        state_saver.state_changed(&config);
+        assert!(state_saver.deadline.is_some());
+        state_saver.save(&compiler_state, &config);
+        assert!(state_saver.deadline.is_none());
+        state_saver.pending_write.take().unwrap().await.unwrap();
+
+        // The temporary file was renamed to the saved state file.
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        assert!(restore_saved_state(&config));
+        assert_eq!(
+            *config.load_saved_state_file.lock().unwrap(),
+            Some(saved_state_file.clone())
+        );
+        let restored_state = CompilerState::deserialize_from_file(&saved_state_file).unwrap();
+        assert_eq!(
+            restored_state.saved_state_version,
+            config.saved_state_version
+        );
+    }
+
+    #[test]
+    fn test_saved_state_is_not_restored_without_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = create_test_config(&dir.path().join("lsp_saved_state"));
+
+        assert!(!restore_saved_state(&config));
+        assert!(config.load_saved_state_file.lock().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_saved_state_is_only_restored_with_watchman() {
+        let dir = tempfile::tempdir().unwrap();
+        let saved_state_file = dir.path().join("lsp_saved_state");
+        fs::write(&saved_state_file, "").unwrap();
+        let mut config = create_test_config(&saved_state_file);
+        config.file_source_config = FileSourceKind::WalkDir;
+
+        assert!(!restore_saved_state(&config));
+        assert!(config.load_saved_state_file.lock().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_state_is_not_saved_without_saved_state_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = create_test_config(&dir.path().join("lsp_saved_state"));
+        config.lsp_saved_state_file = None;
+
+        let mut state_saver = StateSaver::default();
+        state_saver.state_changed(&config);
+        assert!(state_saver.deadline.is_none());
+    }
+}
@@ -0,0 +1,241 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Utilities for providing the signature help language feature
+
+use common::Span;
+use graphql_ir::FragmentDefinitionName;
+use graphql_ir::Program;
+use graphql_ir::Value;
+use graphql_syntax::ExecutableDocument;
+use graphql_text_printer::PrinterOptions;
+use graphql_text_printer::print_value;
+use intern::Lookup;
+use intern::string_key::StringKey;
+use lsp_types::Documentation;
+use lsp_types::MarkupContent;
+use lsp_types::MarkupKind;
+use lsp_types::ParameterInformation;
+use lsp_types::ParameterLabel;
+use lsp_types::SignatureHelp;
+use lsp_types::SignatureHelpOptions;
+use lsp_types::SignatureInformation;
+use lsp_types::WorkDoneProgressOptions;
+use lsp_types::request::Request;
+use lsp_types::request::SignatureHelpRequest;
+use schema::SDLSchema;
+use schema::Schema;
+
+use crate::SchemaDocumentation;
+use crate::completion::ArgumentKind;
+use crate::completion::resolve_argument_list_at_position;
+use crate::lsp_runtime_error::LSPRuntimeResult;
+use crate::server::GlobalState;
+
+/// The signature help capability advertised during initialization.
+pub fn signature_help_capability() -> SignatureHelpOptions {
+    SignatureHelpOptions {
+        trigger_characters: Some(vec!["(".into(), ",".into()]),
+        retrigger_characters: Some(vec![":".into()]),
+        work_done_progress_options: WorkDoneProgressOptions {
+            work_done_progress: None,
+        },
+    }
+}
+
+/// Resolve a [`SignatureHelpRequest`] to the arguments of the field, directive
+/// or fragment spread that the cursor is in.
+pub fn on_signature_help(
+    state: &impl GlobalState,
+    params: <SignatureHelpRequest as Request>::Params,
+) -> LSPRuntimeResult<<SignatureHelpRequest as Request>::Result> {
+    let (document, position_span) =
+        state.extract_executable_document_from_text(&params.text_document_position_params, 0)?;
+    let project_name = state
+        .extract_project_name_from_uri(&params.text_document_position_params.text_document.uri)?;
+    let schema = state.get_schema(&project_name)?;
+    // Only the arguments of fragments need the program, the signatures of
+    // fields and directives are available before the first build.
+    let program = state.get_program(&project_name).ok();
+
+    Ok(resolve_signature_help(
+        document,
+        position_span,
+        project_name,
+        &schema,
+        state.get_schema_documentation(project_name.lookup()),
+        program.as_ref(),
+    ))
+}
+
+fn resolve_signature_help(
+    document: ExecutableDocument,
+    position_span: Span,
+    project_name: StringKey,
+    schema: &SDLSchema,
+    schema_documentation: impl SchemaDocumentation,
+    program: Option<&Program>,
+) -> Option<SignatureHelp> {
+    let argument_list = resolve_argument_list_at_position(document, position_span, project_name)?;
+
+    let mut signature = SignatureBuilder::default();
+    match argument_list.kind {
+        ArgumentKind::Field => {
+            let (parent_type, field) = argument_list.type_path.resolve_current_field(schema)?;
+            let parent_type_name = schema.get_type_name(parent_type).lookup();
+            let field_name = field.name.item.lookup();
+            signature.label.push_str(field_name);
+            signature.label.push('(');
+            for argument in field.arguments.iter() {
+                let argument_name = argument.name.item.0;
+                signature.push_parameter(
+                    argument_name,
+                    &schema.get_type_string(&argument.type_),
+                    argument
+                        .default_value
+                        .as_ref()
+                        .map(|value| value.to_string()),
+                    schema_documentation
+                        .get_field_argument_description(
+                            parent_type_name,
+                            field_name,
+                            argument_name.lookup(),
+                        )
+                        .map(|description| description.to_string())
+                        .or_else(|| {
+                            argument
+                                .description
+                                .map(|description| description.to_string())
+                        }),
+                );
+            }
+            signature.label.push_str("): ");
+            signature
+                .label
+                .push_str(&schema.get_type_string(&field.type_));
+            signature.documentation = schema_documentation
+                .get_field_description(parent_type_name, field_name)
+                .map(|description| description.to_string())
+                .or_else(|| field.description.map(|description| description.to_string()));
+        }
+        ArgumentKind::Directive(directive_name) => {
+            let directive = schema.get_directive(directive_name)?;
+            signature.label.push_str(&format!("@{}(", directive_name));
+            for argument in directive.arguments.iter() {
+                signature.push_parameter(
+                    argument.name.item.0,
+                    &schema.get_type_string(&argument.type_),
+                    argument
+                        .default_value
+                        .as_ref()
+                        .map(|value| value.to_string()),
+                    argument
+                        .description
+                        .map(|description| description.to_string()),
+                );
+            }
+            signature.label.push(')');
+            signature.documentation = directive
+                .description
+                .map(|description| description.to_string());
+        }
+        ArgumentKind::ArgumentsDirective(fragment_spread_name) => {
+            let fragment = program?.fragment(FragmentDefinitionName(fragment_spread_name))?;
+            signature
+                .label
+                .push_str(&format!("...{} @arguments(", fragment_spread_name));
+            for variable_definition in &fragment.variable_definitions {
+                signature.push_parameter(
+                    variable_definition.name.item.0,
+                    &schema.get_type_string(&variable_definition.type_),
+                    variable_definition.default_value.as_ref().map(|value| {
+                        print_value(
+                            schema,
+                            &Value::Constant(value.item.clone()),
+                            PrinterOptions::default(),
+                        )
+                    }),
+                    None,
+                );
+            }
+            signature.label.push(')');
+            signature.documentation = Some(format!(
+                "Arguments defined with `@argumentDefinitions` on fragment `{fragment_spread_name}`"
+            ));
+        }
+    }
+
+    let active_parameter = argument_list.argument_name.and_then(|argument_name| {
+        signature
+            .parameter_names
+            .iter()
+            .position(|name| *name == argument_name)
+            .map(|index| index as u32)
+    });
+
+    Some(SignatureHelp {
+        signatures: vec![signature.build(active_parameter)],
+        active_signature: Some(0),
+        active_parameter,
+    })
+}
+
+/// Accumulates the label of a signature, keeping track of the offsets of each
+/// parameter within the label.
+#[derive(Default)]
+struct SignatureBuilder {
+    label: String,
+    documentation: Option<String>,
+    parameter_names: Vec<StringKey>,
+    parameters: Vec<ParameterInformation>,
+}
+
+impl SignatureBuilder {
+    fn push_parameter(
+        &mut self,
+        name: StringKey,
+        type_string: &str,
+        default_value: Option<String>,
+        documentation: Option<String>,
+    ) {
+        if !self.parameters.is_empty() {
+            self.label.push_str(", ");
+        }
+        // Label offsets count UTF-16 code units, like LSP positions
+        let start = self.label.encode_utf16().count() as u32;
+        self.label.push_str(&format!("{name}: {type_string}"));
+        if let Some(default_value) = default_value {
+            self.label.push_str(&format!(" = {default_value}"));
+        }
+        let end = self.label.encode_utf16().count() as u32;
+
+        self.parameter_names.push(name);
+        self.parameters.push(ParameterInformation {
+            label: ParameterLabel::LabelOffsets([start, end]),
+            documentation: documentation.map(markdown_documentation),
+        });
+    }
+
+    fn build(self, active_parameter: Option<u32>) -> SignatureInformation {
+        SignatureInformation {
+            label: self.label,
+            documentation: self.documentation.map(markdown_documentation),
+            parameters: Some(self.parameters),
+            active_parameter,
+        }
+    }
+}
+
+fn markdown_documentation(value: String) -> Documentation {
+    Documentation::MarkupContent(MarkupContent {
+        kind: MarkupKind::Markdown,
+        value,
+    })
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,243 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::sync::Arc;
+
+use common::SourceLocationKey;
+use common::Span;
+use graphql_ir::Program;
+use graphql_ir::build;
+use graphql_syntax::parse_executable;
+use graphql_syntax::parse_executable_with_error_recovery;
+use intern::string_key::Intern;
+use lsp_types::ParameterLabel;
+use lsp_types::SignatureHelp;
+use relay_test_schema::get_test_schema;
+
+use super::resolve_signature_help;
+
+fn parse_and_resolve_signature_help(
+    source: &str,
+    program: Option<Program>,
+) -> Option<SignatureHelp> {
+    let pos = source.find('|').unwrap() - 1;
+    let next_source = source.replace('|', "");
+    let document = parse_executable_with_error_recovery(
+        &next_source,
+        SourceLocationKey::standalone("/test/file"),
+    )
+    .item;
+
+    let position_span = Span {
+        start: pos as u32,
+        end: pos as u32,
+    };
+    let test_schema = get_test_schema();
+
+    resolve_signature_help(
+        document,
+        position_span,
+        "test_project".intern(),
+        &Arc::clone(&test_schema),
+        Arc::clone(&test_schema),
+        program.as_ref(),
+    )
+}
+
+fn build_test_program(source: &str) -> Program {
+    let document = parse_executable(source, SourceLocationKey::Generated).unwrap();
+    let ir = build(&get_test_schema(), &document.definitions).unwrap();
+    Program::from_definitions(get_test_schema(), ir)
+}
+
+/// Returns the label of the signature, and the labels of its parameters.
+fn get_labels(signature_help: &SignatureHelp) -> (&str, Vec<&str>) {
+    let signature = &signature_help.signatures[0];
+    let label = signature.label.as_str();
+    let parameters = signature
+        .parameters
+        .iter()
+        .flatten()
+        .map(|parameter| match &parameter.label {
+            ParameterLabel::LabelOffsets([start, end]) => {
+                &label[byte_offset(label, *start)..byte_offset(label, *end)]
+            }
+            ParameterLabel::Simple(label) => label.as_str(),
+        })
+        .collect();
+    (label, parameters)
+}
+
+/// Converts an offset in UTF-16 code units to a byte offset in `text`.
+fn byte_offset(text: &str, utf16_offset: u32) -> usize {
+    let mut utf16_len = 0;
+    for (index, chr) in text.char_indices() {
+        if utf16_len == utf16_offset as usize {
+            return index;
+        }
+        utf16_len += chr.len_utf16();
+    }
+    text.len()
+}
+
+#[test]
+fn field_arguments() {
+    let signature_help = parse_and_resolve_signature_help(
+        r#"
+            fragment Test on User {
+                profilePicture(|) {
+                    uri
+                }
+            }
+        "#,
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        get_labels(&signature_help),
+        (
+            "profilePicture(size: [Int], preset: PhotoSize): Image",
+            vec!["size: [Int]", "preset: PhotoSize"]
+        )
+    );
+    assert_eq!(signature_help.active_parameter, None);
+}
+
+#[test]
+fn field_argument_value() {
+    let signature_help = parse_and_resolve_signature_help(
+        r#"
+            fragment Test on User {
+                profilePicture(size: 32, preset: |) {
+                    uri
+                }
+            }
+        "#,
+        None,
+    )
+    .unwrap();
+    assert_eq!(signature_help.active_parameter, Some(1));
+}
+
+#[test]
+fn directive_arguments() {
+    let signature_help = parse_and_resolve_signature_help(
+        r#"
+            fragment Test on User {
+                name @stream(label: "name", |)
+            }
+        "#,
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        get_labels(&signature_help),
+        (
+            "@stream(label: String, initialCount: Int!, if: Boolean = true, useCustomizedBatch: Boolean = false)",
+            vec![
+                "label: String",
+                "initialCount: Int!",
+                "if: Boolean = true",
+                "useCustomizedBatch: Boolean = false"
+            ]
+        )
+    );
+}
+
+#[test]
+fn fragment_spread_arguments() {
+    let signature_help = parse_and_resolve_signature_help(
+        r#"
+            fragment Test on User {
+                ...Picture_user @arguments(pictureSize: |)
+            }
+        "#,
+        Some(build_test_program(
+            r#"
+            fragment Picture_user on User
+                @argumentDefinitions(
+                    pictureSize: {type: "[Int]", defaultValue: [32]},
+                    preset: {type: "PhotoSize"}
+                ) {
+                profilePicture(size: $pictureSize, preset: $preset) {
+                    uri
+                }
+            }
+        "#,
+        )),
+    )
+    .unwrap();
+    assert_eq!(
+        get_labels(&signature_help),
+        (
+            "...Picture_user @arguments(pictureSize: [Int] = [32], preset: PhotoSize)",
+            vec!["pictureSize: [Int] = [32]", "preset: PhotoSize"]
+        )
+    );
+    assert_eq!(signature_help.active_parameter, Some(0));
+}
+
+#[test]
+fn fragment_spread_argument_offsets_are_utf16() {
+    let signature_help = parse_and_resolve_signature_help(
+        r#"
+            fragment Test on User {
+                ...Greeting_user @arguments(size: |)
+            }
+        "#,
+        Some(build_test_program(
+            r#"
+            fragment Greeting_user on User
+                @argumentDefinitions(
+                    greeting: {type: "String", defaultValue: "Grüße 👋"},
+                    size: {type: "[Int]"}
+                ) {
+                profilePicture(size: $size) {
+                    uri
+                }
+            }
+        "#,
+        )),
+    )
+    .unwrap();
+    assert_eq!(
+        get_labels(&signature_help),
+        (
+            "...Greeting_user @arguments(greeting: String = \"Grüße 👋\", size: [Int])",
+            vec!["greeting: String = \"Grüße 👋\"", "size: [Int]"]
+        )
+    );
+    assert_eq!(signature_help.active_parameter, Some(1));
+}
+
+#[test]
+fn fragment_spread_arguments_need_a_program() {
+    let signature_help = parse_and_resolve_signature_help(
+        r#"
+            fragment Test on User {
+                ...Picture_user @arguments(pictureSize: |)
+            }
+        "#,
+        None,
+    );
+    assert!(signature_help.is_none());
+}
+
+#[test]
+fn no_signature_help_outside_of_arguments() {
+    let signature_help = parse_and_resolve_signature_help(
+        r#"
+            fragment Test on User {
+                profilePicture(size: 32) {
+                    ur|
+                }
+            }
+        "#,
+        None,
+    );
+    assert!(signature_help.is_none());
+}
@@ -0,0 +1,271 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Utilities for providing the type hierarchy feature, where the supertypes
+//! of a type are its interfaces and the unions it is a member of.
+
+use intern::Lookup;
+use intern::string_key::StringKey;
+use lsp_types::SymbolKind;
+use lsp_types::TypeHierarchyItem;
+use lsp_types::request::Request;
+use lsp_types::request::TypeHierarchyPrepare;
+use lsp_types::request::TypeHierarchySubtypes;
+use lsp_types::request::TypeHierarchySupertypes;
+use schema::SDLSchema;
+use schema::Schema;
+use schema::Type;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::DocblockNode;
+use crate::FeatureResolutionInfo;
+use crate::docblock_resolution_info::DocblockResolutionInfo;
+use crate::lsp_runtime_error::LSPRuntimeError;
+use crate::lsp_runtime_error::LSPRuntimeResult;
+use crate::node_resolution_info::NodeKind;
+use crate::server::GlobalState;
+
+/// The project and type of an item created by [`on_prepare_type_hierarchy`],
+/// that are stored in the data of the item. A schema may be shared by several
+/// projects, so the project can't be derived from the uri of the item.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TypeHierarchyItemData {
+    pub(crate) project_name: StringKey,
+    pub(crate) type_name: StringKey,
+}
+
+pub(crate) fn get_item_data(item: &TypeHierarchyItem) -> LSPRuntimeResult<TypeHierarchyItemData> {
+    let data = item.data.clone().ok_or_else(|| {
+        LSPRuntimeError::UnexpectedError(format!(
+            "Missing data of the type hierarchy item {}.",
+            item.name
+        ))
+    })?;
+    serde_json::from_value(data).map_err(|err| {
+        LSPRuntimeError::UnexpectedError(format!("Invalid type hierarchy item data: {err}"))
+    })
+}
+
+/// Resolve a [`TypeHierarchyPrepare`] request to the type at the position,
+/// either the type condition of a fragment or a type of a Relay Resolver
+/// docblock.
+pub fn on_prepare_type_hierarchy(
+    state: &impl GlobalState,
+    params: <TypeHierarchyPrepare as Request>::Params,
+) -> LSPRuntimeResult<<TypeHierarchyPrepare as Request>::Result> {
+    let type_name = match state.resolve_node(&params.text_document_position_params)? {
+        FeatureResolutionInfo::GraphqlNode(node_resolution_info) => {
+            match node_resolution_info.kind {
+                NodeKind::TypeCondition(type_name) => type_name,
+                _ => return Ok(None),
+            }
+        }
+        FeatureResolutionInfo::DocblockNode(DocblockNode {
+            resolution_info: DocblockResolutionInfo::Type(type_name),
+            ..
+        }) => type_name,
+        FeatureResolutionInfo::DocblockNode(_) => return Ok(None),
+    };
+
+    let project_name = state
+        .extract_project_name_from_uri(&params.text_document_position_params.text_document.uri)?;
+    let schema = state.get_schema(&project_name)?;
+    let Some(type_) = schema.get_type(type_name) else {
+        return Ok(None);
+    };
+    if !matches!(type_, Type::Object(_) | Type::Interface(_) | Type::Union(_)) {
+        return Ok(None);
+    }
+
+    Ok(Some(vec![create_type_hierarchy_item(
+        state,
+        project_name,
+        &schema,
+        type_,
+    )?]))
+}
+
+/// Resolve a [`TypeHierarchySupertypes`] request to the interfaces of the
+/// type of the item, and the unions it is a member of.
+pub fn on_type_hierarchy_supertypes(
+    state: &impl GlobalState,
+    params: <TypeHierarchySupertypes as Request>::Params,
+) -> LSPRuntimeResult<<TypeHierarchySupertypes as Request>::Result> {
+    let TypeHierarchyItemData {
+        project_name,
+        type_name,
+    } = get_item_data(&params.item)?;
+    let schema = state.get_schema(&project_name)?;
+    let type_ = schema
+        .get_type(type_name)
+        .ok_or(LSPRuntimeError::ExpectedError)?;
+
+    Ok(Some(create_type_hierarchy_items(
+        state,
+        project_name,
+        &schema,
+        get_supertypes(&schema, type_),
+    )))
+}
+
+/// Resolve a [`TypeHierarchySubtypes`] request to the types implementing the
+/// interface of the item, or to the members of its union.
+pub fn on_type_hierarchy_subtypes(
+    state: &impl GlobalState,
+    params: <TypeHierarchySubtypes as Request>::Params,
+) -> LSPRuntimeResult<<TypeHierarchySubtypes as Request>::Result> {
+    let TypeHierarchyItemData {
+        project_name,
+        type_name,
+    } = get_item_data(&params.item)?;
+    let schema = state.get_schema(&project_name)?;
+    let type_ = schema
+        .get_type(type_name)
+        .ok_or(LSPRuntimeError::ExpectedError)?;
+
+    Ok(Some(create_type_hierarchy_items(
+        state,
+        project_name,
+        &schema,
+        get_subtypes(&schema, type_),
+    )))
+}
+
+/// The interfaces directly implemented by an object or interface, and the
+/// unions containing an object, sorted by name.
+pub(crate) fn get_supertypes(schema: &SDLSchema, type_: Type) -> Vec<Type> {
+    let mut supertypes: Vec<Type> = match type_ {
+        Type::Object(object_id) => {
+            let unions = schema
+                .unions()
+                .filter(|union| union.members.contains(&object_id))
+                .filter_map(|union| schema.get_type(union.name.item.0));
+            schema
+                .object(object_id)
+                .interfaces
+                .iter()
+                .map(|interface_id| Type::Interface(*interface_id))
+                .chain(unions)
+                .collect()
+        }
+        Type::Interface(interface_id) => schema
+            .interface(interface_id)
+            .interfaces
+            .iter()
+            .map(|interface_id| Type::Interface(*interface_id))
+            .collect(),
+        _ => vec![],
+    };
+    sort_by_name(schema, &mut supertypes);
+    supertypes
+}
+
+/// The objects and interfaces directly implementing an interface, or the
+/// members of a union, sorted by name.
+pub(crate) fn get_subtypes(schema: &SDLSchema, type_: Type) -> Vec<Type> {
+    let mut subtypes: Vec<Type> = match type_ {
+        Type::Interface(interface_id) => {
+            let interface = schema.interface(interface_id);
+            interface
+                .implementing_interfaces
+                .iter()
+                .map(|interface_id| Type::Interface(*interface_id))
+                .chain(
+                    interface
+                        .implementing_objects
+                        .iter()
+                        .map(|object_id| Type::Object(*object_id)),
+                )
+                .collect()
+        }
+        Type::Union(union_id) => schema
+            .union(union_id)
+            .members
+            .iter()
+            .map(|object_id| Type::Object(*object_id))
+            .collect(),
+        _ => vec![],
+    };
+    sort_by_name(schema, &mut subtypes);
+    subtypes
+}
+
+fn sort_by_name(schema: &SDLSchema, types: &mut [Type]) {
+    types.sort_by_key(|type_| schema.get_type_name(*type_).lookup());
+}
+
+/// Types that can't be located, like the ones of generated schema sources,
+/// are left out of the hierarchy.
+fn create_type_hierarchy_items(
+    state: &impl GlobalState,
+    project_name: StringKey,
+    schema: &SDLSchema,
+    types: Vec<Type>,
+) -> Vec<TypeHierarchyItem> {
+    types
+        .into_iter()
+        .filter_map(|type_| create_type_hierarchy_item(state, project_name, schema, type_).ok())
+        .collect()
+}
+
+fn create_type_hierarchy_item(
+    state: &impl GlobalState,
+    project_name: StringKey,
+    schema: &SDLSchema,
+    type_: Type,
+) -> LSPRuntimeResult<TypeHierarchyItem> {
+    let (location, kind, keyword) = match type_ {
+        Type::Object(object_id) => (
+            schema.object(object_id).name.location,
+            SymbolKind::CLASS,
+            "type",
+        ),
+        Type::Interface(interface_id) => (
+            schema.interface(interface_id).name.location,
+            SymbolKind::INTERFACE,
+            "interface",
+        ),
+        Type::Union(union_id) => (
+            schema.union(union_id).name.location,
+            SymbolKind::ENUM,
+            "union",
+        ),
+        _ => {
+            return Err(LSPRuntimeError::UnexpectedError(format!(
+                "Unexpected type {} in the type hierarchy.",
+                schema.get_type_name(type_)
+            )));
+        }
+    };
+    let detail = if schema.is_extension_type(type_) {
+        format!("{keyword} (client schema extension)")
+    } else {
+        keyword.to_string()
+    };
+    let location = state.transform_relay_location_in_editor_to_lsp_location(location)?;
+    let type_name = schema.get_type_name(type_);
+    let data = TypeHierarchyItemData {
+        project_name,
+        type_name,
+    };
+
+    Ok(TypeHierarchyItem {
+        name: type_name.to_string(),
+        kind,
+        tags: None,
+        detail: Some(detail),
+        uri: location.uri,
+        range: location.range,
+        selection_range: location.range,
+        data: serde_json::to_value(data).ok(),
+    })
+}
+
+#[cfg(test)]
+mod test;
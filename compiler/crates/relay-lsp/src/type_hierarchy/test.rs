@@ -0,0 +1,115 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use intern::string_key::Intern;
+use lsp_types::Range;
+use lsp_types::SymbolKind;
+use lsp_types::TypeHierarchyItem;
+use lsp_types::Uri;
+use relay_test_schema::get_test_schema;
+use relay_test_schema::get_test_schema_with_extensions;
+use schema::SDLSchema;
+use schema::Schema;
+use schema::Type;
+
+use super::TypeHierarchyItemData;
+use super::get_item_data;
+use super::get_subtypes;
+use super::get_supertypes;
+
+fn type_names(schema: &SDLSchema, types: Vec<Type>) -> Vec<String> {
+    types
+        .into_iter()
+        .map(|type_| schema.get_type_name(type_).to_string())
+        .collect()
+}
+
+fn supertypes(schema: &SDLSchema, type_name: &str) -> Vec<String> {
+    let type_ = schema.get_type(type_name.intern()).unwrap();
+    type_names(schema, get_supertypes(schema, type_))
+}
+
+fn subtypes(schema: &SDLSchema, type_name: &str) -> Vec<String> {
+    let type_ = schema.get_type(type_name.intern()).unwrap();
+    type_names(schema, get_subtypes(schema, type_))
+}
+
+#[test]
+fn object_supertypes_include_interfaces_and_unions() {
+    let schema = get_test_schema();
+    assert_eq!(
+        supertypes(&schema, "Story"),
+        vec!["FeedUnit", "MaybeNode", "MaybeNodeInterface", "Node"]
+    );
+}
+
+#[test]
+fn interface_hierarchy() {
+    let schema = get_test_schema();
+    assert_eq!(
+        supertypes(&schema, "ImplementsUserNameRenderable"),
+        vec!["UserNameRenderable"]
+    );
+    assert_eq!(
+        subtypes(&schema, "ImplementsUserNameRenderable"),
+        vec![
+            "ImplementsImplementsUserNameRenderable",
+            "ImplementsImplementsUserNameRenderableAndUserNameRenderable",
+        ]
+    );
+}
+
+#[test]
+fn union_subtypes_are_its_members() {
+    let schema = get_test_schema();
+    assert_eq!(
+        subtypes(&schema, "MaybeNode"),
+        vec!["FakeNode", "NonNode", "Story"]
+    );
+    assert!(supertypes(&schema, "MaybeNode").is_empty());
+}
+
+#[test]
+fn client_schema_extension_types() {
+    let schema = get_test_schema_with_extensions(
+        r#"
+        interface ClientNamed {
+            name: String
+        }
+
+        type ClientUser implements ClientNamed {
+            name: String
+        }
+
+        extend type User implements ClientNamed
+        "#,
+    );
+    assert_eq!(subtypes(&schema, "ClientNamed"), vec!["ClientUser", "User"]);
+    assert!(supertypes(&schema, "User").contains(&"ClientNamed".to_string()));
+}
+
+#[test]
+fn item_data_round_trips_through_data() {
+    let data = TypeHierarchyItemData {
+        project_name: "test_project".intern(),
+        type_name: "User".intern(),
+    };
+    let item = TypeHierarchyItem {
+        name: "User".to_string(),
+        kind: SymbolKind::CLASS,
+        tags: None,
+        detail: None,
+        uri: "file:///schema.graphql".parse::<Uri>().unwrap(),
+        range: Range::default(),
+        selection_range: Range::default(),
+        data: serde_json::to_value(&data).ok(),
+    };
+    assert_eq!(get_item_data(&item).unwrap(), data);
+
+    let item_without_data = TypeHierarchyItem { data: None, ..item };
+    assert!(get_item_data(&item_without_data).is_err());
+}
@@ -7,6 +7,7 @@
 
 use std::path::Path;
 
+use common::DiagnosticsResult;
 use common::Location;
 use common::SourceLocationKey;
 use common::Span;
@@ -16,7 +17,9 @@ use docblock_syntax::parse_docblock;
 use extract_graphql::JavaScriptSourceFeature;
 use graphql_syntax::ExecutableDefinition;
 use graphql_syntax::GraphQLSource;
+use graphql_syntax::Identifier;
 use graphql_syntax::ParserFeatures;
+use graphql_syntax::Selection;
 use graphql_syntax::parse_executable_with_error_recovery_and_parser_features;
 use intern::string_key::StringKey;
 use log::debug;
@@ -28,6 +31,7 @@ use relay_compiler::FileGroup;
 use relay_compiler::ProjectConfig;
 use relay_compiler::config::Config;
 use relay_compiler::get_parser_features;
+use relay_docblock::DocblockIr;
 use relay_docblock::ParseOptions;
 use relay_docblock::parse_docblock_ast;
 
@@ -119,6 +123,12 @@ pub fn get_project_name_from_file_group(file_group: &FileGroup) -> Result<String
     Ok(project_name.into())
 }
 
+/// The key for the locations of a synced schema extension document. Standalone
+/// keys are resolved against the root dir, so this uses the path of the uri.
+pub fn schema_source_location_key(uri: &Uri) -> SourceLocationKey {
+    SourceLocationKey::standalone(uri.path().as_str())
+}
+
 /// Return a parsed executable document, or parsed Docblock IR for this LSP
 /// request, only if the request occurs within a GraphQL document or Docblock.
 pub fn extract_feature_from_text(
@@ -132,7 +142,7 @@ pub fn extract_feature_from_text(
     let position = text_document_position.position;
 
     if let Some(schema_source) = schema_source_cache.get(uri) {
-        let source_location_key = SourceLocationKey::standalone(uri.as_str());
+        let source_location_key = schema_source_location_key(uri);
         let schema_document = graphql_syntax::parse_schema_document(
             &schema_source.text_source().text,
             source_location_key,
@@ -208,31 +218,14 @@ pub fn extract_feature_from_text(
                 js_source_feature_cache,
                 parser_features,
             )?;
-            let docblock_ir = parse_docblock(text, source_location_key)
-                .and_then(|ast| {
-                    parse_docblock_ast(
-                        &project_config.name,
-                        &ast,
-                        Some(&executable_definitions_in_file),
-                        &ParseOptions {
-                            enable_interface_output_type: &project_config
-                                .feature_flags
-                                .relay_resolver_enable_interface_output_type,
-                            allow_resolver_non_nullable_return_type: &project_config
-                                .feature_flags
-                                .allow_resolver_non_nullable_return_type,
-                            allow_legacy_relay_resolver_tag: &project_config
-                                .feature_flags
-                                .allow_legacy_relay_resolver_tag,
-                        },
-                    )
-                })
-                .map_err(|_| {
-                    LSPRuntimeError::UnexpectedError("Failed to parse docblock".to_string())
-                })?
-                .ok_or_else(|| {
-                    LSPRuntimeError::UnexpectedError("No docblock IR found".to_string())
-                })?;
+            let docblock_ir = parse_docblock_ir(
+                project_config,
+                text,
+                source_location_key,
+                &executable_definitions_in_file,
+            )
+            .map_err(|_| LSPRuntimeError::UnexpectedError("Failed to parse docblock".to_string()))?
+            .ok_or_else(|| LSPRuntimeError::UnexpectedError("No docblock IR found".to_string()))?;
 
             let position_span =
                 position_to_offset(&position, index_offset, text_source.line_index, text)
@@ -251,6 +244,34 @@ pub fn extract_feature_from_text(
     }
 }
 
+/// Parse the text of a docblock extracted from a JavaScript source into
+/// Docblock IR, using the resolver feature flags of the project.
+pub fn parse_docblock_ir(
+    project_config: &ProjectConfig,
+    text: &str,
+    source_location_key: SourceLocationKey,
+    executable_definitions: &Vec<ExecutableDefinition>,
+) -> DiagnosticsResult<Option<DocblockIr>> {
+    parse_docblock(text, source_location_key).and_then(|ast| {
+        parse_docblock_ast(
+            &project_config.name,
+            &ast,
+            Some(executable_definitions),
+            &ParseOptions {
+                enable_interface_output_type: &project_config
+                    .feature_flags
+                    .relay_resolver_enable_interface_output_type,
+                allow_resolver_non_nullable_return_type: &project_config
+                    .feature_flags
+                    .allow_resolver_non_nullable_return_type,
+                allow_legacy_relay_resolver_tag: &project_config
+                    .feature_flags
+                    .allow_legacy_relay_resolver_tag,
+            },
+        )
+    })
+}
+
 /// Maps the LSP `Position` type back to a relative span, so we can find out which syntax node(s)
 /// this request came from
 fn position_to_span(position: &Position, source: &TextSource, index_offset: usize) -> Option<Span> {
@@ -296,6 +317,38 @@ pub fn position_to_offset(
     None
 }
 
+/// The position after the last character of a document.
+pub fn get_end_position(text: &str) -> Position {
+    let last_line = text.rsplit('\n').next().unwrap_or_default();
+    Position {
+        line: text.matches('\n').count() as u32,
+        character: last_line.encode_utf16().count() as u32,
+    }
+}
+
+/// Collect the names of the fragments spread in the selections, including the
+/// nested ones.
+pub fn collect_fragment_spread_names(selections: &[Selection], names: &mut Vec<Identifier>) {
+    for selection in selections {
+        match selection {
+            Selection::FragmentSpread(spread) => names.push(spread.name),
+            Selection::LinkedField(field) => {
+                collect_fragment_spread_names(&field.selections.items, names);
+            }
+            Selection::InlineFragment(inline_fragment) => {
+                collect_fragment_spread_names(&inline_fragment.selections.items, names);
+            }
+            Selection::ScalarField(_) => {}
+        }
+    }
+}
+
+/// Unlike [`Span::contains`], the position may also be at the end of the span,
+/// as when a name is being typed.
+pub fn contains(span: Span, position_span: Span) -> bool {
+    span.start <= position_span.start && position_span.end <= span.end
+}
+
 /// Converts a filesystem path to a `file://` URI, handling both Unix and Windows paths.
 pub fn path_to_file_uri(path: &Path) -> Option<Uri> {
     let path_str = path.to_str()?;
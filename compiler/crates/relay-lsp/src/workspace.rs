@@ -0,0 +1,107 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Utilities related to LSP workspace notifications, for changes of the files
+//! watched by the client and of its configuration.
+
+use std::path::Path;
+
+use lsp_types::ClientCapabilities;
+use lsp_types::DidChangeWatchedFilesRegistrationOptions;
+use lsp_types::FileSystemWatcher;
+use lsp_types::GlobPattern;
+use lsp_types::Registration;
+use lsp_types::Uri;
+use lsp_types::notification::DidChangeConfiguration;
+use lsp_types::notification::DidChangeWatchedFiles;
+use lsp_types::notification::Notification;
+
+use crate::lsp_runtime_error::LSPRuntimeResult;
+use crate::server::GlobalState;
+use crate::utils::is_file_uri_in_dir;
+
+/// The files that the client is asked to watch besides the Relay config: the
+/// schema sources and the generated artifacts.
+const WATCHED_FILE_GLOBS: [&str; 2] = ["**/*.{graphql,gql}", "**/__generated__/**"];
+
+pub fn on_did_change_watched_files(
+    lsp_state: &impl GlobalState,
+    params: <DidChangeWatchedFiles as Notification>::Params,
+) -> LSPRuntimeResult<()> {
+    // The config file is not necessarily in the root directory, when the
+    // config sets a `root` of its own
+    let config_path = lsp_state.get_config().config_path.clone();
+    if params
+        .changes
+        .iter()
+        .any(|file_event| is_config_file(&config_path, Path::new(file_event.uri.path().as_str())))
+    {
+        // All active projects are built again with the new config
+        return lsp_state.reload_config();
+    }
+
+    let root_dir = lsp_state.root_dir();
+    let uris: Vec<Uri> = params
+        .changes
+        .into_iter()
+        .map(|file_event| file_event.uri)
+        .filter(|uri| is_file_uri_in_dir(&root_dir, uri))
+        .collect();
+
+    lsp_state.watched_files_changed(&uris)
+}
+
+/// The settings of the client are not used by the server, so the notification
+/// only signals that the Relay config may have to be loaded again. It is only
+/// applied if it changed.
+pub fn on_did_change_configuration(
+    lsp_state: &impl GlobalState,
+    _params: <DidChangeConfiguration as Notification>::Params,
+) -> LSPRuntimeResult<()> {
+    lsp_state.reload_config()
+}
+
+/// Whether the file is the one that the current Relay config was loaded from.
+pub(crate) fn is_config_file(config_path: &Path, file_path: &Path) -> bool {
+    file_path == config_path
+}
+
+/// Whether the client supports registering the watched files dynamically,
+/// with a `client/registerCapability` request.
+pub fn supports_watched_files_registration(client_capabilities: &ClientCapabilities) -> bool {
+    client_capabilities
+        .workspace
+        .as_ref()
+        .and_then(|workspace| workspace.did_change_watched_files.as_ref())
+        .and_then(|did_change_watched_files| did_change_watched_files.dynamic_registration)
+        .unwrap_or(false)
+}
+
+pub(crate) fn watched_files_registration(config_path: &Path) -> Registration {
+    let config_glob = config_path
+        .file_name()
+        .map(|file_name| format!("**/{}", file_name.to_string_lossy()));
+    let register_options = DidChangeWatchedFilesRegistrationOptions {
+        watchers: config_glob
+            .into_iter()
+            .chain(WATCHED_FILE_GLOBS.iter().map(|glob| glob.to_string()))
+            .map(|glob| FileSystemWatcher {
+                glob_pattern: GlobPattern::String(glob),
+                kind: None,
+            })
+            .collect(),
+    };
+
+    Registration {
+        id: "relay-watched-files".to_string(),
+        method: DidChangeWatchedFiles::METHOD.to_string(),
+        register_options: serde_json::to_value(register_options).ok(),
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,58 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::path::Path;
+
+use lsp_types::DidChangeWatchedFilesRegistrationOptions;
+use lsp_types::GlobPattern;
+
+use super::is_config_file;
+use super::watched_files_registration;
+
+#[test]
+fn config_file_of_the_loaded_config() {
+    let config_path = Path::new("/repo/config/relay.json");
+    assert!(is_config_file(
+        config_path,
+        Path::new("/repo/config/relay.json")
+    ));
+
+    // Files that a config could be loaded from, but the current one was not
+    assert!(!is_config_file(
+        config_path,
+        Path::new("/repo/relay.config.json")
+    ));
+    assert!(!is_config_file(
+        config_path,
+        Path::new("/repo/package.json")
+    ));
+    assert!(!is_config_file(
+        config_path,
+        Path::new("/repo/config/schema.graphql")
+    ));
+}
+
+#[test]
+fn watched_files_registration_globs() {
+    let registration = watched_files_registration(Path::new("/repo/config/relay.json"));
+    assert_eq!(registration.method, "workspace/didChangeWatchedFiles");
+
+    let register_options: DidChangeWatchedFilesRegistrationOptions =
+        serde_json::from_value(registration.register_options.unwrap()).unwrap();
+    let globs: Vec<_> = register_options
+        .watchers
+        .into_iter()
+        .map(|watcher| match watcher.glob_pattern {
+            GlobPattern::String(glob) => glob,
+            GlobPattern::Relative(_) => panic!("Expected a glob string"),
+        })
+        .collect();
+    assert_eq!(
+        globs,
+        vec!["**/relay.json", "**/*.{graphql,gql}", "**/__generated__/**",]
+    );
+}
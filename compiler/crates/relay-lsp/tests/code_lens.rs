@@ -0,0 +1,102 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::path::Path;
+use std::str::FromStr;
+
+use common::SourceLocationKey;
+use common::TextSource;
+use fixture_tests::Fixture;
+use graphql_ir::Program;
+use graphql_ir::build;
+use graphql_ir::reexport::Intern;
+use graphql_syntax::parse_executable;
+use lsp_types::Location;
+use lsp_types::Uri;
+use relay_compiler::ProjectConfig;
+use relay_lsp::code_lens::get_code_lenses;
+use relay_lsp::code_lens::get_fragment_spreads_command;
+use relay_test_schema::get_test_schema;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FragmentSpreadsLensData {
+    fragment_name: String,
+}
+
+/// Prints the lenses of the fixture document, with the fragment spread count
+/// lenses resolved. Fixtures marked with `# not built` have no program, as
+/// for a project that has not been built yet.
+pub async fn transform_fixture(fixture: &Fixture<'_>) -> Result<String, String> {
+    let root_dir = Path::new("/root");
+    let uri = Uri::from_str(&format!("file:///root/{}", fixture.file_name)).unwrap();
+    let text_source = TextSource::from_whole_document(fixture.content);
+    let document = parse_executable(
+        fixture.content,
+        SourceLocationKey::standalone(fixture.file_name),
+    )
+    .map_err(|diagnostics| format!("{diagnostics:?}"))?;
+
+    let program = if fixture.content.contains("# not built") {
+        None
+    } else {
+        let schema = get_test_schema();
+        let ir = build(&schema, &document.definitions)
+            .map_err(|diagnostics| format!("{diagnostics:?}"))?;
+        Some(Program::from_definitions(schema, ir))
+    };
+
+    let code_lenses = get_code_lenses(
+        &uri,
+        &document,
+        &text_source,
+        program.as_ref(),
+        &ProjectConfig::default(),
+        root_dir,
+    );
+
+    let mut output = String::new();
+    for code_lens in code_lenses {
+        let command = match (&code_lens.command, code_lens.data, &program) {
+            (Some(command), _, _) => command.clone(),
+            (None, Some(_), None) => {
+                output.push_str(&format!(
+                    "{}:{} unresolved\n",
+                    code_lens.range.start.line, code_lens.range.start.character,
+                ));
+                continue;
+            }
+            (None, Some(data), Some(program)) => {
+                let data: FragmentSpreadsLensData = serde_json::from_value(data).unwrap();
+                get_fragment_spreads_command(
+                    program,
+                    &uri,
+                    data.fragment_name.as_str().intern(),
+                    code_lens.range.start,
+                    |location| {
+                        Ok(Location {
+                            uri: uri.clone(),
+                            range: text_source.to_span_range(location.span()),
+                        })
+                    },
+                )
+                .map_err(|err| format!("{err:?}"))?
+            }
+            (None, None, _) => return Err("Code lens without command or data".to_string()),
+        };
+        output.push_str(&format!(
+            "{}:{} {:?} -> {} {}\n",
+            code_lens.range.start.line,
+            code_lens.range.start.character,
+            command.title,
+            command.command,
+            serde_json::to_string(&command.arguments).unwrap(),
+        ));
+    }
+    Ok(output)
+}
@@ -0,0 +1,27 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ *
+ * @generated SignedSource<<d4bdfa00a496b16e9babb28b407053f0>>
+ */
+
+mod code_lens;
+
+use code_lens::transform_fixture;
+use fixture_tests::test_fixture;
+
+#[tokio::test]
+async fn not_built() {
+    let input = include_str!("code_lens/fixtures/not_built.graphql");
+    let expected = include_str!("code_lens/fixtures/not_built.expected");
+    test_fixture(transform_fixture, file!(), "not_built.graphql", "code_lens/fixtures/not_built.expected", input, expected).await;
+}
+
+#[tokio::test]
+async fn operations_and_fragments() {
+    let input = include_str!("code_lens/fixtures/operations_and_fragments.graphql");
+    let expected = include_str!("code_lens/fixtures/operations_and_fragments.expected");
+    test_fixture(transform_fixture, file!(), "operations_and_fragments.graphql", "code_lens/fixtures/operations_and_fragments.expected", input, expected).await;
+}
@@ -0,0 +1,55 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use common::SourceLocationKey;
+use common::TextSource;
+use fixture_tests::Fixture;
+use graphql_syntax::parse_executable;
+use graphql_syntax::parse_schema_document;
+use lsp_types::DocumentSymbol;
+use relay_lsp::document_symbols::get_executable_document_symbols;
+use relay_lsp::document_symbols::get_schema_document_symbols;
+
+pub async fn transform_fixture(fixture: &Fixture<'_>) -> Result<String, String> {
+    let text_source = TextSource::from_whole_document(fixture.content);
+    let source_location = SourceLocationKey::standalone(fixture.file_name);
+    let symbols = if fixture.file_name.ends_with(".schema.graphql") {
+        let document = parse_schema_document(fixture.content, source_location)
+            .map_err(|diagnostics| format!("{diagnostics:?}"))?;
+        get_schema_document_symbols(&document, &text_source)
+    } else {
+        let document = parse_executable(fixture.content, source_location)
+            .map_err(|diagnostics| format!("{diagnostics:?}"))?;
+        get_executable_document_symbols(&document, &text_source)
+    };
+
+    let mut output = String::new();
+    print_symbols(&symbols, 0, &mut output);
+    Ok(output)
+}
+
+fn print_symbols(symbols: &[DocumentSymbol], depth: usize, output: &mut String) {
+    for symbol in symbols {
+        output.push_str(&format!(
+            "{}{} ({:?}){} {}:{}-{}:{}\n",
+            "  ".repeat(depth),
+            symbol.name,
+            symbol.kind,
+            symbol
+                .detail
+                .as_ref()
+                .map_or_else(String::new, |detail| format!(" {detail}")),
+            symbol.selection_range.start.line,
+            symbol.selection_range.start.character,
+            symbol.selection_range.end.line,
+            symbol.selection_range.end.character,
+        ));
+        if let Some(children) = &symbol.children {
+            print_symbols(children, depth + 1, output);
+        }
+    }
+}
@@ -0,0 +1,34 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ *
+ * @generated SignedSource<<4e6b91cd634562b074b1e2a1ec1b0e20>>
+ */
+
+mod document_symbols;
+
+use document_symbols::transform_fixture;
+use fixture_tests::test_fixture;
+
+#[tokio::test]
+async fn anonymous_operation() {
+    let input = include_str!("document_symbols/fixtures/anonymous_operation.graphql");
+    let expected = include_str!("document_symbols/fixtures/anonymous_operation.expected");
+    test_fixture(transform_fixture, file!(), "anonymous_operation.graphql", "document_symbols/fixtures/anonymous_operation.expected", input, expected).await;
+}
+
+#[tokio::test]
+async fn operations_and_fragments() {
+    let input = include_str!("document_symbols/fixtures/operations_and_fragments.graphql");
+    let expected = include_str!("document_symbols/fixtures/operations_and_fragments.expected");
+    test_fixture(transform_fixture, file!(), "operations_and_fragments.graphql", "document_symbols/fixtures/operations_and_fragments.expected", input, expected).await;
+}
+
+#[tokio::test]
+async fn schema_extension_schema() {
+    let input = include_str!("document_symbols/fixtures/schema_extension.schema.graphql");
+    let expected = include_str!("document_symbols/fixtures/schema_extension.schema.expected");
+    test_fixture(transform_fixture, file!(), "schema_extension.schema.graphql", "document_symbols/fixtures/schema_extension.schema.expected", input, expected).await;
+}
@@ -0,0 +1,21 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use fixture_tests::Fixture;
+use graphql_syntax::ParserFeatures;
+use relay_lsp::formatting::format_embedded_text;
+use relay_lsp::formatting::format_schema_text;
+
+pub async fn transform_fixture(fixture: &Fixture<'_>) -> Result<String, String> {
+    let formatted = if fixture.file_name.ends_with(".schema.graphql") {
+        format_schema_text(fixture.content)
+    } else {
+        format_embedded_text(fixture.content, ParserFeatures::default())
+    };
+
+    Ok(formatted.unwrap_or_else(|| "<NOT FORMATTED>".to_string()))
+}
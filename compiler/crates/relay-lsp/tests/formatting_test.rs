@@ -0,0 +1,62 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ *
+ * @generated SignedSource<<9537ab07f809248eb6e93b5ab2957b1c>>
+ */
+
+mod formatting;
+
+use formatting::transform_fixture;
+use fixture_tests::test_fixture;
+
+#[tokio::test]
+async fn fragment_with_comment() {
+    let input = include_str!("formatting/fixtures/fragment_with_comment.graphql");
+    let expected = include_str!("formatting/fixtures/fragment_with_comment.expected");
+    test_fixture(transform_fixture, file!(), "fragment_with_comment.graphql", "formatting/fixtures/fragment_with_comment.expected", input, expected).await;
+}
+
+#[tokio::test]
+async fn invalid_document() {
+    let input = include_str!("formatting/fixtures/invalid_document.graphql");
+    let expected = include_str!("formatting/fixtures/invalid_document.expected");
+    test_fixture(transform_fixture, file!(), "invalid_document.graphql", "formatting/fixtures/invalid_document.expected", input, expected).await;
+}
+
+#[tokio::test]
+async fn multiple_definitions() {
+    let input = include_str!("formatting/fixtures/multiple_definitions.graphql");
+    let expected = include_str!("formatting/fixtures/multiple_definitions.expected");
+    test_fixture(transform_fixture, file!(), "multiple_definitions.graphql", "formatting/fixtures/multiple_definitions.expected", input, expected).await;
+}
+
+#[tokio::test]
+async fn query_with_indentation() {
+    let input = include_str!("formatting/fixtures/query_with_indentation.graphql");
+    let expected = include_str!("formatting/fixtures/query_with_indentation.expected");
+    test_fixture(transform_fixture, file!(), "query_with_indentation.graphql", "formatting/fixtures/query_with_indentation.expected", input, expected).await;
+}
+
+#[tokio::test]
+async fn schema_extension_schema() {
+    let input = include_str!("formatting/fixtures/schema_extension.schema.graphql");
+    let expected = include_str!("formatting/fixtures/schema_extension.schema.expected");
+    test_fixture(transform_fixture, file!(), "schema_extension.schema.graphql", "formatting/fixtures/schema_extension.schema.expected", input, expected).await;
+}
+
+#[tokio::test]
+async fn schema_extension_with_comment_schema() {
+    let input = include_str!("formatting/fixtures/schema_extension_with_comment.schema.graphql");
+    let expected = include_str!("formatting/fixtures/schema_extension_with_comment.schema.expected");
+    test_fixture(transform_fixture, file!(), "schema_extension_with_comment.schema.graphql", "formatting/fixtures/schema_extension_with_comment.schema.expected", input, expected).await;
+}
+
+#[tokio::test]
+async fn single_line_fragment() {
+    let input = include_str!("formatting/fixtures/single_line_fragment.graphql");
+    let expected = include_str!("formatting/fixtures/single_line_fragment.expected");
+    test_fixture(transform_fixture, file!(), "single_line_fragment.graphql", "formatting/fixtures/single_line_fragment.expected", input, expected).await;
+}
@@ -17,11 +17,16 @@ use graphql_syntax::parse_executable;
 use graphql_test_helpers::diagnostics_to_sorted_string;
 use lsp_types::HoverContents;
 use lsp_types::MarkedString;
+use relay_compiler::config::ProjectConfig;
 use relay_lsp::ContentConsumerType;
 use relay_lsp::DummyExtraDataProvider;
 use relay_lsp::hover::get_hover;
+use relay_lsp::hover::get_selection_type_hover;
+use relay_lsp::hover::with_selection_type;
 use relay_test_schema::get_test_schema;
 use relay_test_schema::get_test_schema_with_extensions;
+use relay_typegen::TypegenConfig;
+use relay_typegen::TypegenLanguage;
 use resolution_path::ResolvePosition;
 
 pub async fn transform_fixture(fixture: &Fixture<'_>) -> Result<String, String> {
@@ -40,13 +45,14 @@ pub async fn transform_fixture(fixture: &Fixture<'_>) -> Result<String, String>
         .map_err(|diagnostics| diagnostics_to_sorted_string(&source, &diagnostics))?;
     let program = Program::from_definitions(Arc::clone(&schema), ir);
 
-    let path = ast.resolve((), Span::from_usize(cursor_position, cursor_position));
+    let position_span = Span::from_usize(cursor_position, cursor_position);
+    let path = ast.resolve((), position_span);
 
     let schema_name = "Some Schema Name".intern();
 
     let extra_data_provider = DummyExtraDataProvider::new();
 
-    let hover_contents = get_hover(
+    let hover = get_hover(
         &path,
         &schema,
         schema_name,
@@ -55,8 +61,25 @@ pub async fn transform_fixture(fixture: &Fixture<'_>) -> Result<String, String>
         &program,
         ContentConsumerType::Relay,
     )
-    .ok_or("<NO HOVER RESPONSE>")?
-    .contents;
+    .ok_or("<NO HOVER RESPONSE>")?;
+
+    let project_config = ProjectConfig {
+        typegen_config: TypegenConfig {
+            language: if fixture.content.contains("# typegen_config.language = flow") {
+                TypegenLanguage::Flow
+            } else {
+                TypegenLanguage::TypeScript
+            },
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let hover_contents =
+        match get_selection_type_hover(&ast, position_span, &program, &project_config) {
+            Some(selection_type) => with_selection_type(hover, selection_type),
+            None => hover,
+        }
+        .contents;
 
     Ok(print_hover_contents(hover_contents))
 }
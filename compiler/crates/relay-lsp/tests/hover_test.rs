@@ -4,7 +4,7 @@
  * This source code is licensed under the MIT license found in the
  * LICENSE file in the root directory of this source tree.
  *
- * @generated SignedSource<<7f7ba42d92c3419f1fffd8013ba3d40f>>
+ * @generated SignedSource<<9ff39a8b0611eeb691ba93f09f287c75>>
  */
 
 mod hover;
@@ -61,6 +61,48 @@ async fn scalar_field_with_description() {
     test_fixture(transform_fixture, file!(), "scalar_field_with_description.graphql", "hover/fixtures/scalar_field_with_description.expected", input, expected).await;
 }
 
+#[tokio::test]
+async fn selection_type_aliased_fragment_spread() {
+    let input = include_str!("hover/fixtures/selection_type_aliased_fragment_spread.graphql");
+    let expected = include_str!("hover/fixtures/selection_type_aliased_fragment_spread.expected");
+    test_fixture(transform_fixture, file!(), "selection_type_aliased_fragment_spread.graphql", "hover/fixtures/selection_type_aliased_fragment_spread.expected", input, expected).await;
+}
+
+#[tokio::test]
+async fn selection_type_catch_field() {
+    let input = include_str!("hover/fixtures/selection_type_catch_field.graphql");
+    let expected = include_str!("hover/fixtures/selection_type_catch_field.expected");
+    test_fixture(transform_fixture, file!(), "selection_type_catch_field.graphql", "hover/fixtures/selection_type_catch_field.expected", input, expected).await;
+}
+
+#[tokio::test]
+async fn selection_type_flow() {
+    let input = include_str!("hover/fixtures/selection_type_flow.graphql");
+    let expected = include_str!("hover/fixtures/selection_type_flow.expected");
+    test_fixture(transform_fixture, file!(), "selection_type_flow.graphql", "hover/fixtures/selection_type_flow.expected", input, expected).await;
+}
+
+#[tokio::test]
+async fn selection_type_linked_field() {
+    let input = include_str!("hover/fixtures/selection_type_linked_field.graphql");
+    let expected = include_str!("hover/fixtures/selection_type_linked_field.expected");
+    test_fixture(transform_fixture, file!(), "selection_type_linked_field.graphql", "hover/fixtures/selection_type_linked_field.expected", input, expected).await;
+}
+
+#[tokio::test]
+async fn selection_type_required_field() {
+    let input = include_str!("hover/fixtures/selection_type_required_field.graphql");
+    let expected = include_str!("hover/fixtures/selection_type_required_field.expected");
+    test_fixture(transform_fixture, file!(), "selection_type_required_field.graphql", "hover/fixtures/selection_type_required_field.expected", input, expected).await;
+}
+
+#[tokio::test]
+async fn selection_type_unmasked_fragment_spread() {
+    let input = include_str!("hover/fixtures/selection_type_unmasked_fragment_spread.graphql");
+    let expected = include_str!("hover/fixtures/selection_type_unmasked_fragment_spread.expected");
+    test_fixture(transform_fixture, file!(), "selection_type_unmasked_fragment_spread.graphql", "hover/fixtures/selection_type_unmasked_fragment_spread.expected", input, expected).await;
+}
+
 #[tokio::test]
 async fn whitespace_after_query_selection() {
     let input = include_str!("hover/fixtures/whitespace_after_query_selection.graphql");
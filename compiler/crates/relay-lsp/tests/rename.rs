@@ -7,27 +7,59 @@
 
 use std::sync::Arc;
 
+use common::FeatureFlag;
 use common::Location;
 use common::SourceLocationKey;
 use common::Span;
+use docblock_syntax::parse_docblock;
+use extract_graphql::JavaScriptSourceFeature;
 use fixture_tests::Fixture;
 use graphql_ir::Program;
 use graphql_ir::build;
 use graphql_syntax::FragmentArgumentSyntaxKind;
 use graphql_syntax::ParserFeatures;
+use graphql_syntax::parse_executable;
 use graphql_syntax::parse_executable_with_features;
+use graphql_syntax::parse_schema_document;
 use graphql_test_helpers::diagnostics_to_sorted_string;
+use intern::Lookup;
 use itertools::Itertools;
+use relay_compiler::ProjectName;
+use relay_docblock::DocblockIr;
+use relay_docblock::ParseOptions;
+use relay_docblock::extend_schema_with_resolver_type_system_definition;
+use relay_docblock::parse_docblock_ast;
 use relay_lsp::Feature;
+use relay_lsp::LSPRuntimeError;
 use relay_lsp::rename::create_rename_request;
 use relay_lsp::rename::get_locations_for_rename;
+use relay_lsp::rename::get_resolver_exports_for_rename;
 use relay_test_schema::get_test_schema;
+use relay_test_schema::get_test_schema_with_extensions;
+use relay_test_schema::get_test_schema_with_located_extensions;
+
+const EXTENSIONS_SEPARATOR: &str = "# %extensions%";
 
 pub async fn transform_fixture(fixture: &Fixture<'_>) -> Result<String, String> {
-    let document = fixture.content;
-    let cursor_position = document.find('|').unwrap() - 1;
-    let source = document.replace("|", "");
+    if fixture.file_name.ends_with(".js") {
+        return transform_docblock_fixture(fixture);
+    }
+
+    let (document, extensions) = match fixture.content.split_once(EXTENSIONS_SEPARATOR) {
+        Some((document, extensions)) => (document, Some(extensions)),
+        None => (fixture.content, None),
+    };
     let source_location = SourceLocationKey::standalone(fixture.file_name);
+    let extensions_location = SourceLocationKey::standalone("extensions.graphql");
+    let schema = match extensions {
+        Some(extensions) => get_test_schema_with_located_extensions(
+            &extensions.replace("|", ""),
+            extensions_location,
+        ),
+        None => get_test_schema(),
+    };
+
+    let source = document.replace("|", "");
     let ast = parse_executable_with_features(
         &source,
         source_location,
@@ -38,13 +70,36 @@ pub async fn transform_fixture(fixture: &Fixture<'_>) -> Result<String, String>
         },
     )
     .map_err(|diagnostics| diagnostics_to_sorted_string(&source, &diagnostics))?;
-    let feature = Feature::ExecutableDocument(ast.to_owned());
-    let span = Span::from_usize(cursor_position, cursor_position);
 
-    let rename_request = create_rename_request(feature, Location::new(source_location, span))
-        .map_err(|_| "Rename failed")?;
+    // The cursor is either in the document or in the schema extensions
+    let (feature, location) = match document.find('|') {
+        Some(cursor_position) => (
+            Feature::ExecutableDocument(ast.to_owned()),
+            Location::new(
+                source_location,
+                Span::from_usize(cursor_position - 1, cursor_position - 1),
+            ),
+        ),
+        None => {
+            let extensions = extensions.unwrap();
+            let cursor_position = extensions.find('|').unwrap() - 1;
+            let schema_document =
+                parse_schema_document(&extensions.replace("|", ""), extensions_location).map_err(
+                    |diagnostics| diagnostics_to_sorted_string(extensions, &diagnostics),
+                )?;
+            (
+                Feature::SchemaDocument(schema_document),
+                Location::new(
+                    extensions_location,
+                    Span::from_usize(cursor_position, cursor_position),
+                ),
+            )
+        }
+    };
+
+    let rename_request =
+        create_rename_request(feature, location, &schema).map_err(rename_error_to_string)?;
 
-    let schema = get_test_schema();
     let ir = build(&schema, &ast.definitions)
         .map_err(|diagnostics| diagnostics_to_sorted_string(&source, &diagnostics))?;
     let program = Program::from_definitions(Arc::clone(&schema), ir);
@@ -52,16 +107,164 @@ pub async fn transform_fixture(fixture: &Fixture<'_>) -> Result<String, String>
     let locations_to_rename = get_locations_for_rename(rename_request, &program)
         .map_err(|_| "Could not get locations for planned text changes")?;
 
-    Ok(rename_locations(locations_to_rename, &source))
+    let document_output = rename_locations(&locations_to_rename, source_location, &source);
+    Ok(match extensions {
+        Some(extensions) => format!(
+            "{document_output}{EXTENSIONS_SEPARATOR}{}",
+            rename_locations(
+                &locations_to_rename,
+                extensions_location,
+                &extensions.replace("|", "")
+            )
+        ),
+        None => document_output,
+    })
 }
 
-fn rename_locations(locations: Vec<Location>, source: &str) -> String {
-    let renamed_key = "RENAMED";
-    let renamed_key_length = renamed_key.len() as i32;
+/// Renames Relay Resolver fields of a JavaScript file, which is also the
+/// module of the resolvers, from a docblock or a selection.
+fn transform_docblock_fixture(fixture: &Fixture<'_>) -> Result<String, String> {
+    let cursor_position = fixture.content.find('|').unwrap();
+    let source = fixture.content.replace("|", "");
+    let features = extract_graphql::extract(&source);
+    let feature_positions = features
+        .iter()
+        .map(|feature| source.find(feature.text_source().text.as_str()).unwrap())
+        .collect::<Vec<_>>();
+
+    let mut schema = get_test_schema_with_extensions("");
+    let mut definitions = vec![];
+    let mut cursor_feature = None;
+    for (index, feature) in features.iter().enumerate() {
+        let text = &feature.text_source().text;
+        let source_location = SourceLocationKey::embedded(fixture.file_name, index);
+        let feature = match feature {
+            JavaScriptSourceFeature::Docblock(_) => {
+                let docblock_ir = parse_resolver_docblock(text, source_location)?;
+                let schema_document = docblock_ir
+                    .clone()
+                    .to_graphql_schema_ast(ProjectName::default(), &schema, &Default::default())
+                    .map_err(|diagnostics| diagnostics_to_sorted_string(text, &diagnostics))?;
+                for definition in schema_document.definitions {
+                    extend_schema_with_resolver_type_system_definition(
+                        definition,
+                        Arc::get_mut(&mut schema).unwrap(),
+                        schema_document.location,
+                    )
+                    .map_err(|diagnostics| diagnostics_to_sorted_string(text, &diagnostics))?;
+                }
+                Feature::DocblockIr(docblock_ir)
+            }
+            JavaScriptSourceFeature::GraphQL(_) => {
+                let document = parse_executable(text, source_location)
+                    .map_err(|diagnostics| diagnostics_to_sorted_string(text, &diagnostics))?;
+                definitions.extend(document.definitions.iter().cloned());
+                Feature::ExecutableDocument(document)
+            }
+        };
+
+        let feature_position = feature_positions[index];
+        if (feature_position..=feature_position + text.len()).contains(&cursor_position) {
+            let cursor_position = cursor_position - feature_position;
+            cursor_feature = Some((
+                feature,
+                Location::new(
+                    source_location,
+                    Span::from_usize(cursor_position, cursor_position),
+                ),
+            ));
+        }
+    }
+    let (feature, location) =
+        cursor_feature.ok_or("Expected the cursor in a docblock or a selection")?;
+
+    let rename_request =
+        create_rename_request(feature, location, &schema).map_err(rename_error_to_string)?;
+
+    let ir = build(&schema, &definitions)
+        .map_err(|diagnostics| diagnostics_to_sorted_string(&source, &diagnostics))?;
+    let program = Program::from_definitions(Arc::clone(&schema), ir);
+
+    let resolver_exports = get_resolver_exports_for_rename(&rename_request, &program);
+    let locations_to_rename = get_locations_for_rename(rename_request, &program)
+        .map_err(|_| "Could not get locations for planned text changes")?;
+
+    let spans = locations_to_rename
+        .iter()
+        .map(|location| match location.source_location() {
+            SourceLocationKey::Embedded { index, .. } => {
+                let feature_position = feature_positions[index as usize] as u32;
+                Span::new(
+                    location.span().start + feature_position,
+                    location.span().end + feature_position,
+                )
+            }
+            _ => location.span(),
+        })
+        .chain(resolver_exports.iter().filter_map(|resolver_export| {
+            assert_eq!(resolver_export.import_path.lookup(), fixture.file_name);
+            resolver_export
+                .find_declaration(&source)
+                .map(|location| location.span())
+        }))
+        .collect::<Vec<_>>();
 
+    Ok(rename_spans(spans, &source))
+}
+
+fn parse_resolver_docblock(
+    text: &str,
+    source_location: SourceLocationKey,
+) -> Result<DocblockIr, String> {
+    parse_docblock(text, source_location)
+        .and_then(|ast| {
+            parse_docblock_ast(
+                &ProjectName::default(),
+                &ast,
+                None,
+                &ParseOptions {
+                    enable_interface_output_type: &FeatureFlag::Disabled,
+                    allow_resolver_non_nullable_return_type: &FeatureFlag::Disabled,
+                    allow_legacy_relay_resolver_tag: &FeatureFlag::Enabled,
+                },
+            )
+        })
+        .map_err(|diagnostics| {
+            diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.message().to_string())
+                .join("\n")
+        })?
+        .ok_or_else(|| "Expected a resolver docblock".to_string())
+}
+
+fn rename_error_to_string(error: LSPRuntimeError) -> String {
+    match error {
+        LSPRuntimeError::ExpectedError => "Rename failed".to_string(),
+        LSPRuntimeError::UnexpectedError(message) => message,
+    }
+}
+
+fn rename_locations(
+    locations: &[Location],
+    source_location: SourceLocationKey,
+    source: &str,
+) -> String {
     let spans = locations
         .iter()
+        .filter(|location| location.source_location() == source_location)
         .map(|location| location.span())
+        .collect::<Vec<_>>();
+
+    rename_spans(spans, source)
+}
+
+fn rename_spans(spans: Vec<Span>, source: &str) -> String {
+    let renamed_key = "RENAMED";
+    let renamed_key_length = renamed_key.len() as i32;
+
+    let spans = spans
+        .into_iter()
         .sorted_by_key(|span| span.start)
         .collect::<Vec<_>>();
 
@@ -0,0 +1,12 @@
+/**
+ * @RelayResolver User.best_fr|iend_name: String
+ */
+export function best_friend_name(): string {
+  return 'Alice';
+}
+
+graphql`
+  fragment BestFriendNameFragment on User {
+    best_friend_name
+  }
+`;
@@ -0,0 +1,12 @@
+/**
+ * @relayField User.best_fr|iend_name: String
+ */
+export async function best_friend_name(): Promise<string> {
+  return 'Alice';
+}
+
+graphql`
+  fragment BestFriendNameFragment on User {
+    best_friend_name
+  }
+`;
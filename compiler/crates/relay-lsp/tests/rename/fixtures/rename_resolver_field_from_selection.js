@@ -0,0 +1,10 @@
+/**
+ * @RelayResolver User.best_friend_name: String
+ */
+export const best_friend_name = (): string => 'Alice';
+
+graphql`
+  fragment BestFriendNameFragment on User {
+    best_fr|iend_name
+  }
+`;
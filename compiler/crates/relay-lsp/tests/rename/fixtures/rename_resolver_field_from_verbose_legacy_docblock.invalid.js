@@ -0,0 +1,10 @@
+// expected-to-throw
+
+/**
+ * @RelayResolver
+ * @onType User
+ * @fieldName best_fr|iend_name
+ */
+export function best_friend_name(): string {
+  return 'Alice';
+}
@@ -4,7 +4,7 @@
  * This source code is licensed under the MIT license found in the
  * LICENSE file in the root directory of this source tree.
  *
- * @generated SignedSource<<5b89214376a83bb1303b10019c567b47>>
+ * @generated SignedSource<<b39944d264f4cf3de43b756e9959b37a>>
  */
 
 mod rename;
@@ -12,6 +12,27 @@ mod rename;
 use rename::transform_fixture;
 use fixture_tests::test_fixture;
 
+#[tokio::test]
+async fn rename_client_extension_field_from_definition() {
+    let input = include_str!("rename/fixtures/rename_client_extension_field_from_definition.graphql");
+    let expected = include_str!("rename/fixtures/rename_client_extension_field_from_definition.expected");
+    test_fixture(transform_fixture, file!(), "rename_client_extension_field_from_definition.graphql", "rename/fixtures/rename_client_extension_field_from_definition.expected", input, expected).await;
+}
+
+#[tokio::test]
+async fn rename_client_extension_field_from_selection() {
+    let input = include_str!("rename/fixtures/rename_client_extension_field_from_selection.graphql");
+    let expected = include_str!("rename/fixtures/rename_client_extension_field_from_selection.expected");
+    test_fixture(transform_fixture, file!(), "rename_client_extension_field_from_selection.graphql", "rename/fixtures/rename_client_extension_field_from_selection.expected", input, expected).await;
+}
+
+#[tokio::test]
+async fn rename_client_extension_field_of_interface() {
+    let input = include_str!("rename/fixtures/rename_client_extension_field_of_interface.graphql");
+    let expected = include_str!("rename/fixtures/rename_client_extension_field_of_interface.expected");
+    test_fixture(transform_fixture, file!(), "rename_client_extension_field_of_interface.graphql", "rename/fixtures/rename_client_extension_field_of_interface.expected", input, expected).await;
+}
+
 #[tokio::test]
 async fn rename_fragment_argument_from_arguments_directive_on_fragment_spread_within() {
     let input = include_str!("rename/fixtures/rename_fragment_argument_from_arguments_directive_on_fragment_spread_within.graphql");
@@ -96,6 +117,41 @@ async fn rename_operation() {
     test_fixture(transform_fixture, file!(), "rename_operation.graphql", "rename/fixtures/rename_operation.expected", input, expected).await;
 }
 
+#[tokio::test]
+async fn rename_resolver_field_from_legacy_docblock() {
+    let input = include_str!("rename/fixtures/rename_resolver_field_from_legacy_docblock.js");
+    let expected = include_str!("rename/fixtures/rename_resolver_field_from_legacy_docblock.expected");
+    test_fixture(transform_fixture, file!(), "rename_resolver_field_from_legacy_docblock.js", "rename/fixtures/rename_resolver_field_from_legacy_docblock.expected", input, expected).await;
+}
+
+#[tokio::test]
+async fn rename_resolver_field_from_relay_field_docblock() {
+    let input = include_str!("rename/fixtures/rename_resolver_field_from_relay_field_docblock.js");
+    let expected = include_str!("rename/fixtures/rename_resolver_field_from_relay_field_docblock.expected");
+    test_fixture(transform_fixture, file!(), "rename_resolver_field_from_relay_field_docblock.js", "rename/fixtures/rename_resolver_field_from_relay_field_docblock.expected", input, expected).await;
+}
+
+#[tokio::test]
+async fn rename_resolver_field_from_selection() {
+    let input = include_str!("rename/fixtures/rename_resolver_field_from_selection.js");
+    let expected = include_str!("rename/fixtures/rename_resolver_field_from_selection.expected");
+    test_fixture(transform_fixture, file!(), "rename_resolver_field_from_selection.js", "rename/fixtures/rename_resolver_field_from_selection.expected", input, expected).await;
+}
+
+#[tokio::test]
+async fn rename_resolver_field_from_verbose_legacy_docblock_invalid() {
+    let input = include_str!("rename/fixtures/rename_resolver_field_from_verbose_legacy_docblock.invalid.js");
+    let expected = include_str!("rename/fixtures/rename_resolver_field_from_verbose_legacy_docblock.invalid.expected");
+    test_fixture(transform_fixture, file!(), "rename_resolver_field_from_verbose_legacy_docblock.invalid.js", "rename/fixtures/rename_resolver_field_from_verbose_legacy_docblock.invalid.expected", input, expected).await;
+}
+
+#[tokio::test]
+async fn rename_server_field_invalid() {
+    let input = include_str!("rename/fixtures/rename_server_field.invalid.graphql");
+    let expected = include_str!("rename/fixtures/rename_server_field.invalid.expected");
+    test_fixture(transform_fixture, file!(), "rename_server_field.invalid.graphql", "rename/fixtures/rename_server_field.invalid.expected", input, expected).await;
+}
+
 #[tokio::test]
 async fn rename_variable_from_definition() {
     let input = include_str!("rename/fixtures/rename_variable_from_definition.graphql");
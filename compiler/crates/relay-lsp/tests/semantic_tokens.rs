@@ -0,0 +1,58 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use common::SourceLocationKey;
+use common::TextSource;
+use fixture_tests::Fixture;
+use graphql_syntax::parse_executable;
+use graphql_test_helpers::diagnostics_to_sorted_string;
+use relay_lsp::semantic_tokens::get_document_semantic_tokens;
+use relay_lsp::semantic_tokens::token_modifiers;
+use relay_test_schema::get_test_schema;
+use relay_test_schema::get_test_schema_with_extensions;
+
+pub async fn transform_fixture(fixture: &Fixture<'_>) -> Result<String, String> {
+    let parts: Vec<_> = fixture.content.split("%extension%").collect();
+    let (schema, source) = if let [document, extension] = parts.as_slice() {
+        (get_test_schema_with_extensions(extension), *document)
+    } else {
+        (get_test_schema(), fixture.content)
+    };
+    let source_location = SourceLocationKey::standalone(fixture.file_name);
+    let document = parse_executable(source, source_location)
+        .map_err(|diagnostics| diagnostics_to_sorted_string(source, &diagnostics))?;
+    let text_source = TextSource::from_whole_document(source);
+
+    Ok(get_document_semantic_tokens(&document, Some(&schema))
+        .into_iter()
+        .map(|token| {
+            let start = text_source.to_span_range(token.span).start;
+            let modifiers = [
+                (token_modifiers::DECLARATION, "declaration"),
+                (token_modifiers::DEPRECATED, "deprecated"),
+                (token_modifiers::CLIENT_EXTENSION, "clientExtension"),
+                (token_modifiers::RESOLVER, "resolver"),
+            ]
+            .into_iter()
+            .filter(|(flag, _)| token.modifiers & flag != 0)
+            .map(|(_, name)| format!(" +{name}"))
+            .collect::<String>();
+            format!(
+                "{}:{} {} {:?}{}",
+                start.line,
+                start.character,
+                {
+                    let (start, end) = token.span.as_usize();
+                    &source[start..end]
+                },
+                token.token_type,
+                modifiers
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
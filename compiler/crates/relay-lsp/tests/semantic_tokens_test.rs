@@ -0,0 +1,27 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ *
+ * @generated SignedSource<<aa6be12bb7753dcba7ad2c66b883f112>>
+ */
+
+mod semantic_tokens;
+
+use semantic_tokens::transform_fixture;
+use fixture_tests::test_fixture;
+
+#[tokio::test]
+async fn fragment_with_client_extensions() {
+    let input = include_str!("semantic_tokens/fixtures/fragment_with_client_extensions.graphql");
+    let expected = include_str!("semantic_tokens/fixtures/fragment_with_client_extensions.expected");
+    test_fixture(transform_fixture, file!(), "fragment_with_client_extensions.graphql", "semantic_tokens/fixtures/fragment_with_client_extensions.expected", input, expected).await;
+}
+
+#[tokio::test]
+async fn query_with_variables() {
+    let input = include_str!("semantic_tokens/fixtures/query_with_variables.graphql");
+    let expected = include_str!("semantic_tokens/fixtures/query_with_variables.expected");
+    test_fixture(transform_fixture, file!(), "query_with_variables.graphql", "semantic_tokens/fixtures/query_with_variables.expected", input, expected).await;
+}
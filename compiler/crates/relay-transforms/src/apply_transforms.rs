@@ -662,7 +662,10 @@ fn apply_operation_text_transforms(
     Ok(Arc::new(program))
 }
 
-fn apply_typegen_transforms(
+/// Applies the transforms of the program that types are generated from. Unlike
+/// the other outputs, it is built from the source program directly, so it can
+/// be computed without the rest of the pipeline.
+pub fn apply_typegen_transforms(
     project_config: &ProjectConfig,
     program: Arc<Program>,
     base_fragment_names: Arc<FragmentDefinitionNameSet>,
@@ -341,6 +341,29 @@ pub enum ValidationMessage {
         "A magic fragment returning interface `{interface_name}` that mixes inline (weak or non-Node value) implementors with refetchable server-object (Node) implementors is not yet supported. The inline arm reads in place while the server arm needs a `node(id:)` refetch, which requires per-`__typename` dispatch. Use an all-inline or all-server interface for now."
     )]
     MagicFragmentMixedInlineAndRefetchableUnsupported { interface_name: StringKey },
+
+    #[error(
+        "The plural fragment `{fragment_name}` is spread on a list of {list_size} items, which is more than the {max_list_size} items allowed by the `pluralFragmentOnLargeList` lint rule. Consider paginating the list, or fetching fewer items."
+    )]
+    LintPluralFragmentOnLargeList {
+        fragment_name: FragmentDefinitionName,
+        list_size: i64,
+        max_list_size: i64,
+    },
+
+    #[error(
+        "The query `{query_name}` is not `@preloadable`, which is required in this directory by the `queryWithoutPreloadable` lint rule."
+    )]
+    LintQueryWithoutPreloadable { query_name: StringKey },
+
+    #[error(
+        "The fragment `{fragment_name}` is spread {spread_count} times, which is more than the {max_spreads} spreads allowed by the `fragmentSpreadCount` lint rule."
+    )]
+    LintFragmentSpreadTooOften {
+        fragment_name: FragmentDefinitionName,
+        spread_count: usize,
+        max_spreads: usize,
+    },
 }
 
 #[derive(
@@ -94,6 +94,7 @@ pub use apply_fragment_arguments::NoInlineFragmentSpreadMetadata;
 pub use apply_fragment_arguments::apply_fragment_arguments;
 pub use apply_transforms::Programs;
 pub use apply_transforms::apply_transforms;
+pub use apply_transforms::apply_typegen_transforms;
 pub use assignable_fragment_spread::ASSIGNABLE_DIRECTIVE;
 pub use assignable_fragment_spread::ASSIGNABLE_DIRECTIVE_FOR_TYPEGEN;
 pub use assignable_fragment_spread::TypeConditionInfo;
@@ -135,6 +136,8 @@ pub use defer_stream::StreamDirective;
 pub use defer_stream::transform_defer_stream;
 pub use directive_finder::DirectiveFinder;
 pub use flatten::flatten;
+pub use fragment_alias_directive::FRAGMENT_ALIAS_DIRECTIVE_NAME;
+pub use fragment_alias_directive::FRAGMENT_DANGEROUSLY_UNALIAS_DIRECTIVE_NAME;
 pub use fragment_alias_directive::FragmentAliasMetadata;
 pub use fragment_alias_directive::fragment_alias_directive;
 pub use fragment_alias_directive::remove_aliased_inline_fragments;
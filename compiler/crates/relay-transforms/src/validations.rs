@@ -17,6 +17,7 @@ mod validate_connections;
 mod validate_fragment_alias_conflict;
 mod validate_global_variable_names;
 mod validate_global_variables;
+mod validate_lint_rules;
 mod validate_module_names;
 mod validate_no_double_underscore_alias;
 mod validate_no_inline_with_raw_response_type;
@@ -42,6 +43,8 @@ pub use validate_connections::validate_connections;
 pub use validate_fragment_alias_conflict::validate_fragment_alias_conflict;
 pub use validate_global_variable_names::validate_global_variable_names;
 pub use validate_global_variables::validate_global_variables;
+pub use validate_lint_rules::FragmentSpreadLocations;
+pub use validate_lint_rules::validate_lint_rules;
 pub use validate_module_names::extract_module_name;
 pub use validate_module_names::validate_module_names;
 pub use validate_no_double_underscore_alias::validate_no_double_underscore_alias;
@@ -0,0 +1,258 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use common::ArgumentName;
+use common::Diagnostic;
+use common::DiagnosticDisplay;
+use common::DiagnosticsResult;
+use common::Location;
+use common::NamedItem;
+use graphql_ir::ConstantValue;
+use graphql_ir::ExecutableDefinition;
+use graphql_ir::FragmentDefinitionName;
+use graphql_ir::FragmentDefinitionNameMap;
+use graphql_ir::FragmentSpread;
+use graphql_ir::LinkedField;
+use graphql_ir::Program;
+use graphql_ir::Value;
+use graphql_ir::Visitor;
+use intern::string_key::Intern;
+use relay_config::DiagnosticLevel;
+use relay_config::FragmentSpreadCountRuleConfig;
+use relay_config::LintRulesConfig;
+use relay_config::PluralFragmentOnLargeListRuleConfig;
+use relay_config::QueryWithoutPreloadableRuleConfig;
+
+use crate::ValidationMessage;
+use crate::is_operation_preloadable;
+use crate::relay_directive::PLURAL_ARG_NAME;
+use crate::relay_directive::RELAY_DIRECTIVE_NAME;
+
+static FIRST_ARG_NAME: LazyLock<ArgumentName> = LazyLock::new(|| ArgumentName("first".intern()));
+static LAST_ARG_NAME: LazyLock<ArgumentName> = LazyLock::new(|| ArgumentName("last".intern()));
+
+/// Runs the lint rules that are configured for the project. The diagnostics
+/// have the severity configured for their rule.
+pub fn validate_lint_rules(
+    program: &Program,
+    lint_rules: &LintRulesConfig,
+    fragment_spread_locations: &FragmentSpreadLocations,
+) -> DiagnosticsResult<()> {
+    let mut diagnostics = vec![];
+    if let Some(config) = &lint_rules.plural_fragment_on_large_list {
+        diagnostics.extend(validate_plural_fragment_on_large_list(program, config));
+    }
+    if let Some(config) = &lint_rules.query_without_preloadable {
+        diagnostics.extend(validate_query_without_preloadable(program, config));
+    }
+    if let Some(config) = &lint_rules.fragment_spread_count {
+        diagnostics.extend(validate_fragment_spread_count(
+            program,
+            config,
+            fragment_spread_locations,
+        ));
+    }
+
+    if diagnostics.is_empty() {
+        Ok(())
+    } else {
+        Err(diagnostics)
+    }
+}
+
+fn diagnostic_with_level<T: 'static + DiagnosticDisplay>(
+    level: DiagnosticLevel,
+    message: T,
+    location: Location,
+) -> Diagnostic {
+    match level {
+        DiagnosticLevel::Error => Diagnostic::error(message, location),
+        DiagnosticLevel::Warning => Diagnostic::warning(message, location, vec![]),
+        DiagnosticLevel::Info => Diagnostic::info(message, location, vec![]),
+        DiagnosticLevel::Hint => Diagnostic::hint(message, location, vec![]),
+    }
+}
+
+fn validate_plural_fragment_on_large_list(
+    program: &Program,
+    config: &PluralFragmentOnLargeListRuleConfig,
+) -> Vec<Diagnostic> {
+    let mut validator = PluralFragmentOnLargeList {
+        program,
+        config,
+        list_sizes: vec![],
+        diagnostics: vec![],
+    };
+    validator.visit_program(program);
+    validator.diagnostics
+}
+
+struct PluralFragmentOnLargeList<'a> {
+    program: &'a Program,
+    config: &'a PluralFragmentOnLargeListRuleConfig,
+    /// The literal `first` or `last` argument of the enclosing linked fields,
+    /// which limits the size of the lists selected within them.
+    list_sizes: Vec<Option<i64>>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl PluralFragmentOnLargeList<'_> {
+    fn is_plural_fragment(&self, name: FragmentDefinitionName) -> bool {
+        self.program.fragment(name).is_some_and(|fragment| {
+            fragment
+                .directives
+                .named(*RELAY_DIRECTIVE_NAME)
+                .and_then(|directive| directive.arguments.named(*PLURAL_ARG_NAME))
+                .is_some_and(|argument| {
+                    matches!(
+                        argument.value.item,
+                        Value::Constant(ConstantValue::Boolean(true))
+                    )
+                })
+        })
+    }
+}
+
+impl Visitor for PluralFragmentOnLargeList<'_> {
+    const NAME: &'static str = "PluralFragmentOnLargeList";
+    const VISIT_ARGUMENTS: bool = false;
+    const VISIT_DIRECTIVES: bool = false;
+
+    fn visit_linked_field(&mut self, field: &LinkedField) {
+        let list_size = [*FIRST_ARG_NAME, *LAST_ARG_NAME]
+            .into_iter()
+            .filter_map(|name| field.arguments.named(name))
+            .find_map(|argument| match argument.value.item {
+                Value::Constant(ConstantValue::Int(size)) => Some(size),
+                _ => None,
+            });
+        self.list_sizes.push(list_size);
+        self.default_visit_linked_field(field);
+        self.list_sizes.pop();
+    }
+
+    fn visit_fragment_spread(&mut self, spread: &FragmentSpread) {
+        if let Some(list_size) = self.list_sizes.iter().rev().flatten().next()
+            && *list_size > self.config.max_list_size
+            && self.is_plural_fragment(spread.fragment.item)
+        {
+            self.diagnostics.push(diagnostic_with_level(
+                self.config.severity,
+                ValidationMessage::LintPluralFragmentOnLargeList {
+                    fragment_name: spread.fragment.item,
+                    list_size: *list_size,
+                    max_list_size: self.config.max_list_size,
+                },
+                spread.fragment.location,
+            ));
+        }
+    }
+}
+
+fn validate_query_without_preloadable(
+    program: &Program,
+    config: &QueryWithoutPreloadableRuleConfig,
+) -> Vec<Diagnostic> {
+    program
+        .operations()
+        .filter(|operation| {
+            operation.is_query()
+                && !is_operation_preloadable(operation)
+                && config.paths.iter().any(|path| {
+                    Path::new(operation.name.location.source_location().path()).starts_with(path)
+                })
+        })
+        .map(|operation| {
+            diagnostic_with_level(
+                config.severity,
+                ValidationMessage::LintQueryWithoutPreloadable {
+                    query_name: operation.name.item.0,
+                },
+                operation.name.location,
+            )
+        })
+        .collect()
+}
+
+/// Reports the fragments of the program that are spread too often in the
+/// project, whose spreads may be in definitions outside of the program.
+fn validate_fragment_spread_count(
+    program: &Program,
+    config: &FragmentSpreadCountRuleConfig,
+    fragment_spread_locations: &FragmentSpreadLocations,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    for (name, locations) in &fragment_spread_locations.0 {
+        if locations.len() <= config.max_spreads {
+            continue;
+        }
+        let Some(fragment) = program.fragment(*name) else {
+            continue;
+        };
+        let mut diagnostic = diagnostic_with_level(
+            config.severity,
+            ValidationMessage::LintFragmentSpreadTooOften {
+                fragment_name: *name,
+                spread_count: locations.len(),
+                max_spreads: config.max_spreads,
+            },
+            fragment.name.location,
+        );
+        // The order of the definitions depends on the build, the order of
+        // the annotations should not
+        let mut locations = locations.clone();
+        locations
+            .sort_by_key(|location| (location.source_location().path(), location.span().start));
+        for location in locations {
+            diagnostic = diagnostic.annotate("spread here", location);
+        }
+        diagnostics.push(diagnostic);
+    }
+    diagnostics
+}
+
+/// The locations of the spreads of each fragment of a project. Incremental
+/// builds only build the definitions affected by a change, so the spreads are
+/// collected from all the definitions of the project instead of the program
+/// being validated.
+#[derive(Debug, Default)]
+pub struct FragmentSpreadLocations(FragmentDefinitionNameMap<Vec<Location>>);
+
+impl FragmentSpreadLocations {
+    pub fn from_definitions(definitions: &[ExecutableDefinition]) -> Self {
+        let mut collector = FragmentSpreadCollector {
+            spreads: Default::default(),
+        };
+        for definition in definitions {
+            match definition {
+                ExecutableDefinition::Operation(operation) => collector.visit_operation(operation),
+                ExecutableDefinition::Fragment(fragment) => collector.visit_fragment(fragment),
+            }
+        }
+        Self(collector.spreads)
+    }
+}
+
+struct FragmentSpreadCollector {
+    spreads: FragmentDefinitionNameMap<Vec<Location>>,
+}
+
+impl Visitor for FragmentSpreadCollector {
+    const NAME: &'static str = "FragmentSpreadCollector";
+    const VISIT_ARGUMENTS: bool = false;
+    const VISIT_DIRECTIVES: bool = false;
+
+    fn visit_fragment_spread(&mut self, spread: &FragmentSpread) {
+        self.spreads
+            .entry(spread.fragment.item)
+            .or_default()
+            .push(spread.fragment.location);
+    }
+}
@@ -0,0 +1,61 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use common::SourceLocationKey;
+use fixture_tests::Fixture;
+use graphql_ir::Program;
+use graphql_ir::build;
+use graphql_syntax::parse_executable;
+use graphql_test_helpers::diagnostics_to_sorted_string;
+use relay_config::DiagnosticLevel;
+use relay_config::FragmentSpreadCountRuleConfig;
+use relay_config::LintRulesConfig;
+use relay_config::PluralFragmentOnLargeListRuleConfig;
+use relay_config::QueryWithoutPreloadableRuleConfig;
+use relay_test_schema::TEST_SCHEMA;
+use relay_transforms::FragmentSpreadLocations;
+use relay_transforms::validate_lint_rules;
+
+pub async fn transform_fixture(fixture: &Fixture<'_>) -> Result<String, String> {
+    // Fixtures with `preloadable-path` in their name are in the directory in
+    // which queries must be `@preloadable`.
+    let directory = if fixture.file_name.contains("preloadable-path") {
+        "preloadable"
+    } else {
+        "other"
+    };
+    let source_location =
+        SourceLocationKey::standalone(&format!("{}/{}", directory, fixture.file_name));
+
+    let ast = parse_executable(fixture.content, source_location).unwrap();
+    let ir = build(&TEST_SCHEMA, &ast.definitions)
+        .map_err(|diagnostics| diagnostics_to_sorted_string(fixture.content, &diagnostics))?;
+    let fragment_spread_locations = FragmentSpreadLocations::from_definitions(&ir);
+    let program = Program::from_definitions(Arc::clone(&TEST_SCHEMA), ir);
+
+    let lint_rules = LintRulesConfig {
+        plural_fragment_on_large_list: Some(PluralFragmentOnLargeListRuleConfig {
+            severity: DiagnosticLevel::Warning,
+            max_list_size: 10,
+        }),
+        query_without_preloadable: Some(QueryWithoutPreloadableRuleConfig {
+            severity: DiagnosticLevel::Error,
+            paths: vec![PathBuf::from("preloadable")],
+        }),
+        fragment_spread_count: Some(FragmentSpreadCountRuleConfig {
+            severity: DiagnosticLevel::Warning,
+            max_spreads: 2,
+        }),
+    };
+    validate_lint_rules(&program, &lint_rules, &fragment_spread_locations)
+        .map_err(|diagnostics| diagnostics_to_sorted_string(fixture.content, &diagnostics))?;
+
+    Ok("OK".to_owned())
+}
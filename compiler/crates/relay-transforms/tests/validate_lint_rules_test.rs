@@ -0,0 +1,48 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ *
+ * @generated SignedSource<<d689611c061a8d663c6db1401ea5c956>>
+ */
+
+mod validate_lint_rules;
+
+use validate_lint_rules::transform_fixture;
+use fixture_tests::test_fixture;
+
+#[tokio::test]
+async fn fragment_spread_count_invalid() {
+    let input = include_str!("validate_lint_rules/fixtures/fragment-spread-count.invalid.graphql");
+    let expected = include_str!("validate_lint_rules/fixtures/fragment-spread-count.invalid.expected");
+    test_fixture(transform_fixture, file!(), "fragment-spread-count.invalid.graphql", "validate_lint_rules/fixtures/fragment-spread-count.invalid.expected", input, expected).await;
+}
+
+#[tokio::test]
+async fn plural_fragment_on_large_list_invalid() {
+    let input = include_str!("validate_lint_rules/fixtures/plural-fragment-on-large-list.invalid.graphql");
+    let expected = include_str!("validate_lint_rules/fixtures/plural-fragment-on-large-list.invalid.expected");
+    test_fixture(transform_fixture, file!(), "plural-fragment-on-large-list.invalid.graphql", "validate_lint_rules/fixtures/plural-fragment-on-large-list.invalid.expected", input, expected).await;
+}
+
+#[tokio::test]
+async fn plural_fragment_on_small_list() {
+    let input = include_str!("validate_lint_rules/fixtures/plural-fragment-on-small-list.graphql");
+    let expected = include_str!("validate_lint_rules/fixtures/plural-fragment-on-small-list.expected");
+    test_fixture(transform_fixture, file!(), "plural-fragment-on-small-list.graphql", "validate_lint_rules/fixtures/plural-fragment-on-small-list.expected", input, expected).await;
+}
+
+#[tokio::test]
+async fn query_in_other_path() {
+    let input = include_str!("validate_lint_rules/fixtures/query-in-other-path.graphql");
+    let expected = include_str!("validate_lint_rules/fixtures/query-in-other-path.expected");
+    test_fixture(transform_fixture, file!(), "query-in-other-path.graphql", "validate_lint_rules/fixtures/query-in-other-path.expected", input, expected).await;
+}
+
+#[tokio::test]
+async fn query_in_preloadable_path_invalid() {
+    let input = include_str!("validate_lint_rules/fixtures/query-in-preloadable-path.invalid.graphql");
+    let expected = include_str!("validate_lint_rules/fixtures/query-in-preloadable-path.invalid.expected");
+    test_fixture(transform_fixture, file!(), "query-in-preloadable-path.invalid.graphql", "validate_lint_rules/fixtures/query-in-preloadable-path.invalid.expected", input, expected).await;
+}
@@ -35,6 +35,7 @@ use relay_transforms::UPDATABLE_DIRECTIVE;
 use schema::SDLSchema;
 pub use typegen_state::FragmentLocations;
 pub use write::has_raw_response_type_directive;
+use write::write_fragment_selections_type;
 use write::write_fragment_type_exports_section;
 use write::write_operation_type_exports_section;
 use write::write_split_operation_type_exports_section;
@@ -163,6 +164,31 @@ fn generate_fragment_type_exports_section_impl(
     writer.into_string()
 }
 
+/// Generates the type of the selections of a fragment, as an object type with
+/// a property for each selection, the way the parent of a spread of the
+/// fragment would see them.
+pub fn generate_fragment_selections_type(
+    fragment_definition: &FragmentDefinition,
+    schema: &SDLSchema,
+    project_config: &ProjectConfig,
+    fragment_locations: &FragmentLocations,
+) -> String {
+    let typegen_context = TypegenContext::new(
+        schema,
+        project_config,
+        false,
+        fragment_definition.name.map(|x| x.0),
+        fragment_locations,
+        TypegenOptions {
+            no_optional_fields_in_raw_response_type: false,
+            is_extra_artifact_branch_module: false,
+        },
+    );
+    let mut writer = new_writer_from_config(&project_config.typegen_config);
+    write_fragment_selections_type(&typegen_context, fragment_definition, &mut writer).unwrap();
+    writer.into_string()
+}
+
 pub fn generate_named_validator_export(
     fragment_definition: &FragmentDefinition,
     schema: &SDLSchema,
@@ -563,6 +563,71 @@ pub(crate) fn write_fragment_type_exports_section(
     Ok(())
 }
 
+/// Writes the type of the selections of a fragment, as they are seen by the
+/// parent of a spread of the fragment: the `$data` type without the
+/// `$fragmentType` marker and without the nullability of a bubbling
+/// `@required` field.
+pub(crate) fn write_fragment_selections_type(
+    typegen_context: &'_ TypegenContext<'_>,
+    fragment_definition: &FragmentDefinition,
+    writer: &mut Box<dyn Writer>,
+) -> FmtResult {
+    let mut encountered_enums = Default::default();
+    let mut encountered_fragments = Default::default();
+    let mut imported_resolvers = Default::default();
+    let mut custom_scalars = CustomScalarsImports::default();
+    let mut input_object_types = Default::default();
+    let mut runtime_imports = RuntimeImports::default();
+    let mut custom_error_import: Option<CustomTypeImport> = None;
+    let mut imported_raw_response_types = Default::default();
+
+    let mut type_selections = visit_selections(
+        typegen_context,
+        &fragment_definition.selections,
+        &mut input_object_types,
+        &mut encountered_enums,
+        &mut imported_raw_response_types,
+        &mut encountered_fragments,
+        &mut imported_resolvers,
+        &mut custom_scalars,
+        &mut runtime_imports,
+        &mut custom_error_import,
+        None,
+        false,
+    );
+    if !fragment_definition.type_condition.is_abstract_type() {
+        let num_concrete_selections = type_selections
+            .iter()
+            .filter(|sel| sel.get_enclosing_concrete_type().is_some())
+            .count();
+        if num_concrete_selections <= 1 {
+            for selection in type_selections.iter_mut().filter(|sel| sel.is_typename()) {
+                selection.set_concrete_type(fragment_definition.type_condition);
+            }
+        }
+    }
+    let mask_status = if RelayDirective::is_unmasked_fragment_definition(fragment_definition) {
+        MaskStatus::Unmasked
+    } else {
+        MaskStatus::Masked
+    };
+    let data_type = get_data_type(
+        typegen_context,
+        &fragment_definition.type_condition,
+        type_selections.into_iter(),
+        mask_status,
+        None,
+        false,
+        false,
+        &mut encountered_enums,
+        &mut encountered_fragments,
+        &mut custom_scalars,
+        &mut runtime_imports,
+        &mut custom_error_import,
+    );
+    writer.write(&data_type)
+}
+
 fn write_import_custom_type(
     custom_type_import: Option<CustomTypeImport>,
     writer: &mut Box<dyn Writer>,
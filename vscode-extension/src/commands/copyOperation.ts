@@ -10,7 +10,12 @@ import {window, env} from 'vscode';
 import {RequestType, TextDocumentPositionParams} from 'vscode-languageclient';
 import {RelayExtensionContext} from '../context';
 
-export function handleCopyOperation(context: RelayExtensionContext): void {
+// The code lens of an operation passes its position, otherwise we use the
+// cursor of the active editor.
+export function handleCopyOperation(
+  context: RelayExtensionContext,
+  position?: TextDocumentPositionParams,
+): void {
   const {binaryVersion} = context.relayBinaryExecutionOptions;
 
   if (binaryVersion) {
@@ -30,22 +35,25 @@ export function handleCopyOperation(context: RelayExtensionContext): void {
     return;
   }
 
-  const activeEditor = window.activeTextEditor;
-
-  if (!activeEditor) {
-    return;
-  }
-
   const request = new RequestType<
     TextDocumentPositionParams,
     PrintOperationResponse,
     void
   >('relay/printOperation');
 
-  const params: TextDocumentPositionParams = {
-    textDocument: {uri: activeEditor.document.uri.toString()},
-    position: activeEditor.selection.active,
-  };
+  const activeEditor = window.activeTextEditor;
+  let params: TextDocumentPositionParams;
+
+  if (position) {
+    params = position;
+  } else if (activeEditor) {
+    params = {
+      textDocument: {uri: activeEditor.document.uri.toString()},
+      position: activeEditor.selection.active,
+    };
+  } else {
+    return;
+  }
 
   context.client.sendRequest(request, params).then(response => {
     env.clipboard.writeText(response.operationText).then(() => {
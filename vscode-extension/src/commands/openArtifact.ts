@@ -0,0 +1,28 @@
+/**
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+import {window} from 'vscode';
+import {RelayExtensionContext} from '../context';
+
+// Invoked by the artifact code lens of the language server, with the uri of
+// the generated artifact of the definition.
+export function handleOpenArtifactCommand(
+  context: RelayExtensionContext,
+  uri?: string,
+): void {
+  if (!context.client || !uri) {
+    return;
+  }
+
+  const converter = context.client.protocol2CodeConverter;
+
+  window.showTextDocument(converter.asUri(uri)).then(undefined, () => {
+    window.showWarningMessage(
+      `Unable to open the artifact ${uri}, it may not have been generated yet.`,
+    );
+  });
+}
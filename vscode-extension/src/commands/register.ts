@@ -12,6 +12,8 @@ import {handleShowOutputCommand} from './showOutput';
 import {handleStartCompilerCommand} from './startCompiler';
 import {handleStopCompilerCommand} from './stopCompiler';
 import {handleCopyOperation} from './copyOperation';
+import {handleShowReferencesCommand} from './showReferences';
+import {handleOpenArtifactCommand} from './openArtifact';
 
 export function registerCommands(context: RelayExtensionContext) {
   context.extensionContext.subscriptions.push(
@@ -35,5 +37,13 @@ export function registerCommands(context: RelayExtensionContext) {
       'relay.copyOperation',
       handleCopyOperation.bind(null, context),
     ),
+    commands.registerCommand(
+      'relay.showReferences',
+      handleShowReferencesCommand.bind(null, context),
+    ),
+    commands.registerCommand(
+      'relay.openArtifact',
+      handleOpenArtifactCommand.bind(null, context),
+    ),
   );
 }